@@ -1,12 +1,46 @@
 pub use sea_orm_migration::prelude::*;
 
 mod m20220101_000001_create_table;
+mod m20261019_000002_add_execution_schedule_id;
+mod m20261019_000003_add_execution_start_at;
+mod m20261019_000004_unique_execution_workflow_id;
+mod m20261019_000005_add_execution_previous_run_ids;
+mod m20261019_000006_add_execution_status_reason;
+mod m20261019_000007_add_execution_synced_at;
+mod m20261019_000008_create_scheduler_leases;
+mod m20261019_000009_normalize_execution_status;
+mod m20261019_000010_add_execution_workflow_type;
+mod m20261019_000011_create_command_logs;
+mod m20261019_000012_create_execution_steps;
+mod m20261019_000013_add_execution_compensation;
+mod m20261019_000014_add_execution_parent_id;
+mod m20261019_000015_add_execution_build_id;
+mod m20261019_000016_add_execution_imported;
+mod m20261019_000017_create_drift_reports;
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m20220101_000001_create_table::Migration)]
+        vec![
+            Box::new(m20220101_000001_create_table::Migration),
+            Box::new(m20261019_000002_add_execution_schedule_id::Migration),
+            Box::new(m20261019_000003_add_execution_start_at::Migration),
+            Box::new(m20261019_000004_unique_execution_workflow_id::Migration),
+            Box::new(m20261019_000005_add_execution_previous_run_ids::Migration),
+            Box::new(m20261019_000006_add_execution_status_reason::Migration),
+            Box::new(m20261019_000007_add_execution_synced_at::Migration),
+            Box::new(m20261019_000008_create_scheduler_leases::Migration),
+            Box::new(m20261019_000009_normalize_execution_status::Migration),
+            Box::new(m20261019_000010_add_execution_workflow_type::Migration),
+            Box::new(m20261019_000011_create_command_logs::Migration),
+            Box::new(m20261019_000012_create_execution_steps::Migration),
+            Box::new(m20261019_000013_add_execution_compensation::Migration),
+            Box::new(m20261019_000014_add_execution_parent_id::Migration),
+            Box::new(m20261019_000015_add_execution_build_id::Migration),
+            Box::new(m20261019_000016_add_execution_imported::Migration),
+            Box::new(m20261019_000017_create_drift_reports::Migration),
+        ]
     }
 }
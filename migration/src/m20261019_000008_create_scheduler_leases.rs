@@ -0,0 +1,45 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20261019_000008_create_scheduler_leases"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SchedulerLease::Table)
+                    .col(
+                        ColumnDef::new(SchedulerLease::Name)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(SchedulerLease::Owner).string().not_null())
+                    .col(ColumnDef::new(SchedulerLease::ExpiresAt).timestamp_with_time_zone().not_null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SchedulerLease::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum SchedulerLease {
+    #[iden = "scheduler_leases"]
+    Table,
+    Name,
+    Owner,
+    ExpiresAt,
+}
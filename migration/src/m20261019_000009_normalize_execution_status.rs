@@ -0,0 +1,78 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20261019_000009_normalize_execution_status"
+    }
+}
+
+// Valeurs reconnues par ExecutionStatus
+const KNOWN_STATUSES: [&str; 11] = [
+    "PENDING",
+    "SCHEDULED",
+    "RUNNING",
+    "CONTINUED_AS_NEW",
+    "COMPLETED",
+    "FAILED",
+    "CANCELED",
+    "TERMINATED",
+    "TIMED_OUT",
+    "LOST",
+    "UNKNOWN",
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Casse et espaces
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(Execution::Table)
+                    .value(Execution::Status, Expr::cust("UPPER(TRIM(status))"))
+                    .to_owned(),
+            )
+            .await?;
+
+        // COMPLETE était la seule valeur qui ne reprenait pas le nom Temporal
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(Execution::Table)
+                    .value(Execution::Status, "COMPLETED")
+                    .and_where(Expr::col(Execution::Status).eq("COMPLETE"))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Valeurs inconnues: UNKNOWN, en conservant l'ancienne valeur dans la raison
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(Execution::Table)
+                    .value(Execution::StatusReason, Expr::cust("COALESCE(status_reason, 'Statut non reconnu: ' || status)"))
+                    .value(Execution::Status, "UNKNOWN")
+                    .and_where(Expr::col(Execution::Status).is_not_in(KNOWN_STATUSES))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    // Les valeurs d'origine (casse, espaces, COMPLETE, valeurs inconnues) ne sont pas
+    // conservées: on ne peut pas distinguer une ligne normalisée d'une ligne déjà valide
+    async fn down(&self, _manager: &SchemaManager) -> Result<(), DbErr> {
+        Err(DbErr::Migration(
+            "m20261019_000009_normalize_execution_status est irréversible: les statuts d'origine ne sont pas conservés".to_string(),
+        ))
+    }
+}
+
+#[derive(Iden)]
+enum Execution {
+    #[iden = "executions"]
+    Table,
+    Status,
+    StatusReason,
+}
@@ -0,0 +1,65 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20261019_000011_create_command_logs"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(CommandLog::Table)
+                    .col(
+                        ColumnDef::new(CommandLog::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(CommandLog::WorkflowId).string().not_null())
+                    .col(ColumnDef::new(CommandLog::RunId).string().not_null())
+                    .col(ColumnDef::new(CommandLog::ActivityId).string().not_null())
+                    .col(ColumnDef::new(CommandLog::Stream).string().not_null())
+                    .col(ColumnDef::new(CommandLog::Line).text().not_null())
+                    .col(ColumnDef::new(CommandLog::LoggedAt).timestamp_with_time_zone().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Les logs sont toujours lus par exécution
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_command_logs_workflow_id")
+                    .table(CommandLog::Table)
+                    .col(CommandLog::WorkflowId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(CommandLog::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum CommandLog {
+    #[iden = "command_logs"]
+    Table,
+    Id,
+    WorkflowId,
+    RunId,
+    ActivityId,
+    Stream,
+    Line,
+    LoggedAt,
+}
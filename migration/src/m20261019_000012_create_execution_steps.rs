@@ -0,0 +1,78 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20261019_000012_create_execution_steps"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ExecutionStep::Table)
+                    .col(
+                        ColumnDef::new(ExecutionStep::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ExecutionStep::WorkflowId).string().not_null())
+                    .col(ColumnDef::new(ExecutionStep::RunId).string().not_null())
+                    .col(ColumnDef::new(ExecutionStep::StepId).string().not_null())
+                    .col(ColumnDef::new(ExecutionStep::ActivityType).string().not_null())
+                    .col(ColumnDef::new(ExecutionStep::DependsOn).json().not_null())
+                    .col(ColumnDef::new(ExecutionStep::Status).string().not_null())
+                    .col(ColumnDef::new(ExecutionStep::Result).json().null())
+                    .col(ColumnDef::new(ExecutionStep::Error).text().null())
+                    .col(ColumnDef::new(ExecutionStep::StartedAt).timestamp_with_time_zone().null())
+                    .col(ColumnDef::new(ExecutionStep::FinishedAt).timestamp_with_time_zone().null())
+                    .col(ColumnDef::new(ExecutionStep::UpdatedAt).timestamp_with_time_zone().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Une ligne par étape et par run, mise à jour à chaque transition
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_execution_steps_run_step")
+                    .table(ExecutionStep::Table)
+                    .col(ExecutionStep::WorkflowId)
+                    .col(ExecutionStep::RunId)
+                    .col(ExecutionStep::StepId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ExecutionStep::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum ExecutionStep {
+    #[iden = "execution_steps"]
+    Table,
+    Id,
+    WorkflowId,
+    RunId,
+    StepId,
+    ActivityType,
+    DependsOn,
+    Status,
+    Result,
+    Error,
+    StartedAt,
+    FinishedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,45 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20261019_000017_create_drift_reports"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(DriftReport::Table)
+                    .col(
+                        ColumnDef::new(DriftReport::Name)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(DriftReport::Report).json().not_null())
+                    .col(ColumnDef::new(DriftReport::GeneratedAt).timestamp_with_time_zone().not_null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(DriftReport::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum DriftReport {
+    #[iden = "drift_reports"]
+    Table,
+    Name,
+    Report,
+    GeneratedAt,
+}
@@ -0,0 +1,18 @@
+use log::debug;
+use temporal_sdk::{ActContext, ActExitValue, ActivityError};
+
+use crate::{fan_out::ChildExecution, helpers::child_store::SharedChildStore};
+
+// Activité locale record_child_execution: rattache un workflow enfant démarré à l'exécution appelante
+pub async fn record_child_activity(store: SharedChildStore, ctx: ActContext, payload: serde_json::Value) -> Result<ActExitValue<()>, ActivityError> {
+    let child = serde_json::from_value::<ChildExecution>(payload)
+        .map_err(|e| ActivityError::NonRetryable(anyhow::anyhow!("Entrée invalide pour record_child_execution: {}", e)))?;
+    let execution = ctx.get_info().workflow_execution.clone().unwrap_or_default();
+
+    store
+        .record_child(&execution.workflow_id, &child)
+        .await
+        .map_err(|source| ActivityError::Retryable { source, explicit_delay: None })?;
+    debug!("Recorded child {} of workflow {}", child.workflow_id, execution.workflow_id);
+    Ok(ActExitValue::Normal(()))
+}
@@ -0,0 +1,442 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    env, fmt,
+    future::Future,
+    path::Path,
+    process::Stdio,
+    sync::Arc,
+    time::Duration,
+};
+
+use chrono::Utc;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use temporal_sdk::{ActContext, ActExitValue, ActivityError};
+use temporal_sdk_core_protos::coresdk::AsJsonPayloadExt;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, BufReader},
+    process::{Child, Command},
+    sync::mpsc,
+    time::{interval, sleep, timeout, MissedTickBehavior},
+};
+
+use crate::helpers::log_store::{LogLine, LogStore, LogStream, LogTarget};
+
+// Nombre de lignes de sortie renvoyées dans le résultat de l'activité
+pub const OUTPUT_TAIL_LINES: usize = 50;
+
+// Délai appliqué quand l'entrée n'en précise pas
+pub const DEFAULT_COMMAND_TIMEOUT: Duration = Duration::from_secs(600);
+
+// Délai maximal accepté en entrée (un jour)
+pub const MAX_COMMAND_TIMEOUT: Duration = Duration::from_secs(24 * 3600);
+
+// Heartbeat envoyé même sans sortie, pour que l'annulation parvienne à l'activité
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+
+// Délai laissé au processus entre SIGTERM et SIGKILL
+pub const KILL_GRACE: Duration = Duration::from_secs(5);
+
+// Temps laissé aux lecteurs pour vider les tubes une fois le processus terminé.
+// Seule la lecture est bornée: les lignes lues sont toutes enregistrées ensuite.
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(1);
+
+// Lignes en attente entre les lecteurs et l'enregistrement: au-delà, la commande
+// est ralentie (tube plein) plutôt que la mémoire du worker
+const LINE_BUFFER: usize = 1024;
+
+// Nombre maximal de lignes enregistrées en une écriture
+const LOG_BATCH_SIZE: usize = 256;
+
+// Longueur maximale d'une ligne enregistrée, en octets: la suite de la ligne est ignorée
+pub const MAX_LINE_BYTES: usize = 16 * 1024;
+
+// Intervalle de vérification de la fin du processus pendant KILL_GRACE
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+// Environnement de base des commandes: celui du worker n'est pas transmis
+pub const BASE_ENV: &[(&str, &str)] = &[("PATH", "/usr/local/bin:/usr/bin:/bin"), ("LANG", "C.UTF-8")];
+
+// Variables que l'entrée ne peut pas fixer: elles changent le programme réellement chargé
+const FORBIDDEN_ENV_PREFIXES: &[&str] = &["LD_", "DYLD_"];
+
+// Entrée de run_command
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CommandInput {
+    // Chemin absolu du programme, tel qu'il figure dans ALLOWED_COMMANDS
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    // Variables ajoutées à BASE_ENV (PATH, LD_* et DYLD_* sont refusées)
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    #[serde(default)]
+    pub working_dir: Option<String>,
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+impl CommandInput {
+    pub fn timeout(&self) -> Duration {
+        self.timeout_secs.map(Duration::from_secs).unwrap_or(DEFAULT_COMMAND_TIMEOUT)
+    }
+
+    // Refuser les variables qui détourneraient la résolution ou le chargement du programme
+    pub fn check_env(&self) -> Result<(), CommandError> {
+        match self.env.keys().find(|key| *key == "PATH" || FORBIDDEN_ENV_PREFIXES.iter().any(|prefix| key.starts_with(prefix))) {
+            Some(key) => Err(CommandError::ForbiddenEnv(key.clone())),
+            None => Ok(()),
+        }
+    }
+}
+
+// Ligne de sortie conservée dans le résultat
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutputLine {
+    pub stream: LogStream,
+    pub line: String,
+}
+
+// Résultat de run_command
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CommandOutput {
+    // Absent si le processus a été tué par un signal
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    // Dernières lignes de stdout et stderr, dans l'ordre de lecture
+    pub output_tail: Vec<OutputLine>,
+}
+
+impl CommandOutput {
+    pub fn succeeded(&self) -> bool {
+        !self.timed_out && self.exit_code == Some(0)
+    }
+}
+
+// Avancement envoyé en heartbeat
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CommandProgress {
+    pub lines: u64,
+    pub last_line: Option<String>,
+}
+
+// Commandes que le worker accepte de lancer, par chemin absolu
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CommandPolicy {
+    allowed: HashSet<String>,
+}
+
+impl CommandPolicy {
+    // Les chemins relatifs sont ignorés: leur résolution dépendrait du PATH du worker
+    pub fn new<I, S>(allowed: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let allowed = allowed
+            .into_iter()
+            .map(Into::into)
+            .filter(|command: &String| {
+                let absolute = Path::new(command).is_absolute();
+                if !absolute {
+                    warn!("Ignoring {} in ALLOWED_COMMANDS: not an absolute path", command);
+                }
+                absolute
+            })
+            .collect();
+        CommandPolicy { allowed }
+    }
+
+    // ALLOWED_COMMANDS: chemins absolus séparés par des virgules. Vide ou absente: aucune commande autorisée.
+    pub fn from_env() -> Self {
+        let allowed = env::var("ALLOWED_COMMANDS").unwrap_or_default();
+        Self::new(allowed.split(',').map(str::trim).filter(|command| !command.is_empty()))
+    }
+
+    // La commande doit figurer telle quelle dans la liste (pas de correspondance partielle)
+    pub fn check(&self, command: &str) -> Result<(), CommandError> {
+        if Path::new(command).is_absolute() && self.allowed.contains(command) {
+            Ok(())
+        } else {
+            Err(CommandError::NotAllowed(command.to_string()))
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum CommandError {
+    // Commande absente de ALLOWED_COMMANDS
+    NotAllowed(String),
+    // Variable d'environnement refusée (PATH, LD_*, DYLD_*)
+    ForbiddenEnv(String),
+    // Programme introuvable, répertoire invalide...
+    Spawn(std::io::Error),
+    // Activité annulée: le groupe de processus a été tué
+    Cancelled,
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::NotAllowed(command) => write!(f, "Commande non autorisée: {}", command),
+            CommandError::ForbiddenEnv(key) => write!(f, "Variable d'environnement non autorisée: {}", key),
+            CommandError::Spawn(err) => write!(f, "Impossible de lancer la commande: {}", err),
+            CommandError::Cancelled => write!(f, "Commande annulée"),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+// Lancer la commande, enregistrer sa sortie ligne à ligne et attendre sa fin.
+// `heartbeat` est appelé à chaque ligne et toutes les HEARTBEAT_INTERVAL;
+// si `cancelled` se termine, le groupe de processus est tué.
+pub async fn execute_command(
+    policy: &CommandPolicy,
+    input: &CommandInput,
+    logs: &dyn LogStore,
+    target: &LogTarget,
+    heartbeat: impl Fn(&CommandProgress),
+    cancelled: impl Future<Output = ()>,
+) -> Result<CommandOutput, CommandError> {
+    policy.check(&input.command)?;
+    input.check_env()?;
+
+    let mut command = Command::new(&input.command);
+    command
+        .args(&input.args)
+        .env_clear()
+        .envs(BASE_ENV.iter().copied())
+        .envs(&input.env)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        // Groupe dédié: les sous-processus sont tués avec la commande
+        .process_group(0)
+        .kill_on_drop(true);
+    if let Some(dir) = &input.working_dir {
+        command.current_dir(dir);
+    }
+    let mut child = command.spawn().map_err(CommandError::Spawn)?;
+    info!("🚀 Running {} {:?} (pid {:?})", input.command, input.args, child.id());
+
+    let (tx, mut rx) = mpsc::channel(LINE_BUFFER);
+    if let Some(stdout) = child.stdout.take() {
+        tokio::spawn(forward_lines(stdout, LogStream::Stdout, tx.clone()));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        tokio::spawn(forward_lines(stderr, LogStream::Stderr, tx));
+    }
+
+    let mut output = CommandOutput::default();
+    let mut tail = VecDeque::with_capacity(OUTPUT_TAIL_LINES);
+    let mut progress = CommandProgress::default();
+    let deadline = sleep(input.timeout());
+    let mut ticker = interval(HEARTBEAT_INTERVAL);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    tokio::pin!(deadline, cancelled);
+
+    let status = loop {
+        tokio::select! {
+            biased;
+            _ = &mut cancelled => {
+                warn!("🛑 {} cancelled, killing process group", input.command);
+                terminate(&mut child).await;
+                return Err(CommandError::Cancelled);
+            }
+            _ = &mut deadline => {
+                warn!("⏰ {} timed out after {:?}", input.command, input.timeout());
+                output.timed_out = true;
+                break terminate(&mut child).await;
+            }
+            Some(first) = rx.recv() => {
+                // Enregistrer d'un coup les lignes déjà arrivées
+                let mut batch = vec![first];
+                while batch.len() < LOG_BATCH_SIZE {
+                    match rx.try_recv() {
+                        Ok(line) => batch.push(line),
+                        Err(_) => break,
+                    }
+                }
+                record_lines(logs, target, &mut tail, &mut progress, batch).await;
+                heartbeat(&progress);
+            }
+            status = child.wait() => break status.ok(),
+            _ = ticker.tick() => heartbeat(&progress),
+        }
+    };
+
+    // Lire les dernières lignes encore dans les tubes, puis les enregistrer toutes
+    let mut remaining = Vec::new();
+    let _ = timeout(DRAIN_TIMEOUT, async {
+        while let Some(line) = rx.recv().await {
+            remaining.push(line);
+        }
+    })
+    .await;
+    while !remaining.is_empty() {
+        let rest = remaining.split_off(remaining.len().min(LOG_BATCH_SIZE));
+        record_lines(logs, target, &mut tail, &mut progress, remaining).await;
+        remaining = rest;
+    }
+    heartbeat(&progress);
+
+    output.exit_code = status.and_then(|status| status.code());
+    output.output_tail = tail.into();
+    Ok(output)
+}
+
+// Transmettre la sortie ligne à ligne jusqu'à la fin du tube. Les octets invalides en UTF-8
+// sont remplacés et les lignes coupées à MAX_LINE_BYTES, pour que le tube soit toujours vidé.
+async fn forward_lines(reader: impl AsyncRead + Unpin, stream: LogStream, tx: mpsc::Sender<(LogStream, String)>) {
+    let mut reader = BufReader::new(reader);
+    let mut buffer = Vec::with_capacity(MAX_LINE_BYTES);
+    loop {
+        buffer.clear();
+        match (&mut reader).take(MAX_LINE_BYTES as u64).read_until(b'\n', &mut buffer).await {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(err) => {
+                warn!("Failed to read command {}: {:?}", stream.as_str(), err);
+                break;
+            }
+        }
+        if buffer.last() == Some(&b'\n') {
+            buffer.pop();
+            if buffer.last() == Some(&b'\r') {
+                buffer.pop();
+            }
+        } else if buffer.len() == MAX_LINE_BYTES {
+            if let Err(err) = skip_line(&mut reader).await {
+                warn!("Failed to read command {}: {:?}", stream.as_str(), err);
+                break;
+            }
+        }
+        let line = String::from_utf8_lossy(&buffer).into_owned();
+        if tx.send((stream, line)).await.is_err() {
+            break;
+        }
+    }
+}
+
+// Ignorer la fin d'une ligne trop longue, jusqu'au saut de ligne ou à la fin du tube
+async fn skip_line(reader: &mut (impl AsyncBufReadExt + Unpin)) -> std::io::Result<()> {
+    loop {
+        let available = reader.fill_buf().await?;
+        if available.is_empty() {
+            return Ok(());
+        }
+        match available.iter().position(|byte| *byte == b'\n') {
+            Some(end) => {
+                reader.consume(end + 1);
+                return Ok(());
+            }
+            None => {
+                let len = available.len();
+                reader.consume(len);
+            }
+        }
+    }
+}
+
+async fn record_lines(
+    logs: &dyn LogStore,
+    target: &LogTarget,
+    tail: &mut VecDeque<OutputLine>,
+    progress: &mut CommandProgress,
+    lines: Vec<(LogStream, String)>,
+) {
+    let logged_at = Utc::now();
+    let log_lines = lines
+        .iter()
+        .map(|(stream, line)| LogLine { target: target.clone(), stream: *stream, line: line.clone(), logged_at })
+        .collect();
+    if let Err(err) = logs.append_batch(log_lines).await {
+        warn!("Failed to store command output: {:?}", err);
+    }
+    for (stream, line) in lines {
+        if tail.len() == OUTPUT_TAIL_LINES {
+            tail.pop_front();
+        }
+        tail.push_back(OutputLine { stream, line: line.clone() });
+        progress.lines += 1;
+        progress.last_line = Some(line);
+    }
+}
+
+// SIGTERM au groupe, puis SIGKILL à la fin du processus ou après KILL_GRACE.
+// Le processus n'est récolté qu'après SIGKILL: tant qu'il reste zombie, son pid
+// (et donc le groupe) ne peut pas être réattribué à un autre processus.
+async fn terminate(child: &mut Child) -> Option<std::process::ExitStatus> {
+    let Some(pid) = child.id() else {
+        // Déjà terminé et récolté: le groupe n'est plus le nôtre
+        return child.try_wait().ok().flatten();
+    };
+    let pgid = pid as libc::pid_t;
+    unsafe {
+        libc::killpg(pgid, libc::SIGTERM);
+    }
+    let grace = sleep(KILL_GRACE);
+    tokio::pin!(grace);
+    while !has_exited(pid) {
+        tokio::select! {
+            _ = &mut grace => break,
+            _ = sleep(EXIT_POLL_INTERVAL) => {}
+        }
+    }
+    // Tuer aussi les sous-processus qui auraient ignoré SIGTERM
+    unsafe {
+        libc::killpg(pgid, libc::SIGKILL);
+    }
+    child.wait().await.ok()
+}
+
+// Le processus est-il terminé, sans le récolter (WNOWAIT)
+fn has_exited(pid: u32) -> bool {
+    let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+    let result = unsafe { libc::waitid(libc::P_PID, pid as libc::id_t, &mut info, libc::WEXITED | libc::WNOHANG | libc::WNOWAIT) };
+    // Erreur: plus d'enfant à attendre
+    result != 0 || unsafe { info.si_pid() } != 0
+}
+
+// Activité run_command: lance une commande autorisée et renvoie son code de sortie
+pub async fn run_command_activity(
+    policy: Arc<CommandPolicy>,
+    logs: Arc<dyn LogStore>,
+    ctx: ActContext,
+    payload: serde_json::Value,
+) -> Result<ActExitValue<CommandOutput>, ActivityError> {
+    let input = serde_json::from_value::<CommandInput>(payload)
+        .map_err(|e| ActivityError::NonRetryable(anyhow::anyhow!("Entrée invalide pour run_command: {}", e)))?;
+    let info = ctx.get_info();
+    let execution = info.workflow_execution.clone().unwrap_or_default();
+    let target = LogTarget {
+        workflow_id: execution.workflow_id,
+        run_id: execution.run_id,
+        activity_id: info.activity_id.clone(),
+    };
+
+    let result = execute_command(
+        &policy,
+        &input,
+        logs.as_ref(),
+        &target,
+        |progress| {
+            if let Ok(details) = progress.as_json_payload() {
+                ctx.record_heartbeat(vec![details]);
+            }
+        },
+        ctx.cancelled(),
+    )
+    .await;
+
+    match result {
+        Ok(output) => {
+            info!("✅ {} exited with {:?} (timed out: {})", input.command, output.exit_code, output.timed_out);
+            Ok(ActExitValue::Normal(output))
+        }
+        Err(CommandError::Cancelled) => Err(ActivityError::Cancelled { details: None }),
+        // Relancer ne changerait rien: commande ou environnement interdit, ou impossible à lancer
+        Err(err) => Err(ActivityError::NonRetryable(err.into())),
+    }
+}
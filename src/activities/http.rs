@@ -0,0 +1,179 @@
+use std::{collections::HashMap, fmt, time::Duration};
+
+use log::{info, warn};
+use reqwest::{header::RETRY_AFTER, Client, Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use temporal_sdk::{ActContext, ActExitValue, ActivityError};
+
+// Délai appliqué quand l'entrée n'en précise pas
+pub const DEFAULT_HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+// Délai maximal accepté en entrée (une heure)
+pub const MAX_HTTP_TIMEOUT: Duration = Duration::from_secs(3600);
+
+// Taille maximale du corps conservé dans le résultat (stocké dans l'historique Temporal)
+pub const MAX_BODY_BYTES: usize = 256 * 1024;
+
+// Entrée de http_request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HttpRequestInput {
+    #[serde(default = "default_method")]
+    pub method: String,
+    pub url: String,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    // Chaîne envoyée telle quelle, toute autre valeur envoyée en JSON
+    #[serde(default)]
+    pub body: Option<Value>,
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+fn default_method() -> String {
+    "GET".to_string()
+}
+
+impl HttpRequestInput {
+    pub fn timeout(&self) -> Duration {
+        self.timeout_secs.map(Duration::from_secs).unwrap_or(DEFAULT_HTTP_TIMEOUT)
+    }
+}
+
+// Réponse renvoyée par http_request et stockée dans le résultat du workflow
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HttpResponseOutput {
+    pub status: u16,
+    pub headers: HashMap<String, String>,
+    // Corps décodé en JSON si possible, sinon en texte
+    pub body: Value,
+    // Corps coupé à MAX_BODY_BYTES
+    pub truncated: bool,
+}
+
+#[derive(Debug)]
+pub enum HttpRequestError {
+    // Méthode, URL ou en-tête invalide
+    InvalidRequest(String),
+    // Connexion impossible, délai dépassé...
+    Transport(reqwest::Error),
+    // Réponse hors 2xx
+    Status(HttpResponseOutput),
+}
+
+impl HttpRequestError {
+    // 408, 429, 5xx et erreurs réseau sont transitoires; les autres réponses ne changeront pas
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            HttpRequestError::InvalidRequest(_) => false,
+            HttpRequestError::Transport(_) => true,
+            HttpRequestError::Status(response) => is_retryable_status(response.status),
+        }
+    }
+
+    // Délai demandé par le serveur via Retry-After (en secondes)
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            HttpRequestError::Status(response) => response
+                .headers
+                .get(RETRY_AFTER.as_str())
+                .and_then(|value| value.trim().parse::<u64>().ok())
+                .map(Duration::from_secs),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for HttpRequestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HttpRequestError::InvalidRequest(message) => write!(f, "Requête invalide: {}", message),
+            HttpRequestError::Transport(err) => write!(f, "Échec de la requête: {}", err),
+            HttpRequestError::Status(response) => write!(f, "HTTP {}", response.status),
+        }
+    }
+}
+
+impl std::error::Error for HttpRequestError {}
+
+pub fn is_retryable_status(status: u16) -> bool {
+    status == StatusCode::REQUEST_TIMEOUT.as_u16() || status == StatusCode::TOO_MANY_REQUESTS.as_u16() || (500..600).contains(&status)
+}
+
+// Envoyer la requête; toute réponse hors 2xx est une erreur
+pub async fn send_http_request(client: &Client, input: &HttpRequestInput) -> Result<HttpResponseOutput, HttpRequestError> {
+    let method = Method::from_bytes(input.method.to_uppercase().as_bytes())
+        .map_err(|_| HttpRequestError::InvalidRequest(format!("méthode {}", input.method)))?;
+    let url = reqwest::Url::parse(&input.url).map_err(|e| HttpRequestError::InvalidRequest(format!("URL {}: {}", input.url, e)))?;
+
+    let mut request = client.request(method, url).timeout(input.timeout());
+    for (name, value) in &input.headers {
+        request = request.header(name, value);
+    }
+    request = match &input.body {
+        None | Some(Value::Null) => request,
+        Some(Value::String(body)) => request.body(body.clone()),
+        Some(body) => request.json(body),
+    };
+
+    // Une erreur de construction (en-tête invalide...) ne se corrigera pas en réessayant
+    let mut response = request.send().await.map_err(|err| {
+        if err.is_builder() {
+            HttpRequestError::InvalidRequest(err.to_string())
+        } else {
+            HttpRequestError::Transport(err)
+        }
+    })?;
+    let status = response.status();
+    let headers = response
+        .headers()
+        .iter()
+        .map(|(name, value)| (name.to_string(), String::from_utf8_lossy(value.as_bytes()).into_owned()))
+        .collect();
+    // Lire le corps par morceaux et s'arrêter à MAX_BODY_BYTES, sans télécharger le reste
+    let mut bytes = Vec::new();
+    let mut truncated = false;
+    while let Some(chunk) = response.chunk().await.map_err(HttpRequestError::Transport)? {
+        let room = MAX_BODY_BYTES - bytes.len();
+        if chunk.len() > room {
+            bytes.extend_from_slice(&chunk[..room]);
+            truncated = true;
+            break;
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    let body = match serde_json::from_slice::<Value>(&bytes) {
+        Ok(body) if !truncated => body,
+        _ if bytes.is_empty() => Value::Null,
+        _ => Value::String(String::from_utf8_lossy(&bytes).into_owned()),
+    };
+
+    let output = HttpResponseOutput { status: status.as_u16(), headers, body, truncated };
+    if status.is_success() {
+        Ok(output)
+    } else {
+        Err(HttpRequestError::Status(output))
+    }
+}
+
+// Activité http_request: l'échec est marqué retryable ou non pour la RetryPolicy de Temporal
+pub async fn http_request_activity(client: Client, _ctx: ActContext, payload: Value) -> Result<ActExitValue<HttpResponseOutput>, ActivityError> {
+    let input = serde_json::from_value::<HttpRequestInput>(payload)
+        .map_err(|e| ActivityError::NonRetryable(anyhow::anyhow!("Entrée invalide pour http_request: {}", e)))?;
+
+    match send_http_request(&client, &input).await {
+        Ok(response) => {
+            info!("✅ {} {} -> {}", input.method, input.url, response.status);
+            Ok(ActExitValue::Normal(response))
+        }
+        Err(err) if err.is_retryable() => {
+            warn!("🔁 {} {} failed, will retry: {}", input.method, input.url, err);
+            let explicit_delay = err.retry_after();
+            Err(ActivityError::Retryable { source: err.into(), explicit_delay })
+        }
+        Err(err) => {
+            warn!("❌ {} {} failed: {}", input.method, input.url, err);
+            Err(ActivityError::NonRetryable(err.into()))
+        }
+    }
+}
@@ -0,0 +1,5 @@
+// Activités génériques proposées par le registre, en plus de repeat_activity
+pub mod children;
+pub mod command;
+pub mod http;
+pub mod steps;
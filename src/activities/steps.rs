@@ -0,0 +1,32 @@
+use log::debug;
+use temporal_sdk::{ActContext, ActExitValue, ActivityError};
+
+use crate::{helpers::step_store::SharedStepStore, pipeline::{CompensationState, StepState}};
+
+// Activité locale record_pipeline_steps: enregistre l'état des étapes du run appelant
+pub async fn record_steps_activity(store: SharedStepStore, ctx: ActContext, payload: serde_json::Value) -> Result<ActExitValue<()>, ActivityError> {
+    let steps = serde_json::from_value::<Vec<StepState>>(payload)
+        .map_err(|e| ActivityError::NonRetryable(anyhow::anyhow!("Entrée invalide pour record_pipeline_steps: {}", e)))?;
+    let execution = ctx.get_info().workflow_execution.clone().unwrap_or_default();
+
+    store
+        .save(&execution.workflow_id, &execution.run_id, &steps)
+        .await
+        .map_err(|source| ActivityError::Retryable { source, explicit_delay: None })?;
+    debug!("Recorded {} steps for workflow {}", steps.len(), execution.workflow_id);
+    Ok(ActExitValue::Normal(()))
+}
+
+// Activité locale record_pipeline_compensation: enregistre la compensation sur l'exécution
+pub async fn record_compensation_activity(store: SharedStepStore, ctx: ActContext, payload: serde_json::Value) -> Result<ActExitValue<()>, ActivityError> {
+    let compensation = serde_json::from_value::<CompensationState>(payload)
+        .map_err(|e| ActivityError::NonRetryable(anyhow::anyhow!("Entrée invalide pour record_pipeline_compensation: {}", e)))?;
+    let execution = ctx.get_info().workflow_execution.clone().unwrap_or_default();
+
+    store
+        .save_compensation(&execution.workflow_id, &compensation)
+        .await
+        .map_err(|source| ActivityError::Retryable { source, explicit_delay: None })?;
+    debug!("Recorded compensation {:?} for workflow {}", compensation.status, execution.workflow_id);
+    Ok(ActExitValue::Normal(()))
+}
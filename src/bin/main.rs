@@ -1,4 +1,7 @@
-use executor::{controller, database::init_db, workers};
+use std::sync::Arc;
+
+use migration::{Migrator, MigratorTrait};
+use executor::{activities::command::CommandPolicy, controller, database::init_db, helpers::{child_store::DbChildStore, client::{get_client, SharedClient}, clock::SystemClock, log_store::DbLogStore, status_tracker::{SharedStatusTracker, StatusTracker}, step_store::DbStepStore}, registry::ActivityDeps, workers};
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
@@ -7,13 +10,41 @@ async fn main() -> std::io::Result<()> {
     dotenv::dotenv().ok();
     println!("🚀 Starting application");
 
+    // Client Temporal partagé par le serveur et le scheduler
+    let client: SharedClient = match get_client().await {
+        Ok(client) => Arc::new(client),
+        Err(e) => {
+            log::error!("❌ Failed to connect to Temporal: {:?}", e);
+            return Err(std::io::Error::new(std::io::ErrorKind::Other, e.to_string()));
+        }
+    };
+
+    // Appliquer les migrations avant de lancer le serveur, le worker et les tâches de fond
+    let db = init_db().await;
+    if let Err(e) = Migrator::up(&db, None).await {
+        log::error!("❌ Failed to run migrations: {:?}", e);
+        return Err(std::io::Error::new(std::io::ErrorKind::Other, e.to_string()));
+    }
+
+    // Compteurs de la synchronisation des statuts, exposés par l'API
+    let tracker: SharedStatusTracker = Arc::new(StatusTracker::new());
+
     println!("🔧 Starting Temporal worker...");
     println!("🌐 Starting Actix Web server...");
 
     // Lancer worker et serveur en parallèle, sans tokio::spawn
     let (worker_result, server_result,()) = tokio::join!(
         async {
-            if let Err(e) = workers::start_worker().await {
+            // Sortie des commandes, étapes de pipeline et enfants des fan-outs enregistrés en base, lus par l'API
+            let deps = ActivityDeps {
+                clock: Arc::new(SystemClock),
+                logs: Arc::new(DbLogStore::new(db.clone())),
+                commands: Arc::new(CommandPolicy::from_env()),
+                http: reqwest::Client::new(),
+                steps: Arc::new(DbStepStore::new(db.clone())),
+                children: Arc::new(DbChildStore::new(db.clone())),
+            };
+            if let Err(e) = workers::start_worker(deps).await {
                 log::error!("❌ Failed to start worker: {:?}", e);
                 Err(std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))
             } else {
@@ -22,13 +53,15 @@ async fn main() -> std::io::Result<()> {
             }
         },
         async {
-            controller::run_server().await
+            controller::run_server(db.clone(), client.clone(), tracker.clone()).await
         },
         async {
-            let db = std::sync::Arc::new(init_db().await);
+            let db = std::sync::Arc::new(db.clone());
 
-            // 🔁 Lancer le scheduler en tâche de fond
-            crate::workers::start_execution_status_scheduler(db.clone()).await;
+            // 🔁 Lancer le renouvellement du bail puis le scheduler en tâche de fond
+            crate::workers::start_lease_heartbeat(db.clone(), tracker.clone()).await;
+            crate::workers::start_execution_status_scheduler(db.clone(), client.clone(), tracker.clone()).await;
+            crate::workers::start_visibility_reconciler(db.clone(), client.clone(), tracker.clone()).await;
         }
     );
 
@@ -0,0 +1,22 @@
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+
+// Ligne de sortie d'une commande lancée par l'activité run_command
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+#[sea_orm(table_name = "command_logs")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub workflow_id: String,
+    pub run_id: String,
+    pub activity_id: String,
+    // "stdout" ou "stderr"
+    pub stream: String,
+    pub line: String,
+    pub logged_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
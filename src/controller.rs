@@ -1,35 +1,108 @@
 use std::env;
 
-use actix_web::{delete, get, post, web, App, HttpResponse, HttpServer, Responder};
-use migration::{Migrator, MigratorTrait};
+use actix_web::{delete, get, http::header::{ContentDisposition, DispositionParam, DispositionType}, post, web, App, HttpRequest, HttpResponse, HttpServer, Responder};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use temporal_sdk_core_protos::temporal::api::history::v1::{History, HistoryEvent};
 use uuid::Uuid;
-use crate::database::init_db;
-use crate::service::{create_execution, delete_execution, get_execution_by_id, init_workflow, list_executions, ExecutionInput};
+use crate::executions::ExecutionStatus;
+use crate::helpers::client::{SharedClient, StartOptions};
+use crate::helpers::status_tracker::{SharedStatusTracker, StatusSyncCounters};
+use crate::leader::{current_leader, SCHEDULER_LEASE};
+use crate::leases;
+use crate::reconciler::{latest_drift_report, VISIBILITY_RECONCILE_PERIOD};
+use crate::registry::{registry, ActivityType, WorkflowType, PIPELINE_WORKFLOW, REPEAT_WORKFLOW};
+use crate::schedules::{create_schedule, delete_schedule, list_schedules, set_schedule_paused, trigger_schedule, ScheduleInput};
+use crate::service::{cancel_workflow, confirm_execution, create_or_get_execution, delete_execution, get_execution_by_id, get_execution_state, get_execution_tree, get_execution_version, get_full_workflow_history, get_pipeline_steps, get_workflow_history_page, list_command_logs, list_executions, new_workflow_id, signal_workflow, start_workflow, update_workflow, ExecutionInput, NewExecution, UpdateResult};
+use crate::workflows::{REPEAT_SIGNALS, REPEAT_UPDATES};
 
-// Créer une nouvelle exécution
-#[post("/executions")]
-async fn add_execution(db: web::Data<DatabaseConnection>) -> impl Responder {
-
-    match init_workflow().await {
-        Ok((workflow_id, run_id)) => {
-            let execution_input = ExecutionInput {
-                id: Uuid::new_v4(),
-                workflow_id,
-                run_id,
-                status: "RUNNING".to_string(),
-            };
+// En-tête permettant de rejouer sans risque une création d'exécution
+const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
 
-            match create_execution(&db, execution_input).await {
-                Ok(execution) => HttpResponse::Ok().json(execution),
-                Err(e) => {
-                    HttpResponse::InternalServerError().body(format!("Échec de la création de l'exécution:{}",e))
-                }
-            }
-        }
-        Err(e) => {
-            HttpResponse::InternalServerError().body(format!("Échec du démarrage du workflow: {}", e))
+// En-tête de réponse signalant qu'une exécution existante a été renvoyée
+const IDEMPOTENT_REPLAYED_HEADER: &str = "Idempotent-Replayed";
+
+// Créer une nouvelle exécution, immédiate ou différée
+#[post("/executions")]
+async fn add_execution(req: HttpRequest, body: web::Bytes, db: web::Data<DatabaseConnection>, client: web::Data<SharedClient>) -> impl Responder {
+    let request = if body.is_empty() {
+        NewExecution::default()
+    } else {
+        match serde_json::from_slice::<NewExecution>(&body) {
+            Ok(request) => request,
+            Err(e) => return HttpResponse::BadRequest().body(format!("Corps JSON invalide: {}", e)),
         }
+    };
+
+    let idempotency_key = match req.headers().get(IDEMPOTENCY_KEY_HEADER).map(|value| value.to_str()) {
+        None => None,
+        Some(Ok(key)) => Some(key.trim()),
+        Some(Err(_)) => return HttpResponse::BadRequest().body("En-tête Idempotency-Key invalide"),
+    };
+    let requested_workflow_id = match request.requested_workflow_id(idempotency_key) {
+        Ok(workflow_id) => workflow_id,
+        Err(e) => return HttpResponse::BadRequest().body(e),
+    };
+
+    let now = chrono::Utc::now();
+    let start_at = match request.planned_start(now) {
+        Ok(start_at) => start_at,
+        Err(e) => return HttpResponse::BadRequest().body(e),
+    };
+
+    let workflow_type = request.workflow_type.as_deref().unwrap_or(REPEAT_WORKFLOW);
+    let Some(definition) = registry().workflow(workflow_type) else {
+        return HttpResponse::BadRequest().body(format!("Type de workflow inconnu: {}", workflow_type));
+    };
+    let input = match definition.input(request.input) {
+        Ok(input) => input,
+        Err(e) => return HttpResponse::BadRequest().body(e),
+    };
+
+    // Phase 1: réserver l'exécution en base avant tout démarrage.
+    // Une requête rejouée (même clé) renvoie l'exécution existante sans redémarrer de workflow;
+    // l'index unique départage deux requêtes concurrentes.
+    let execution_input = ExecutionInput {
+        id: Uuid::new_v4(),
+        workflow_id: requested_workflow_id.unwrap_or_else(new_workflow_id),
+        run_id: String::new(),
+        status: ExecutionStatus::Pending,
+        schedule_id: None,
+        start_at,
+        previous_run_ids: vec![],
+        workflow_type: definition.name.to_string(),
+        input: Some(input),
+        parent_id: None,
+    };
+    let execution = match create_or_get_execution(&db, execution_input).await {
+        Ok((execution, true)) => execution,
+        // Réservation d'un essai précédent resté sans réponse: reprendre son démarrage
+        Ok((execution, false)) if execution.status == ExecutionStatus::Pending => execution,
+        Ok((execution, false)) => return HttpResponse::Ok().insert_header((IDEMPOTENT_REPLAYED_HEADER, "true")).json(execution),
+        Err(e) => return HttpResponse::InternalServerError().body(format!("Échec de la création de l'exécution:{}", e)),
+    };
+
+    // Phase 2: démarrer le workflow sous l'ID réservé, à l'heure enregistrée avec la
+    // réservation (celle de la requête d'origine pour un nouvel essai). Une heure
+    // dépassée entre-temps démarre immédiatement, comme dans le réconciliateur.
+    let start_delay = execution.start_at.and_then(|start_at| (start_at - now).to_std().ok());
+    let options = StartOptions {
+        start_delay,
+        ..Default::default()
+    };
+    // En cas d'échec la réservation reste PENDING: un nouvel essai avec la même clé ou
+    // le réconciliateur reprendra le démarrage.
+    let run_id = match start_workflow(client.get_ref().as_ref(), &execution.workflow_type, execution.workflow_id.clone(), execution.input.clone(), options).await {
+        Ok((_, run_id)) => run_id,
+        Err(e) => return HttpResponse::InternalServerError().body(format!("Échec du démarrage du workflow: {}", e)),
+    };
+
+    // Phase 3: confirmer. En cas d'échec, le réconciliateur confirmera la ligne PENDING.
+    let status = if start_delay.is_some() { ExecutionStatus::Scheduled } else { ExecutionStatus::Running };
+    match confirm_execution(&db, execution.id, run_id, status).await {
+        Ok(execution) => HttpResponse::Ok().json(execution),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Échec de la confirmation de l'exécution: {}", e)),
     }
 }
 
@@ -43,6 +116,79 @@ async fn get_execution(id: web::Path<Uuid>, db: web::Data<DatabaseConnection>) -
     }
 }
 
+// Récupérer la version d'exécution (build IDs, patches) du run courant d'une exécution
+#[get("/executions/{id}/version")]
+async fn get_execution_version_endpoint(id: web::Path<Uuid>, db: web::Data<DatabaseConnection>, client: web::Data<SharedClient>) -> impl Responder {
+    let execution = match get_execution_by_id(&db, id.into_inner()).await {
+        Ok(Some(execution)) => execution,
+        Ok(None) => return HttpResponse::NotFound().body("Exécution non trouvée"),
+        Err(_) => return HttpResponse::InternalServerError().body("Échec de la récupération de l'exécution"),
+    };
+
+    match get_execution_version(client.get_ref().as_ref(), execution.workflow_id, execution.run_id).await {
+        Ok(version) => HttpResponse::Ok().json(version),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Échec de la récupération de la version: {}", e)),
+    }
+}
+
+// Récupérer une exécution avec ses workflows enfants, récursivement
+#[get("/executions/{id}/tree")]
+async fn get_execution_tree_endpoint(id: web::Path<Uuid>, db: web::Data<DatabaseConnection>) -> impl Responder {
+    match get_execution_tree(&db, id.into_inner()).await {
+        Ok(Some(tree)) => HttpResponse::Ok().json(tree),
+        Ok(None) => HttpResponse::NotFound().body("Exécution non trouvée"),
+        Err(_) => HttpResponse::InternalServerError().body("Échec de la récupération de l'arbre d'exécution"),
+    }
+}
+
+// Récupérer l'état des étapes d'une exécution de pipeline_workflow
+#[get("/executions/{id}/steps")]
+async fn get_execution_steps(id: web::Path<Uuid>, db: web::Data<DatabaseConnection>) -> impl Responder {
+    let execution = match get_execution_by_id(&db, id.into_inner()).await {
+        Ok(Some(execution)) => execution,
+        Ok(None) => return HttpResponse::NotFound().body("Exécution non trouvée"),
+        Err(_) => return HttpResponse::InternalServerError().body("Échec de la récupération de l'exécution"),
+    };
+    if execution.workflow_type != PIPELINE_WORKFLOW {
+        return HttpResponse::BadRequest().body(format!("L'exécution n'est pas un {}", PIPELINE_WORKFLOW));
+    }
+
+    match get_pipeline_steps(&db, execution.workflow_id, execution.run_id).await {
+        Ok(steps) => HttpResponse::Ok().json(steps),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Échec de la récupération des étapes: {}", e)),
+    }
+}
+
+// Récupérer la sortie des commandes lancées par une exécution
+#[get("/executions/{id}/logs")]
+async fn get_execution_logs(id: web::Path<Uuid>, db: web::Data<DatabaseConnection>) -> impl Responder {
+    let execution = match get_execution_by_id(&db, id.into_inner()).await {
+        Ok(Some(execution)) => execution,
+        Ok(None) => return HttpResponse::NotFound().body("Exécution non trouvée"),
+        Err(_) => return HttpResponse::InternalServerError().body("Échec de la récupération de l'exécution"),
+    };
+
+    match list_command_logs(&db, &execution.workflow_id).await {
+        Ok(logs) => HttpResponse::Ok().json(logs),
+        Err(_) => HttpResponse::InternalServerError().body("Échec de la récupération des logs"),
+    }
+}
+
+// Récupérer l'état courant du workflow d'une exécution
+#[get("/executions/{id}/state")]
+async fn get_execution_live_state(id: web::Path<Uuid>, db: web::Data<DatabaseConnection>, client: web::Data<SharedClient>) -> impl Responder {
+    let execution = match get_execution_by_id(&db, id.into_inner()).await {
+        Ok(Some(execution)) => execution,
+        Ok(None) => return HttpResponse::NotFound().body("Exécution non trouvée"),
+        Err(_) => return HttpResponse::InternalServerError().body("Échec de la récupération de l'exécution"),
+    };
+
+    match get_execution_state(client.get_ref().as_ref(), execution.workflow_id, execution.run_id).await {
+        Ok(state) => HttpResponse::Ok().json(state),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Échec de la récupération de l'état: {}", e)),
+    }
+}
+
 // Supprimer une exécution
 #[delete("/executions/{id}")]
 async fn delete_execution_endpoint(id: web::Path<Uuid>, db: web::Data<DatabaseConnection>) -> impl Responder {
@@ -53,6 +199,87 @@ async fn delete_execution_endpoint(id: web::Path<Uuid>, db: web::Data<DatabaseCo
     }
 }
 
+// Annuler le workflow d'une exécution
+#[post("/executions/{id}/cancel")]
+async fn cancel_execution(id: web::Path<Uuid>, db: web::Data<DatabaseConnection>, client: web::Data<SharedClient>) -> impl Responder {
+    let execution = match get_execution_by_id(&db, id.into_inner()).await {
+        Ok(Some(execution)) => execution,
+        Ok(None) => return HttpResponse::NotFound().body("Exécution non trouvée"),
+        Err(_) => return HttpResponse::InternalServerError().body("Échec de la récupération de l'exécution"),
+    };
+
+    match cancel_workflow(client.get_ref().as_ref(), execution.workflow_id, execution.run_id).await {
+        Ok(()) => HttpResponse::Accepted().body("Annulation demandée"),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Échec de l'annulation du workflow: {}", e)),
+    }
+}
+
+// Envoyer un signal au workflow d'une exécution
+#[post("/executions/{id}/signals/{name}")]
+async fn signal_execution(
+    path: web::Path<(Uuid, String)>,
+    body: web::Bytes,
+    db: web::Data<DatabaseConnection>,
+    client: web::Data<SharedClient>,
+) -> impl Responder {
+    let (id, signal_name) = path.into_inner();
+    if !REPEAT_SIGNALS.contains(&signal_name.as_str()) {
+        return HttpResponse::BadRequest().body(format!("Signal inconnu: {}", signal_name));
+    }
+
+    let payload = if body.is_empty() {
+        None
+    } else {
+        match serde_json::from_slice::<serde_json::Value>(&body) {
+            Ok(value) => Some(value),
+            Err(e) => return HttpResponse::BadRequest().body(format!("Corps JSON invalide: {}", e)),
+        }
+    };
+
+    let execution = match get_execution_by_id(&db, id).await {
+        Ok(Some(execution)) => execution,
+        Ok(None) => return HttpResponse::NotFound().body("Exécution non trouvée"),
+        Err(_) => return HttpResponse::InternalServerError().body("Échec de la récupération de l'exécution"),
+    };
+    if execution.workflow_type != REPEAT_WORKFLOW {
+        return HttpResponse::Conflict().body(format!("Le signal {} n'existe que pour {}", signal_name, REPEAT_WORKFLOW));
+    }
+
+    match signal_workflow(client.get_ref().as_ref(), execution.workflow_id, execution.run_id, signal_name, payload).await {
+        Ok(()) => HttpResponse::Accepted().body("Signal envoyé"),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Échec de l'envoi du signal: {}", e)),
+    }
+}
+
+// Modifier une exécution en cours via un update Temporal et renvoyer sa réponse
+#[post("/executions/{id}/updates/{name}")]
+async fn update_execution_endpoint(
+    path: web::Path<(Uuid, String)>,
+    payload: web::Json<serde_json::Value>,
+    db: web::Data<DatabaseConnection>,
+    client: web::Data<SharedClient>,
+) -> impl Responder {
+    let (id, update_name) = path.into_inner();
+    if !REPEAT_UPDATES.contains(&update_name.as_str()) {
+        return HttpResponse::BadRequest().body(format!("Update inconnu: {}", update_name));
+    }
+
+    let execution = match get_execution_by_id(&db, id).await {
+        Ok(Some(execution)) => execution,
+        Ok(None) => return HttpResponse::NotFound().body("Exécution non trouvée"),
+        Err(_) => return HttpResponse::InternalServerError().body("Échec de la récupération de l'exécution"),
+    };
+    if execution.workflow_type != REPEAT_WORKFLOW {
+        return HttpResponse::Conflict().body(format!("L'update {} n'existe que pour {}", update_name, REPEAT_WORKFLOW));
+    }
+
+    match update_workflow(client.get_ref().as_ref(), execution.workflow_id, execution.run_id, update_name, payload.into_inner()).await {
+        Ok(result @ UpdateResult::Accepted { .. }) => HttpResponse::Ok().json(result),
+        Ok(result @ UpdateResult::Rejected { .. }) => HttpResponse::UnprocessableEntity().json(result),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Échec de l'update du workflow: {}", e)),
+    }
+}
+
 // Lister toutes les exécutions
 #[get("/executions")]
 async fn list_execution(db: web::Data<DatabaseConnection>) -> impl Responder {
@@ -62,10 +289,254 @@ async fn list_execution(db: web::Data<DatabaseConnection>) -> impl Responder {
     }
 }
 
+// Format de l'historique renvoyé par /executions/{id}/history
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum HistoryFormat {
+    // Résumé lisible des événements
+    #[default]
+    Json,
+    // Historique protobuf sérialisé tel quel (rejouable)
+    Raw,
+}
+
+#[derive(Debug, Deserialize)]
+struct HistoryQuery {
+    page_token: Option<String>,
+    #[serde(default)]
+    format: HistoryFormat,
+    #[serde(default)]
+    download: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct HistoryEventSummary {
+    event_id: i64,
+    event_type: String,
+    event_time: serde_json::Value,
+}
+
+impl From<&HistoryEvent> for HistoryEventSummary {
+    fn from(event: &HistoryEvent) -> Self {
+        HistoryEventSummary {
+            event_id: event.event_id,
+            event_type: event.event_type().as_str_name().to_string(),
+            event_time: serde_json::to_value(&event.event_time).unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct HistoryPage {
+    execution_id: Uuid,
+    workflow_id: String,
+    run_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    events: Option<Vec<HistoryEventSummary>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    history: Option<History>,
+    next_page_token: Option<String>,
+}
+
+// Récupérer l'historique Temporal d'une exécution
+#[get("/executions/{id}/history")]
+async fn get_execution_history(
+    id: web::Path<Uuid>,
+    query: web::Query<HistoryQuery>,
+    db: web::Data<DatabaseConnection>,
+    client: web::Data<SharedClient>,
+) -> impl Responder {
+    let execution = match get_execution_by_id(&db, id.into_inner()).await {
+        Ok(Some(execution)) => execution,
+        Ok(None) => return HttpResponse::NotFound().body("Exécution non trouvée"),
+        Err(_) => return HttpResponse::InternalServerError().body("Échec de la récupération de l'exécution"),
+    };
+
+    // Le téléchargement renvoie toujours l'historique complet au format brut, rejouable tel quel
+    if query.download {
+        return match get_full_workflow_history(client.get_ref().as_ref(), execution.workflow_id.clone(), execution.run_id.clone()).await {
+            Ok(history) => HttpResponse::Ok()
+                .insert_header(ContentDisposition {
+                    disposition: DispositionType::Attachment,
+                    parameters: vec![DispositionParam::Filename(format!(
+                        "{}_{}_history.json",
+                        execution.workflow_id, execution.run_id
+                    ))],
+                })
+                .json(history),
+            Err(e) => HttpResponse::InternalServerError().body(format!("Échec de la récupération de l'historique: {}", e)),
+        };
+    }
+
+    let page_token = match query.page_token.as_deref().map(|token| URL_SAFE_NO_PAD.decode(token)) {
+        Some(Ok(token)) => token,
+        Some(Err(_)) => return HttpResponse::BadRequest().body("page_token invalide"),
+        None => vec![],
+    };
+
+    match get_workflow_history_page(client.get_ref().as_ref(), execution.workflow_id.clone(), execution.run_id.clone(), page_token).await {
+        Ok(response) => {
+            let history = response.history.unwrap_or_default();
+            let next_page_token = (!response.next_page_token.is_empty())
+                .then(|| URL_SAFE_NO_PAD.encode(&response.next_page_token));
+            let (events, history) = match query.format {
+                HistoryFormat::Json => (Some(history.events.iter().map(HistoryEventSummary::from).collect()), None),
+                HistoryFormat::Raw => (None, Some(history)),
+            };
+
+            HttpResponse::Ok().json(HistoryPage {
+                execution_id: execution.id,
+                workflow_id: execution.workflow_id,
+                run_id: execution.run_id,
+                events,
+                history,
+                next_page_token,
+            })
+        }
+        Err(e) => HttpResponse::InternalServerError().body(format!("Échec de la récupération de l'historique: {}", e)),
+    }
+}
+
+// Créer un schedule
+#[post("/schedules")]
+async fn add_schedule(input: web::Json<ScheduleInput>, client: web::Data<SharedClient>) -> impl Responder {
+    let input = input.into_inner();
+    if let Err(e) = input.validate() {
+        return HttpResponse::BadRequest().body(e);
+    }
+
+    match create_schedule(client.get_ref().as_ref(), input).await {
+        Ok(schedule) => HttpResponse::Ok().json(schedule),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Échec de la création du schedule: {}", e)),
+    }
+}
+
+// Lister les schedules
+#[get("/schedules")]
+async fn list_schedule(client: web::Data<SharedClient>) -> impl Responder {
+    match list_schedules(client.get_ref().as_ref()).await {
+        Ok(schedules) => HttpResponse::Ok().json(schedules),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Échec de la récupération des schedules: {}", e)),
+    }
+}
+
+// Mettre en pause un schedule
+#[post("/schedules/{id}/pause")]
+async fn pause_schedule(id: web::Path<String>, client: web::Data<SharedClient>) -> impl Responder {
+    match set_schedule_paused(client.get_ref().as_ref(), id.into_inner(), true).await {
+        Ok(()) => HttpResponse::Ok().body("Schedule mis en pause"),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Échec de la mise en pause du schedule: {}", e)),
+    }
+}
+
+// Reprendre un schedule en pause
+#[post("/schedules/{id}/unpause")]
+async fn unpause_schedule(id: web::Path<String>, client: web::Data<SharedClient>) -> impl Responder {
+    match set_schedule_paused(client.get_ref().as_ref(), id.into_inner(), false).await {
+        Ok(()) => HttpResponse::Ok().body("Schedule repris"),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Échec de la reprise du schedule: {}", e)),
+    }
+}
+
+// Déclencher immédiatement un run du schedule
+#[post("/schedules/{id}/trigger")]
+async fn trigger_schedule_endpoint(id: web::Path<String>, client: web::Data<SharedClient>) -> impl Responder {
+    match trigger_schedule(client.get_ref().as_ref(), id.into_inner()).await {
+        Ok(()) => HttpResponse::Accepted().body("Run du schedule déclenché"),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Échec du déclenchement du schedule: {}", e)),
+    }
+}
+
+// Supprimer un schedule
+#[delete("/schedules/{id}")]
+async fn delete_schedule_endpoint(id: web::Path<String>, client: web::Data<SharedClient>) -> impl Responder {
+    match delete_schedule(client.get_ref().as_ref(), id.into_inner()).await {
+        Ok(()) => HttpResponse::Ok().body("Schedule supprimé"),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Échec de la suppression du schedule: {}", e)),
+    }
+}
+
+// Consulter le dernier rapport de dérive entre la base et la visibilité Temporal.
+// Produit par l'instance leader, il est lu en base: toutes les instances le servent.
+#[get("/admin/drift")]
+async fn get_drift_report(db: web::Data<DatabaseConnection>) -> impl Responder {
+    match latest_drift_report(&db).await {
+        Ok(Some(report)) => HttpResponse::Ok().json(report),
+        Ok(None) => HttpResponse::ServiceUnavailable()
+            .insert_header(("Retry-After", VISIBILITY_RECONCILE_PERIOD.as_secs().to_string()))
+            .body("Aucune réconciliation effectuée pour le moment: l'instance leader produit le rapport toutes les 60 secondes"),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Échec de la lecture du rapport de dérive: {}", e)),
+    }
+}
+
+// État du scheduler de statuts sur cette instance
+#[derive(Serialize)]
+struct SchedulerStatus {
+    instance_id: String,
+    // Détenteur actuel du bail, toutes instances confondues
+    leader: Option<leases::Model>,
+    #[serde(flatten)]
+    counters: StatusSyncCounters,
+}
+
+// Consulter le leader et les compteurs de la synchronisation des statuts
+#[get("/admin/scheduler")]
+async fn get_scheduler_counters(db: web::Data<DatabaseConnection>, tracker: web::Data<SharedStatusTracker>) -> impl Responder {
+    match current_leader(&db, SCHEDULER_LEASE, chrono::Utc::now()).await {
+        Ok(leader) => HttpResponse::Ok().json(SchedulerStatus {
+            instance_id: tracker.instance_id().to_string(),
+            leader,
+            counters: tracker.snapshot(),
+        }),
+        Err(err) => HttpResponse::InternalServerError().body(format!("Erreur lors de la lecture du bail: {}", err)),
+    }
+}
+
+// Types de workflow et d'activité disponibles
+#[derive(Serialize)]
+struct WorkflowTypes {
+    workflows: &'static [WorkflowType],
+    activities: &'static [ActivityType],
+}
+
+// Lister les types de workflow démarrables et les activités du registre
+#[get("/workflow-types")]
+async fn list_workflow_types() -> impl Responder {
+    HttpResponse::Ok().json(WorkflowTypes {
+        workflows: registry().workflows(),
+        activities: registry().activities(),
+    })
+}
+
+// Enregistrer les routes de l'API
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(add_execution)
+        .service(get_execution)
+        .service(get_execution_history)
+        .service(get_execution_live_state)
+        .service(get_execution_logs)
+        .service(get_execution_steps)
+        .service(get_execution_tree_endpoint)
+        .service(get_execution_version_endpoint)
+        .service(cancel_execution)
+        .service(signal_execution)
+        .service(update_execution_endpoint)
+        .service(delete_execution_endpoint)
+        .service(list_execution)
+        .service(add_schedule)
+        .service(list_schedule)
+        .service(pause_schedule)
+        .service(unpause_schedule)
+        .service(trigger_schedule_endpoint)
+        .service(delete_schedule_endpoint)
+        .service(get_drift_report)
+        .service(get_scheduler_counters)
+        .service(list_workflow_types);
+}
+
 // Lancer le serveur
-pub async fn run_server() -> std::io::Result<()> {
-    let db = init_db().await;
-    Migrator::up(&db, None).await.expect("Échec de l'application des migrations");
+// Les migrations doivent avoir été appliquées sur `db` (voir bin/main.rs)
+pub async fn run_server(db: DatabaseConnection, client: SharedClient, tracker: SharedStatusTracker) -> std::io::Result<()> {
     // Read the Temporal server address from environment variable, with fallback
     let server_url = env::var("SERVER_URL")
         .unwrap_or_else(|_| "127.0.0.1:8080".to_string());
@@ -73,10 +544,9 @@ pub async fn run_server() -> std::io::Result<()> {
     HttpServer::new(move || {
         App::new()
             .app_data(web::Data::new(db.clone()))
-            .service(add_execution)
-            .service(get_execution)
-            .service(delete_execution_endpoint)
-            .service(list_execution)
+            .app_data(web::Data::new(client.clone()))
+            .app_data(web::Data::new(tracker.clone()))
+            .configure(configure)
     })
     .bind(&server_url)?
     .run()
@@ -1,6 +1,10 @@
+use std::env;
+
 use sea_orm::{Database, DatabaseConnection};
 
 pub async fn init_db() -> DatabaseConnection {
-    let db_url = "sqlite:db.sqlite?mode=rwc";
-    Database::connect(db_url).await.expect("Échec de la connexion à la base de données")
-}
\ No newline at end of file
+    // Read the database URL from environment variable, with fallback
+    let db_url = env::var("DATABASE_URL")
+        .unwrap_or_else(|_| "sqlite:db.sqlite?mode=rwc".to_string());
+    Database::connect(&db_url).await.expect("Échec de la connexion à la base de données")
+}
@@ -0,0 +1,18 @@
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+
+// Dernier rapport de dérive, écrit par l'instance leader et lu par toutes les instances
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+#[sea_orm(table_name = "drift_reports")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub name: String,
+    #[sea_orm(column_type = "Json")]
+    pub report: Json,
+    pub generated_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,67 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// Étape d'un pipeline_workflow, enregistrée par le worker à chaque transition
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+#[sea_orm(table_name = "execution_steps")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    #[serde(skip)]
+    pub id: i64,
+    pub workflow_id: String,
+    pub run_id: String,
+    pub step_id: String,
+    pub activity_type: String,
+    #[sea_orm(column_type = "Json")]
+    pub depends_on: StepIds,
+    pub status: StepStatus,
+    // Résultat de l'activité une fois l'étape terminée
+    #[sea_orm(column_type = "Json", nullable)]
+    pub result: Option<Json>,
+    pub error: Option<String>,
+    // Heures du workflow (déterministes), pas celles du worker
+    pub started_at: Option<DateTimeUtc>,
+    pub finished_at: Option<DateTimeUtc>,
+    pub updated_at: DateTimeUtc,
+}
+
+// Statut d'une étape de pipeline
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum StepStatus {
+    // En attente de ses dépendances
+    #[default]
+    #[sea_orm(string_value = "PENDING")]
+    Pending,
+    #[sea_orm(string_value = "RUNNING")]
+    Running,
+    #[sea_orm(string_value = "COMPLETED")]
+    Completed,
+    #[sea_orm(string_value = "FAILED")]
+    Failed,
+    // Non lancée car une autre étape a échoué
+    #[sea_orm(string_value = "SKIPPED")]
+    Skipped,
+    #[sea_orm(string_value = "CANCELED")]
+    Canceled,
+    // Terminée puis annulée par son activité de compensation
+    #[sea_orm(string_value = "COMPENSATED")]
+    Compensated,
+}
+
+impl StepStatus {
+    pub fn is_finished(&self) -> bool {
+        !matches!(self, StepStatus::Pending | StepStatus::Running)
+    }
+}
+
+// Identifiants d'étapes stockés en JSON
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult)]
+#[serde(transparent)]
+pub struct StepIds(pub Vec<String>);
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
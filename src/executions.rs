@@ -1,5 +1,5 @@
 use sea_orm::entity::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
 #[sea_orm(table_name = "executions")]
@@ -8,9 +8,117 @@ pub struct Model {
     pub id: Uuid,
     pub workflow_id: String,
     pub run_id: String,
-    pub status: String,
+    pub status: ExecutionStatus,
+    // Schedule Temporal à l'origine de l'exécution, le cas échéant
+    pub schedule_id: Option<String>,
+    // Heure de démarrage planifiée pour un démarrage différé
+    pub start_at: Option<DateTimeUtc>,
+    // Renseignée par la base à l'insertion
+    pub created_at: DateTime,
+    // Runs précédents de la chaîne (continue-as-new, retries), du plus ancien au plus récent
+    #[sea_orm(column_type = "Json")]
+    pub previous_run_ids: RunIds,
+    // Raison d'un statut terminal posé par le service (ex: LOST)
+    pub status_reason: Option<String>,
+    // Dernière synchronisation du statut avec Temporal par le scheduler
+    pub synced_at: Option<DateTimeUtc>,
+    // Type de workflow démarré (voir le registre)
+    pub workflow_type: String,
+    // Entrée du workflow, complétée des valeurs par défaut du type
+    #[sea_orm(column_type = "Json", nullable)]
+    pub input: Option<Json>,
+    // Compensation des étapes d'un pipeline après échec ou annulation (voir pipeline::CompensationState)
+    #[sea_orm(column_type = "Json", nullable)]
+    pub compensation: Option<Json>,
+    // Exécution parente pour un workflow enfant (ex: fan_out_workflow)
+    pub parent_id: Option<Uuid>,
+    // Build ID du dernier worker ayant exécuté une tâche du workflow (voir versioning)
+    pub build_id: Option<String>,
+    // Workflow démarré hors du service et importé par le réconciliateur
+    pub imported: bool,
 }
 
+// Statut d'une exécution. Les valeurs reprennent les noms Temporal, complétés par
+// les statuts propres au service (PENDING, SCHEDULED, LOST)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ExecutionStatus {
+    // Réservée en base, workflow pas encore confirmé
+    #[sea_orm(string_value = "PENDING")]
+    Pending,
+    // Démarrage différé pas encore échu
+    #[sea_orm(string_value = "SCHEDULED")]
+    Scheduled,
+    #[sea_orm(string_value = "RUNNING")]
+    Running,
+    // Lignes enregistrées avant le suivi des chaînes de runs
+    #[sea_orm(string_value = "CONTINUED_AS_NEW")]
+    ContinuedAsNew,
+    #[sea_orm(string_value = "COMPLETED")]
+    Completed,
+    #[sea_orm(string_value = "FAILED")]
+    Failed,
+    #[sea_orm(string_value = "CANCELED")]
+    Canceled,
+    #[sea_orm(string_value = "TERMINATED")]
+    Terminated,
+    #[sea_orm(string_value = "TIMED_OUT")]
+    TimedOut,
+    // Workflow introuvable sur le serveur Temporal
+    #[sea_orm(string_value = "LOST")]
+    Lost,
+    // Statut Temporal non reconnu: toujours synchronisé, jusqu'à un statut connu
+    #[sea_orm(string_value = "UNKNOWN")]
+    Unknown,
+}
+
+impl ExecutionStatus {
+    // Statut définitif: l'exécution n'est plus synchronisée
+    pub fn is_terminal(self) -> bool {
+        matches!(
+            self,
+            Self::Completed | Self::Failed | Self::Canceled | Self::Terminated | Self::TimedOut | Self::Lost
+        )
+    }
+
+    // Statuts synchronisés par le scheduler
+    pub fn is_active(self) -> bool {
+        matches!(self, Self::Scheduled | Self::Running | Self::ContinuedAsNew | Self::Unknown)
+    }
+
+    // Machine à états: PENDING ne mène qu'à un statut confirmé, un démarrage différé peut
+    // démarrer ou se terminer, un workflow en cours (ou de statut inconnu) ne redevient
+    // pas planifié, et un statut terminal est définitif
+    pub fn can_transition_to(self, next: Self) -> bool {
+        if self == next {
+            return true;
+        }
+        match self {
+            Self::Pending => true,
+            Self::Scheduled => next != Self::Pending,
+            Self::Running | Self::ContinuedAsNew | Self::Unknown => !matches!(next, Self::Pending | Self::Scheduled),
+            _ => false,
+        }
+    }
+
+    // Statuts depuis lesquels `next` est atteignable
+    pub fn predecessors(next: Self) -> Vec<Self> {
+        Self::iter().filter(|status| status.can_transition_to(next)).collect()
+    }
+}
+
+impl std::fmt::Display for ExecutionStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.to_value())
+    }
+}
+
+// Liste de run IDs stockée en JSON
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult)]
+#[serde(transparent)]
+pub struct RunIds(pub Vec<String>);
+
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {}
 
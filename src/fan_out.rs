@@ -0,0 +1,202 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use temporal_sdk_core_protos::{
+    coresdk::child_workflow::{child_workflow_result::Status, ChildWorkflowResult},
+    temporal::api::enums::v1::ParentClosePolicy,
+};
+
+use crate::{
+    executions::ExecutionStatus,
+    helpers::parse_activity_result::root_cause_message,
+    registry::registry,
+};
+
+// Enfants lancés en même temps quand l'entrée ne le précise pas
+pub const DEFAULT_FAN_OUT_CONCURRENCY: usize = 10;
+
+// Nombre maximal d'enfants d'un fan-out (chaque enfant ajoute des événements à l'historique du parent)
+pub const MAX_CHILDREN: usize = 1000;
+
+// Sort des enfants quand le parent se termine, s'ils tournent encore
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChildClosePolicy {
+    #[default]
+    Terminate,
+    RequestCancel,
+    Abandon,
+}
+
+impl ChildClosePolicy {
+    pub fn to_proto(self) -> ParentClosePolicy {
+        match self {
+            ChildClosePolicy::Terminate => ParentClosePolicy::Terminate,
+            ChildClosePolicy::RequestCancel => ParentClosePolicy::RequestCancel,
+            ChildClosePolicy::Abandon => ParentClosePolicy::Abandon,
+        }
+    }
+}
+
+// Groupe d'enfants identiques
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChildSpec {
+    pub workflow_type: String,
+    #[serde(default)]
+    pub input: Value,
+    #[serde(default = "default_count")]
+    pub count: usize,
+}
+
+fn default_count() -> usize {
+    1
+}
+
+// Entrée de fan_out_workflow
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FanOutInput {
+    pub children: Vec<ChildSpec>,
+    #[serde(default)]
+    pub concurrency: Option<usize>,
+    #[serde(default)]
+    pub parent_close_policy: ChildClosePolicy,
+}
+
+impl FanOutInput {
+    pub fn concurrency(&self) -> usize {
+        self.concurrency.unwrap_or(DEFAULT_FAN_OUT_CONCURRENCY).max(1)
+    }
+
+    // Liste à plat des enfants, dans l'ordre de lancement
+    pub fn expand(&self) -> Vec<(&str, &Value)> {
+        self.children
+            .iter()
+            .flat_map(|spec| std::iter::repeat_n((spec.workflow_type.as_str(), &spec.input), spec.count))
+            .collect()
+    }
+
+    // Valider les types et entrées des enfants, en complétant les entrées des valeurs par défaut
+    pub fn normalize(mut self) -> Result<Self, String> {
+        let total: usize = self.children.iter().map(|spec| spec.count).sum();
+        if total == 0 {
+            return Err("aucun enfant à lancer".to_string());
+        }
+        if total > MAX_CHILDREN {
+            return Err(format!("{} enfants demandés, {} au plus", total, MAX_CHILDREN));
+        }
+        if self.concurrency == Some(0) {
+            return Err("concurrency doit être au moins 1".to_string());
+        }
+        for spec in &mut self.children {
+            let workflow = registry()
+                .workflow(&spec.workflow_type)
+                .ok_or_else(|| format!("type de workflow inconnu: {}", spec.workflow_type))?;
+            spec.input = workflow.input(Some(spec.input.clone()))?;
+        }
+        Ok(self)
+    }
+}
+
+// Validation utilisée par le registre
+pub fn normalize_fan_out_input(input: Value) -> Result<Value, serde_json::Error> {
+    let fan_out = serde_json::from_value::<FanOutInput>(input)?;
+    serde_json::to_value(fan_out.normalize().map_err(serde::de::Error::custom)?)
+}
+
+// ID d'un enfant: dérivé du parent, stable d'un rejeu à l'autre
+pub fn child_workflow_id(parent_workflow_id: &str, index: usize) -> String {
+    format!("{}-child-{}", parent_workflow_id, index)
+}
+
+// Enfant démarré, enregistré en base sous l'exécution parente
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChildExecution {
+    pub workflow_id: String,
+    pub run_id: String,
+    pub workflow_type: String,
+    pub input: Value,
+}
+
+// Sort d'un enfant dans le résultat du parent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChildOutcome {
+    pub workflow_id: String,
+    pub workflow_type: String,
+    pub run_id: Option<String>,
+    pub status: ExecutionStatus,
+    pub result: Option<Value>,
+    pub error: Option<String>,
+}
+
+// Résultat agrégé de fan_out_workflow
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FanOutResult {
+    pub completed: usize,
+    pub failed: usize,
+    pub canceled: usize,
+    pub children: Vec<ChildOutcome>,
+}
+
+impl FanOutResult {
+    pub fn new(parent_workflow_id: &str, input: &FanOutInput) -> Self {
+        FanOutResult {
+            children: input
+                .expand()
+                .into_iter()
+                .enumerate()
+                .map(|(index, (workflow_type, _))| ChildOutcome {
+                    workflow_id: child_workflow_id(parent_workflow_id, index),
+                    workflow_type: workflow_type.to_string(),
+                    run_id: None,
+                    status: ExecutionStatus::Pending,
+                    result: None,
+                    error: None,
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    pub fn started(&mut self, index: usize, run_id: String) {
+        let child = &mut self.children[index];
+        child.run_id = Some(run_id);
+        child.status = ExecutionStatus::Running;
+    }
+
+    // Enfant impossible à démarrer (ex: ID déjà utilisé)
+    pub fn start_failed(&mut self, index: usize, error: String) {
+        self.children[index].status = ExecutionStatus::Failed;
+        self.children[index].error = Some(error);
+        self.failed += 1;
+    }
+
+    pub fn finish(&mut self, index: usize, result: &ChildWorkflowResult) {
+        let child = &mut self.children[index];
+        match &result.status {
+            Some(Status::Completed(success)) => {
+                child.status = ExecutionStatus::Completed;
+                child.result = Some(
+                    success
+                        .result
+                        .as_ref()
+                        .filter(|payload| !payload.data.is_empty())
+                        .and_then(|payload| serde_json::from_slice(&payload.data).ok())
+                        .unwrap_or(Value::Null),
+                );
+                self.completed += 1;
+            }
+            Some(Status::Cancelled(_)) => {
+                child.status = ExecutionStatus::Canceled;
+                self.canceled += 1;
+            }
+            Some(Status::Failed(failed)) => {
+                child.status = ExecutionStatus::Failed;
+                child.error = failed.failure.as_ref().map(root_cause_message);
+                self.failed += 1;
+            }
+            None => {
+                child.status = ExecutionStatus::Unknown;
+                self.failed += 1;
+            }
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use sea_orm::DatabaseConnection;
+
+use crate::{fan_out::ChildExecution, service::record_child_execution};
+
+// Enregistrement des workflows enfants sous leur parent, en base en production
+// et en mémoire dans les tests
+#[async_trait]
+pub trait ChildStore: Send + Sync {
+    async fn record_child(&self, parent_workflow_id: &str, child: &ChildExecution) -> Result<(), anyhow::Error>;
+}
+
+pub type SharedChildStore = Arc<dyn ChildStore>;
+
+// Enfants écrits dans la table executions, avec parent_id
+pub struct DbChildStore {
+    db: DatabaseConnection,
+}
+
+impl DbChildStore {
+    pub fn new(db: DatabaseConnection) -> Self {
+        DbChildStore { db }
+    }
+}
+
+#[async_trait]
+impl ChildStore for DbChildStore {
+    async fn record_child(&self, parent_workflow_id: &str, child: &ChildExecution) -> Result<(), anyhow::Error> {
+        record_child_execution(&self.db, parent_workflow_id, child).await?;
+        Ok(())
+    }
+}
+
+// Enfants gardés en mémoire, avec le workflow ID de leur parent, pour les tests
+#[derive(Default)]
+pub struct MemoryChildStore {
+    children: Mutex<Vec<(String, ChildExecution)>>,
+}
+
+impl MemoryChildStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn children(&self, parent_workflow_id: &str) -> Vec<ChildExecution> {
+        self.children
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(parent, _)| parent == parent_workflow_id)
+            .map(|(_, child)| child.clone())
+            .collect()
+    }
+}
+
+#[async_trait]
+impl ChildStore for MemoryChildStore {
+    async fn record_child(&self, parent_workflow_id: &str, child: &ChildExecution) -> Result<(), anyhow::Error> {
+        let mut children = self.children.lock().unwrap();
+        // Une nouvelle tentative de l'activité ne doit pas dupliquer l'enfant
+        if !children.iter().any(|(_, recorded)| recorded.workflow_id == child.workflow_id) {
+            children.push((parent_workflow_id.to_string(), child.clone()));
+        }
+        Ok(())
+    }
+}
@@ -1,23 +1,285 @@
+use std::collections::HashMap;
 use std::str::FromStr;
 use std::env;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
 
-use temporal_client::{Client, RetryClient};
+use async_trait::async_trait;
+use temporal_client::{Client, RetryClient, WorkflowClientTrait};
 use temporal_sdk::sdk_client_options;
+use temporal_sdk_core_protos::{
+    coresdk::{AsJsonPayloadExt, FromJsonPayloadExt},
+    temporal::api::{
+        common::v1::{Memo, Payload, Payloads, WorkflowType},
+        enums::v1::{TaskQueueKind, UpdateWorkflowExecutionLifecycleStage, WorkflowIdConflictPolicy, WorkflowIdReusePolicy},
+        schedule::v1::{Schedule, SchedulePatch},
+        taskqueue::v1::TaskQueue,
+        update::v1::{outcome, WaitPolicy},
+        workflowservice::v1::{
+            CreateScheduleRequest, DeleteScheduleRequest, DescribeWorkflowExecutionResponse,
+            GetWorkflowExecutionHistoryResponse, ListSchedulesRequest, ListSchedulesResponse,
+            ListWorkflowExecutionsResponse, PatchScheduleRequest, StartWorkflowExecutionRequest,
+        },
+        workflow::v1::WorkflowExecutionInfo,
+    },
+};
 use url::Url;
 
+// Namespace Temporal utilisé par le client et le worker
+pub const NAMESPACE: &str = "default";
+
+// File de tâches sur laquelle écoute le worker
+pub const TASK_QUEUE: &str = "repeat-task-queue";
+
 pub async fn get_client() -> Result<RetryClient<Client>, anyhow::Error> {
     // Read the Temporal server address from environment variable, with fallback
     let temporal_address = env::var("TEMPORAL_URL")
         .unwrap_or_else(|_| "http://localhost:7233".to_string());
 
+    connect(&temporal_address).await
+}
+
+// Se connecter à un serveur Temporal à une adresse donnée
+pub async fn connect(temporal_address: &str) -> Result<RetryClient<Client>, anyhow::Error> {
     log::info!("🔌 Connecting to Temporal server at {}", temporal_address);
 
     // Parse the address as a URL
-    let url = Url::from_str(&temporal_address)
+    let url = Url::from_str(temporal_address)
         .map_err(|e| anyhow::anyhow!("Invalid TEMPORAL_URL: {}", e))?;
 
     let server_options = sdk_client_options(url).build()?;
-    let client = server_options.connect("default", None).await?;
+    let client = server_options.connect(NAMESPACE, None).await?;
     log::info!("✅ Successfully connected to Temporal server at {}", temporal_address);
     Ok(client)
-}
\ No newline at end of file
+}
+
+// Code gRPC NOT_FOUND
+const GRPC_NOT_FOUND: i32 = 5;
+
+// Workflow ou run inconnu du serveur: jamais démarré, ou supprimé par la rétention du namespace.
+// Les autres erreurs (réseau, indisponibilité) sont considérées transitoires.
+#[derive(Debug)]
+pub struct NotFoundError(pub String);
+
+impl fmt::Display for NotFoundError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "not found: {}", self.0)
+    }
+}
+
+impl std::error::Error for NotFoundError {}
+
+// Indique si l'erreur signale une ressource inexistante côté serveur
+pub fn is_not_found(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<NotFoundError>().is_some()
+}
+
+// Mémo posé sur les workflows démarrés par le service (API et schedules), pour les
+// distinguer des workflows démarrés ailleurs quand la base n'en garde pas trace
+pub const ORIGIN_MEMO_KEY: &str = "origin";
+pub const SERVICE_ORIGIN: &str = "executor";
+
+pub fn origin_memo() -> Memo {
+    let origin = SERVICE_ORIGIN.as_json_payload().expect("a string is always serializable");
+    Memo {
+        fields: HashMap::from([(ORIGIN_MEMO_KEY.to_string(), origin)]),
+    }
+}
+
+// Indique si le workflow a été démarré par le service (voir origin_memo)
+pub fn started_by_service(info: &WorkflowExecutionInfo) -> bool {
+    info.memo
+        .as_ref()
+        .and_then(|memo| memo.fields.get(ORIGIN_MEMO_KEY))
+        .and_then(|origin| String::from_json_payload(origin).ok())
+        .is_some_and(|origin| origin == SERVICE_ORIGIN)
+}
+
+// Valeur littérale d'une requête de visibilité: entre apostrophes, avec les
+// apostrophes et barres obliques inverses échappées
+pub fn visibility_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+// Options de démarrage d'un workflow
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StartOptions {
+    // Délai appliqué par Temporal avant la première tâche du workflow
+    pub start_delay: Option<Duration>,
+    // Comportement si un workflow fermé porte déjà cet ID
+    pub id_reuse_policy: WorkflowIdReusePolicy,
+    // Comportement si un workflow en cours porte déjà cet ID
+    pub id_conflict_policy: WorkflowIdConflictPolicy,
+    // Durée maximale de l'exécution, continue-as-new et retries compris
+    pub execution_timeout: Option<Duration>,
+}
+
+// Résultat d'un update de workflow
+#[derive(Debug, Clone, PartialEq)]
+pub enum UpdateOutcome {
+    // Update accepté et exécuté, avec la valeur retournée par le handler
+    Completed(Option<Payloads>),
+    // Update refusé par le validateur ou en échec dans le handler
+    Rejected(String),
+}
+
+// Opérations Temporal utilisées par le service, le contrôleur et le scheduler.
+// Implémenté pour le vrai client et par `FakeTemporalClient` dans les tests.
+#[async_trait]
+pub trait TemporalClient: Send + Sync {
+    // Démarrer un workflow et retourner son run ID
+    async fn start(&self, workflow_type: String, workflow_id: String, input: Vec<Payload>, options: StartOptions) -> Result<String, anyhow::Error>;
+
+    // Échoue avec NotFoundError si le workflow (ou le run) n'existe pas
+    async fn describe(&self, workflow_id: String, run_id: Option<String>) -> Result<DescribeWorkflowExecutionResponse, anyhow::Error>;
+
+    async fn cancel(&self, workflow_id: String, run_id: Option<String>, reason: String) -> Result<(), anyhow::Error>;
+
+    async fn terminate(&self, workflow_id: String, run_id: Option<String>) -> Result<(), anyhow::Error>;
+
+    async fn signal(&self, workflow_id: String, run_id: String, signal_name: String, input: Option<Payloads>) -> Result<(), anyhow::Error>;
+
+    // Exécuter un update et attendre le résultat du handler
+    async fn update(&self, workflow_id: String, run_id: String, update_name: String, args: Option<Payloads>) -> Result<UpdateOutcome, anyhow::Error>;
+
+    // Lister les workflows via l'API de visibilité (requête au format Temporal)
+    async fn list(&self, query: String, page_token: Vec<u8>) -> Result<ListWorkflowExecutionsResponse, anyhow::Error>;
+
+    async fn history_page(&self, workflow_id: String, run_id: Option<String>, page_token: Vec<u8>) -> Result<GetWorkflowExecutionHistoryResponse, anyhow::Error>;
+
+    async fn create_schedule(&self, schedule_id: String, schedule: Schedule) -> Result<(), anyhow::Error>;
+
+    async fn list_schedules(&self, page_token: Vec<u8>) -> Result<ListSchedulesResponse, anyhow::Error>;
+
+    // Mettre en pause, reprendre ou déclencher immédiatement un schedule
+    async fn patch_schedule(&self, schedule_id: String, patch: SchedulePatch) -> Result<(), anyhow::Error>;
+
+    async fn delete_schedule(&self, schedule_id: String) -> Result<(), anyhow::Error>;
+}
+
+pub type SharedClient = Arc<dyn TemporalClient>;
+
+#[async_trait]
+impl TemporalClient for RetryClient<Client> {
+    async fn start(&self, workflow_type: String, workflow_id: String, input: Vec<Payload>, options: StartOptions) -> Result<String, anyhow::Error> {
+        // Requête brute: le délai de démarrage n'est pas exposé par WorkflowOptions
+        let request = StartWorkflowExecutionRequest {
+            namespace: NAMESPACE.to_string(),
+            workflow_id,
+            workflow_type: Some(WorkflowType { name: workflow_type }),
+            task_queue: Some(TaskQueue {
+                name: TASK_QUEUE.to_string(),
+                kind: TaskQueueKind::Normal as i32,
+                ..Default::default()
+            }),
+            input: Some(Payloads { payloads: input }),
+            memo: Some(origin_memo()),
+            request_id: uuid::Uuid::new_v4().to_string(),
+            workflow_id_reuse_policy: options.id_reuse_policy as i32,
+            workflow_id_conflict_policy: options.id_conflict_policy as i32,
+            workflow_start_delay: options.start_delay.map(|delay| prost_wkt_types::Duration {
+                seconds: delay.as_secs() as i64,
+                nanos: delay.subsec_nanos() as i32,
+            }),
+            workflow_execution_timeout: options.execution_timeout.map(|timeout| prost_wkt_types::Duration {
+                seconds: timeout.as_secs() as i64,
+                nanos: timeout.subsec_nanos() as i32,
+            }),
+            ..Default::default()
+        };
+        let response = temporal_client::WorkflowService::start_workflow_execution(&mut self.clone(), request).await?;
+        Ok(response.into_inner().run_id)
+    }
+
+    async fn describe(&self, workflow_id: String, run_id: Option<String>) -> Result<DescribeWorkflowExecutionResponse, anyhow::Error> {
+        self.describe_workflow_execution(workflow_id, run_id).await.map_err(|status| {
+            if status.code() as i32 == GRPC_NOT_FOUND {
+                NotFoundError(status.message().to_string()).into()
+            } else {
+                anyhow::Error::from(status)
+            }
+        })
+    }
+
+    async fn cancel(&self, workflow_id: String, run_id: Option<String>, reason: String) -> Result<(), anyhow::Error> {
+        self.cancel_workflow_execution(workflow_id, run_id, reason, None).await?;
+        Ok(())
+    }
+
+    async fn terminate(&self, workflow_id: String, run_id: Option<String>) -> Result<(), anyhow::Error> {
+        self.terminate_workflow_execution(workflow_id, run_id).await?;
+        Ok(())
+    }
+
+    async fn signal(&self, workflow_id: String, run_id: String, signal_name: String, input: Option<Payloads>) -> Result<(), anyhow::Error> {
+        self.signal_workflow_execution(workflow_id, run_id, signal_name, input, None).await?;
+        Ok(())
+    }
+
+    async fn update(&self, workflow_id: String, run_id: String, update_name: String, args: Option<Payloads>) -> Result<UpdateOutcome, anyhow::Error> {
+        let wait_policy = WaitPolicy {
+            lifecycle_stage: UpdateWorkflowExecutionLifecycleStage::Completed as i32,
+        };
+        let response = self
+            .update_workflow_execution(workflow_id, run_id, update_name, wait_policy, args)
+            .await?;
+        match response.outcome.and_then(|outcome| outcome.value) {
+            Some(outcome::Value::Success(result)) => Ok(UpdateOutcome::Completed(Some(result))),
+            Some(outcome::Value::Failure(failure)) => Ok(UpdateOutcome::Rejected(failure.message)),
+            None => Err(anyhow::anyhow!("Update finished without outcome")),
+        }
+    }
+
+    async fn list(&self, query: String, page_token: Vec<u8>) -> Result<ListWorkflowExecutionsResponse, anyhow::Error> {
+        Ok(self.list_workflow_executions(100, page_token, query).await?)
+    }
+
+    async fn history_page(&self, workflow_id: String, run_id: Option<String>, page_token: Vec<u8>) -> Result<GetWorkflowExecutionHistoryResponse, anyhow::Error> {
+        Ok(self.get_workflow_execution_history(workflow_id, run_id, page_token).await?)
+    }
+
+    async fn create_schedule(&self, schedule_id: String, schedule: Schedule) -> Result<(), anyhow::Error> {
+        let request = CreateScheduleRequest {
+            namespace: NAMESPACE.to_string(),
+            schedule_id,
+            schedule: Some(schedule),
+            request_id: uuid::Uuid::new_v4().to_string(),
+            ..Default::default()
+        };
+        temporal_client::WorkflowService::create_schedule(&mut self.clone(), request).await?;
+        Ok(())
+    }
+
+    async fn list_schedules(&self, page_token: Vec<u8>) -> Result<ListSchedulesResponse, anyhow::Error> {
+        let request = ListSchedulesRequest {
+            namespace: NAMESPACE.to_string(),
+            maximum_page_size: 100,
+            next_page_token: page_token,
+            ..Default::default()
+        };
+        Ok(temporal_client::WorkflowService::list_schedules(&mut self.clone(), request).await?.into_inner())
+    }
+
+    async fn patch_schedule(&self, schedule_id: String, patch: SchedulePatch) -> Result<(), anyhow::Error> {
+        let request = PatchScheduleRequest {
+            namespace: NAMESPACE.to_string(),
+            schedule_id,
+            patch: Some(patch),
+            request_id: uuid::Uuid::new_v4().to_string(),
+            ..Default::default()
+        };
+        temporal_client::WorkflowService::patch_schedule(&mut self.clone(), request).await?;
+        Ok(())
+    }
+
+    async fn delete_schedule(&self, schedule_id: String) -> Result<(), anyhow::Error> {
+        let request = DeleteScheduleRequest {
+            namespace: NAMESPACE.to_string(),
+            schedule_id,
+            ..Default::default()
+        };
+        temporal_client::WorkflowService::delete_schedule(&mut self.clone(), request).await?;
+        Ok(())
+    }
+}
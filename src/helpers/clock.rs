@@ -0,0 +1,45 @@
+use std::{sync::Mutex, time::Duration};
+
+use async_trait::async_trait;
+
+// Horloge injectable utilisée par les activités pour attendre
+#[async_trait]
+pub trait Clock: Send + Sync {
+    async fn sleep(&self, duration: Duration);
+}
+
+// Horloge réelle, basée sur tokio
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+#[async_trait]
+impl Clock for SystemClock {
+    async fn sleep(&self, duration: Duration) {
+        tokio::time::sleep(duration).await;
+    }
+}
+
+// Horloge de test: chaque attente avance le temps instantanément
+#[derive(Debug, Default)]
+pub struct FakeClock {
+    elapsed: Mutex<Duration>,
+}
+
+impl FakeClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Temps total écoulé selon cette horloge
+    pub fn elapsed(&self) -> Duration {
+        *self.elapsed.lock().unwrap()
+    }
+}
+
+#[async_trait]
+impl Clock for FakeClock {
+    async fn sleep(&self, duration: Duration) {
+        *self.elapsed.lock().unwrap() += duration;
+        tokio::task::yield_now().await;
+    }
+}
@@ -0,0 +1,454 @@
+use std::{collections::HashMap, sync::Mutex, time::SystemTime};
+
+use async_trait::async_trait;
+use prost_wkt_types::Timestamp;
+use temporal_sdk_core_protos::temporal::api::{
+    common::v1::{Memo, Payload, Payloads, WorkerVersionStamp, WorkflowExecution, WorkflowType},
+    enums::v1::{WorkflowExecutionStatus, WorkflowIdConflictPolicy, WorkflowIdReusePolicy},
+    history::v1::History,
+    schedule::v1::{
+        schedule_action, Schedule, ScheduleListEntry, ScheduleListInfo, SchedulePatch,
+    },
+    workflow::v1::WorkflowExecutionInfo,
+    workflowservice::v1::{
+        DescribeWorkflowExecutionResponse, GetWorkflowExecutionHistoryResponse, ListSchedulesResponse,
+        ListWorkflowExecutionsResponse,
+    },
+};
+use uuid::Uuid;
+
+use super::client::{origin_memo, NotFoundError, StartOptions, TemporalClient, UpdateOutcome};
+
+// Workflow tel que connu du faux serveur
+#[derive(Debug, Clone)]
+pub struct FakeWorkflow {
+    pub workflow_type: String,
+    pub run_id: String,
+    // Runs précédents de la chaîne (continue-as-new), du plus ancien au plus récent
+    pub previous_run_ids: Vec<String>,
+    pub input: Vec<Payload>,
+    pub options: StartOptions,
+    // Heure de démarrage effective, décalée du délai de démarrage
+    pub execution_time: Option<Timestamp>,
+    pub status: WorkflowExecutionStatus,
+    pub signals: Vec<(String, Option<Payloads>)>,
+    pub updates: Vec<(String, Option<Payloads>)>,
+    pub history: History,
+    // Workflow parent, pour un workflow enfant
+    pub parent: Option<WorkflowExecution>,
+    // Build ID du dernier worker ayant traité une tâche
+    pub build_id: Option<String>,
+    // Schedule ayant démarré le workflow (attribut TemporalScheduledById)
+    pub scheduled_by: Option<String>,
+    // Mémo du démarrage (origine du workflow)
+    pub memo: Option<Memo>,
+}
+
+// Schedule tel que connu du faux serveur
+#[derive(Debug, Clone)]
+pub struct FakeSchedule {
+    pub schedule: Schedule,
+    pub paused: bool,
+    pub runs: Vec<WorkflowExecution>,
+}
+
+// Client Temporal en mémoire, pour tester le service, le contrôleur et le scheduler
+// sans serveur Temporal.
+#[derive(Default)]
+pub struct FakeTemporalClient {
+    workflows: Mutex<HashMap<String, FakeWorkflow>>,
+    update_outcomes: Mutex<HashMap<String, UpdateOutcome>>,
+    schedules: Mutex<HashMap<String, FakeSchedule>>,
+    list_queries: Mutex<Vec<String>>,
+    describe_calls: Mutex<usize>,
+    unavailable: Mutex<bool>,
+    describe_unavailable: Mutex<bool>,
+}
+
+impl FakeTemporalClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Simuler une indisponibilité du serveur: tous les appels échouent
+    pub fn set_unavailable(&self, unavailable: bool) {
+        *self.unavailable.lock().unwrap() = unavailable;
+    }
+
+    // Simuler une erreur transitoire limitée à describe, les autres appels réussissant
+    pub fn set_describe_unavailable(&self, unavailable: bool) {
+        *self.describe_unavailable.lock().unwrap() = unavailable;
+    }
+
+    // Simuler la suppression d'un workflow par la rétention du namespace
+    pub fn remove_workflow(&self, workflow_id: &str) {
+        self.workflows.lock().unwrap().remove(workflow_id);
+    }
+
+    pub fn set_status(&self, workflow_id: &str, status: WorkflowExecutionStatus) {
+        if let Some(workflow) = self.workflows.lock().unwrap().get_mut(workflow_id) {
+            workflow.status = status;
+        }
+    }
+
+    // Simuler une tâche traitée par un worker de ce build ID
+    pub fn set_build_id(&self, workflow_id: &str, build_id: &str) {
+        if let Some(workflow) = self.workflows.lock().unwrap().get_mut(workflow_id) {
+            workflow.build_id = Some(build_id.to_string());
+        }
+    }
+
+    // Simuler un workflow démarré hors du service (ex: bin/client.rs): pas de mémo d'origine
+    pub fn set_foreign(&self, workflow_id: &str) {
+        if let Some(workflow) = self.workflows.lock().unwrap().get_mut(workflow_id) {
+            workflow.memo = None;
+        }
+    }
+
+    // Simuler un workflow enfant démarré par `parent_workflow_id`
+    pub fn set_parent(&self, workflow_id: &str, parent_workflow_id: &str) {
+        let mut workflows = self.workflows.lock().unwrap();
+        let parent_run_id = workflows.get(parent_workflow_id).map(|parent| parent.run_id.clone()).unwrap_or_default();
+        if let Some(workflow) = workflows.get_mut(workflow_id) {
+            workflow.parent = Some(WorkflowExecution {
+                workflow_id: parent_workflow_id.to_string(),
+                run_id: parent_run_id,
+            });
+        }
+    }
+
+    // Simuler un continue-as-new: le workflow repart sur un nouveau run de la même chaîne
+    pub fn continue_as_new(&self, workflow_id: &str) -> Option<String> {
+        let mut workflows = self.workflows.lock().unwrap();
+        let workflow = workflows.get_mut(workflow_id)?;
+        let run_id = Uuid::new_v4().to_string();
+        let previous = std::mem::replace(&mut workflow.run_id, run_id.clone());
+        workflow.previous_run_ids.push(previous);
+        workflow.status = WorkflowExecutionStatus::Running;
+        Some(run_id)
+    }
+
+    // Simuler l'écoulement du délai de démarrage
+    pub fn set_execution_time(&self, workflow_id: &str, execution_time: Timestamp) {
+        if let Some(workflow) = self.workflows.lock().unwrap().get_mut(workflow_id) {
+            workflow.execution_time = Some(execution_time);
+        }
+    }
+
+    pub fn set_history(&self, workflow_id: &str, history: History) {
+        if let Some(workflow) = self.workflows.lock().unwrap().get_mut(workflow_id) {
+            workflow.history = history;
+        }
+    }
+
+    // Résultat renvoyé pour un update donné, quel que soit le workflow
+    pub fn set_update_outcome(&self, update_name: &str, outcome: UpdateOutcome) {
+        self.update_outcomes.lock().unwrap().insert(update_name.to_string(), outcome);
+    }
+
+    // Enregistrer un workflow démarré en dehors du service
+    pub fn insert_workflow(&self, workflow_id: &str, workflow: FakeWorkflow) {
+        self.workflows.lock().unwrap().insert(workflow_id.to_string(), workflow);
+    }
+
+    pub fn workflow(&self, workflow_id: &str) -> Option<FakeWorkflow> {
+        self.workflows.lock().unwrap().get(workflow_id).cloned()
+    }
+
+    pub fn schedule(&self, schedule_id: &str) -> Option<FakeSchedule> {
+        self.schedules.lock().unwrap().get(schedule_id).cloned()
+    }
+
+    // Nombre d'appels à `describe`
+    pub fn describe_calls(&self) -> usize {
+        *self.describe_calls.lock().unwrap()
+    }
+
+    // Requêtes de visibilité reçues par `list`, dans l'ordre
+    pub fn list_queries(&self) -> Vec<String> {
+        self.list_queries.lock().unwrap().clone()
+    }
+
+    pub fn workflow_count(&self) -> usize {
+        self.workflows.lock().unwrap().len()
+    }
+
+    fn check_available(&self) -> Result<(), anyhow::Error> {
+        if *self.unavailable.lock().unwrap() {
+            return Err(anyhow::anyhow!("Temporal server unavailable"));
+        }
+        Ok(())
+    }
+
+    fn with_workflow<T>(
+        &self,
+        workflow_id: &str,
+        run_id: Option<&str>,
+        f: impl FnOnce(&mut FakeWorkflow) -> T,
+    ) -> Result<T, anyhow::Error> {
+        self.check_available()?;
+        let mut workflows = self.workflows.lock().unwrap();
+        match workflows.get_mut(workflow_id) {
+            Some(workflow) if run_id.is_none_or(|run_id| run_id == workflow.run_id) => Ok(f(workflow)),
+            _ => Err(NotFoundError(format!("Workflow {}", workflow_id)).into()),
+        }
+    }
+
+    // Démarrer l'action d'un schedule, comme le ferait le serveur à l'échéance
+    fn run_schedule_action(&self, schedule_id: &str) -> Result<(), anyhow::Error> {
+        let mut schedules = self.schedules.lock().unwrap();
+        let schedule = schedules
+            .get_mut(schedule_id)
+            .ok_or_else(|| anyhow::anyhow!("Schedule {} not found", schedule_id))?;
+        let Some(schedule_action::Action::StartWorkflow(action)) =
+            schedule.schedule.action.as_ref().and_then(|action| action.action.clone())
+        else {
+            return Err(anyhow::anyhow!("Schedule {} has no workflow action", schedule_id));
+        };
+
+        let workflow_id = format!("{}-{}", action.workflow_id, schedule.runs.len() + 1);
+        let run_id = Uuid::new_v4().to_string();
+        self.workflows.lock().unwrap().insert(
+            workflow_id.clone(),
+            FakeWorkflow {
+                workflow_type: action.workflow_type.map(|t| t.name).unwrap_or_default(),
+                run_id: run_id.clone(),
+                previous_run_ids: vec![],
+                input: action.input.map(|input| input.payloads).unwrap_or_default(),
+                options: StartOptions::default(),
+                execution_time: Some(Timestamp::from(SystemTime::now())),
+                status: WorkflowExecutionStatus::Running,
+                signals: vec![],
+                updates: vec![],
+                history: History::default(),
+                parent: None,
+                build_id: None,
+                scheduled_by: Some(schedule_id.to_string()),
+                memo: action.memo,
+            },
+        );
+        schedule.runs.push(WorkflowExecution { workflow_id, run_id });
+        Ok(())
+    }
+
+    fn execution_info(workflow_id: &str, workflow: &FakeWorkflow) -> WorkflowExecutionInfo {
+        WorkflowExecutionInfo {
+            execution: Some(WorkflowExecution {
+                workflow_id: workflow_id.to_string(),
+                run_id: workflow.run_id.clone(),
+            }),
+            r#type: Some(WorkflowType {
+                name: workflow.workflow_type.clone(),
+            }),
+            status: workflow.status as i32,
+            execution_time: workflow.execution_time.clone(),
+            first_run_id: workflow.previous_run_ids.first().unwrap_or(&workflow.run_id).clone(),
+            parent_execution: workflow.parent.clone(),
+            memo: workflow.memo.clone(),
+            most_recent_worker_version_stamp: workflow.build_id.clone().map(|build_id| WorkerVersionStamp {
+                build_id,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+}
+
+#[async_trait]
+impl TemporalClient for FakeTemporalClient {
+    async fn start(&self, workflow_type: String, workflow_id: String, input: Vec<Payload>, options: StartOptions) -> Result<String, anyhow::Error> {
+        self.check_available()?;
+        let mut workflows = self.workflows.lock().unwrap();
+        if let Some(existing) = workflows.get(&workflow_id) {
+            let running = existing.status == WorkflowExecutionStatus::Running;
+            if running && options.id_conflict_policy == WorkflowIdConflictPolicy::UseExisting {
+                return Ok(existing.run_id.clone());
+            }
+            if running || options.id_reuse_policy == WorkflowIdReusePolicy::RejectDuplicate {
+                return Err(anyhow::anyhow!("Workflow execution already started: {}", workflow_id));
+            }
+        }
+
+        let run_id = Uuid::new_v4().to_string();
+        let execution_time = SystemTime::now() + options.start_delay.unwrap_or_default();
+        workflows.insert(
+            workflow_id,
+            FakeWorkflow {
+                workflow_type,
+                run_id: run_id.clone(),
+                previous_run_ids: vec![],
+                input,
+                options,
+                execution_time: Some(Timestamp::from(execution_time)),
+                status: WorkflowExecutionStatus::Running,
+                signals: vec![],
+                updates: vec![],
+                history: History::default(),
+                parent: None,
+                build_id: None,
+                scheduled_by: None,
+                memo: Some(origin_memo()),
+            },
+        );
+        Ok(run_id)
+    }
+
+    async fn describe(&self, workflow_id: String, run_id: Option<String>) -> Result<DescribeWorkflowExecutionResponse, anyhow::Error> {
+        *self.describe_calls.lock().unwrap() += 1;
+        if *self.describe_unavailable.lock().unwrap() {
+            return Err(anyhow::anyhow!("Temporal server unavailable"));
+        }
+        // Run précédent de la chaîne: clos par continue-as-new
+        let previous = self
+            .workflow(&workflow_id)
+            .zip(run_id.as_ref())
+            .filter(|(workflow, run_id)| workflow.previous_run_ids.contains(run_id));
+        if let Some((workflow, run_id)) = previous {
+            self.check_available()?;
+            let mut info = Self::execution_info(&workflow_id, &workflow);
+            info.execution = Some(WorkflowExecution { workflow_id, run_id: run_id.clone() });
+            info.status = WorkflowExecutionStatus::ContinuedAsNew as i32;
+            return Ok(DescribeWorkflowExecutionResponse {
+                workflow_execution_info: Some(info),
+                ..Default::default()
+            });
+        }
+        self.with_workflow(&workflow_id, run_id.as_deref(), |workflow| DescribeWorkflowExecutionResponse {
+            workflow_execution_info: Some(Self::execution_info(&workflow_id, workflow)),
+            ..Default::default()
+        })
+    }
+
+    async fn cancel(&self, workflow_id: String, run_id: Option<String>, _reason: String) -> Result<(), anyhow::Error> {
+        self.with_workflow(&workflow_id, run_id.as_deref(), |workflow| {
+            workflow.status = WorkflowExecutionStatus::Canceled;
+        })
+    }
+
+    async fn terminate(&self, workflow_id: String, run_id: Option<String>) -> Result<(), anyhow::Error> {
+        self.with_workflow(&workflow_id, run_id.as_deref(), |workflow| {
+            workflow.status = WorkflowExecutionStatus::Terminated;
+        })
+    }
+
+    async fn signal(&self, workflow_id: String, run_id: String, signal_name: String, input: Option<Payloads>) -> Result<(), anyhow::Error> {
+        self.with_workflow(&workflow_id, Some(&run_id), |workflow| {
+            workflow.signals.push((signal_name, input));
+        })
+    }
+
+    async fn update(&self, workflow_id: String, run_id: String, update_name: String, args: Option<Payloads>) -> Result<UpdateOutcome, anyhow::Error> {
+        let outcome = self
+            .update_outcomes
+            .lock()
+            .unwrap()
+            .get(&update_name)
+            .cloned()
+            .unwrap_or_else(|| UpdateOutcome::Rejected(format!("Unknown update: {}", update_name)));
+        self.with_workflow(&workflow_id, Some(&run_id), |workflow| {
+            workflow.updates.push((update_name, args));
+        })?;
+        Ok(outcome)
+    }
+
+    async fn list(&self, query: String, _page_token: Vec<u8>) -> Result<ListWorkflowExecutionsResponse, anyhow::Error> {
+        self.check_available()?;
+        // Seul le filtre sur le schedule est appliqué, les autres conditions sont ignorées
+        let scheduled_by = query_literal(&query, "TemporalScheduledById");
+        self.list_queries.lock().unwrap().push(query);
+        let workflows = self.workflows.lock().unwrap();
+        let mut executions: Vec<WorkflowExecutionInfo> = workflows
+            .iter()
+            .filter(|(_, workflow)| scheduled_by.is_none() || workflow.scheduled_by == scheduled_by)
+            .map(|(workflow_id, workflow)| Self::execution_info(workflow_id, workflow))
+            .collect();
+        executions.sort_by(|a, b| a.execution.as_ref().map(|e| &e.workflow_id).cmp(&b.execution.as_ref().map(|e| &e.workflow_id)));
+        Ok(ListWorkflowExecutionsResponse {
+            executions,
+            next_page_token: vec![],
+        })
+    }
+
+    async fn history_page(&self, workflow_id: String, run_id: Option<String>, _page_token: Vec<u8>) -> Result<GetWorkflowExecutionHistoryResponse, anyhow::Error> {
+        self.with_workflow(&workflow_id, run_id.as_deref(), |workflow| GetWorkflowExecutionHistoryResponse {
+            history: Some(workflow.history.clone()),
+            ..Default::default()
+        })
+    }
+
+    async fn create_schedule(&self, schedule_id: String, schedule: Schedule) -> Result<(), anyhow::Error> {
+        self.check_available()?;
+        let mut schedules = self.schedules.lock().unwrap();
+        if schedules.contains_key(&schedule_id) {
+            return Err(anyhow::anyhow!("Schedule {} already exists", schedule_id));
+        }
+        let paused = schedule.state.as_ref().is_some_and(|state| state.paused);
+        schedules.insert(schedule_id, FakeSchedule { schedule, paused, runs: vec![] });
+        Ok(())
+    }
+
+    async fn list_schedules(&self, _page_token: Vec<u8>) -> Result<ListSchedulesResponse, anyhow::Error> {
+        self.check_available()?;
+        let schedules = self.schedules.lock().unwrap();
+        let mut entries: Vec<ScheduleListEntry> = schedules
+            .iter()
+            .map(|(schedule_id, schedule)| ScheduleListEntry {
+                schedule_id: schedule_id.clone(),
+                info: Some(ScheduleListInfo {
+                    spec: schedule.schedule.spec.clone(),
+                    paused: schedule.paused,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .collect();
+        entries.sort_by(|a, b| a.schedule_id.cmp(&b.schedule_id));
+        Ok(ListSchedulesResponse {
+            schedules: entries,
+            next_page_token: vec![],
+        })
+    }
+
+    async fn patch_schedule(&self, schedule_id: String, patch: SchedulePatch) -> Result<(), anyhow::Error> {
+        self.check_available()?;
+        {
+            let mut schedules = self.schedules.lock().unwrap();
+            let schedule = schedules
+                .get_mut(&schedule_id)
+                .ok_or_else(|| anyhow::anyhow!("Schedule {} not found", schedule_id))?;
+            if !patch.pause.is_empty() {
+                schedule.paused = true;
+            }
+            if !patch.unpause.is_empty() {
+                schedule.paused = false;
+            }
+        }
+        if patch.trigger_immediately.is_some() {
+            self.run_schedule_action(&schedule_id)?;
+        }
+        Ok(())
+    }
+
+    async fn delete_schedule(&self, schedule_id: String) -> Result<(), anyhow::Error> {
+        self.check_available()?;
+        match self.schedules.lock().unwrap().remove(&schedule_id) {
+            Some(_) => Ok(()),
+            None => Err(anyhow::anyhow!("Schedule {} not found", schedule_id)),
+        }
+    }
+}
+
+// Valeur comparée à `attribute` dans une requête de visibilité (`attribute = '...'`)
+fn query_literal(query: &str, attribute: &str) -> Option<String> {
+    let rest = query.split(&format!("{} = '", attribute)).nth(1)?;
+    let mut value = String::new();
+    let mut chars = rest.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => value.push(chars.next()?),
+            '\'' => return Some(value),
+            c => value.push(c),
+        }
+    }
+    None
+}
@@ -0,0 +1,121 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, Set};
+use serde::{Deserialize, Serialize};
+
+use crate::command_logs;
+
+// Flux de sortie d'une commande
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+}
+
+impl LogStream {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogStream::Stdout => "stdout",
+            LogStream::Stderr => "stderr",
+        }
+    }
+}
+
+// Activité à laquelle une ligne de sortie est rattachée
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LogTarget {
+    pub workflow_id: String,
+    pub run_id: String,
+    pub activity_id: String,
+}
+
+// Ligne de sortie conservée pour l'API
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LogLine {
+    pub target: LogTarget,
+    pub stream: LogStream,
+    pub line: String,
+    pub logged_at: DateTime<Utc>,
+}
+
+// Stockage des sorties de commandes, en base en production et en mémoire dans les tests
+#[async_trait]
+pub trait LogStore: Send + Sync {
+    async fn append(&self, line: LogLine) -> Result<(), anyhow::Error>;
+
+    // Plusieurs lignes d'un coup, dans l'ordre: une seule écriture quand le stockage le permet
+    async fn append_batch(&self, lines: Vec<LogLine>) -> Result<(), anyhow::Error> {
+        for line in lines {
+            self.append(line).await?;
+        }
+        Ok(())
+    }
+}
+
+pub type SharedLogStore = Arc<dyn LogStore>;
+
+// Logs écrits dans la table command_logs
+pub struct DbLogStore {
+    db: DatabaseConnection,
+}
+
+impl DbLogStore {
+    pub fn new(db: DatabaseConnection) -> Self {
+        DbLogStore { db }
+    }
+}
+
+fn log_model(line: LogLine) -> command_logs::ActiveModel {
+    command_logs::ActiveModel {
+        workflow_id: Set(line.target.workflow_id),
+        run_id: Set(line.target.run_id),
+        activity_id: Set(line.target.activity_id),
+        stream: Set(line.stream.as_str().to_string()),
+        line: Set(line.line),
+        logged_at: Set(line.logged_at),
+        ..Default::default()
+    }
+}
+
+#[async_trait]
+impl LogStore for DbLogStore {
+    async fn append(&self, line: LogLine) -> Result<(), anyhow::Error> {
+        log_model(line).insert(&self.db).await?;
+        Ok(())
+    }
+
+    async fn append_batch(&self, lines: Vec<LogLine>) -> Result<(), anyhow::Error> {
+        if lines.is_empty() {
+            return Ok(());
+        }
+        command_logs::Entity::insert_many(lines.into_iter().map(log_model)).exec(&self.db).await?;
+        Ok(())
+    }
+}
+
+// Logs gardés en mémoire, pour les tests
+#[derive(Default)]
+pub struct MemoryLogStore {
+    lines: Mutex<Vec<LogLine>>,
+}
+
+impl MemoryLogStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn lines(&self) -> Vec<LogLine> {
+        self.lines.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl LogStore for MemoryLogStore {
+    async fn append(&self, line: LogLine) -> Result<(), anyhow::Error> {
+        self.lines.lock().unwrap().push(line);
+        Ok(())
+    }
+}
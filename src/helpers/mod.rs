@@ -1,2 +1,10 @@
 pub mod client;
-pub mod parse_activity_result;
\ No newline at end of file
+pub mod child_store;
+pub mod clock;
+pub mod fake_client;
+pub mod log_store;
+pub mod parse_activity_result;
+pub mod replay;
+pub mod status_tracker;
+pub mod step_store;
+pub mod workflow_state;
\ No newline at end of file
@@ -1,7 +1,8 @@
 use log::debug;
 use temporal_sdk_core::protos::coresdk::activity_result::{
-    activity_resolution::Status::Completed, ActivityResolution,
+    activity_resolution::Status::{Completed, Failed}, ActivityResolution,
 };
+use temporal_sdk_core_protos::temporal::api::failure::v1::Failure;
 use serde::de::DeserializeOwned;
 
 pub fn parse_activity_result<T>(result: &ActivityResolution) -> Result<T, anyhow::Error>
@@ -23,5 +24,19 @@ where
         }
     }
 
+    // Remonter le message de l'erreur d'origine (ex: "HTTP 404") plutôt que l'enveloppe Temporal
+    if let Some(Failed(failed)) = &result.status {
+        if let Some(message) = failed.failure.as_ref().map(root_cause_message).filter(|message| !message.is_empty()) {
+            return Err(anyhow::anyhow!("Activity failed: {}", message));
+        }
+    }
+
     Err(anyhow::anyhow!("Activity failed"))
 }
+
+pub fn root_cause_message(failure: &Failure) -> String {
+    match &failure.cause {
+        Some(cause) => root_cause_message(cause),
+        None => failure.message.clone(),
+    }
+}
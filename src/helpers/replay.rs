@@ -0,0 +1,85 @@
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use futures::stream;
+use temporal_sdk::{interceptors::WorkerInterceptor, Worker};
+use temporal_sdk_core::{init_replay_worker, replay::HistoryForReplay, ReplayWorkerInput};
+use temporal_sdk_core_api::worker::{WorkerConfigBuilder, WorkerVersioningStrategy};
+use temporal_sdk_core_protos::{
+    coresdk::workflow_completion::{workflow_activation_completion, Failure, WorkflowActivationCompletion},
+    temporal::api::{enums::v1::WorkflowTaskFailedCause, history::v1::History},
+};
+
+use crate::registry::registry;
+
+const REPLAY_TASK_QUEUE: &str = "replay-task-queue";
+
+// Charger un historique exporté via GET /executions/{id}/history?download=true
+pub fn load_history_fixture(path: &Path) -> Result<History, anyhow::Error> {
+    let content = std::fs::read_to_string(path)?;
+    serde_json::from_str(&content)
+        .map_err(|e| anyhow::anyhow!("Invalid history fixture {}: {}", path.display(), e))
+}
+
+// Intercepteur qui relève les échecs de tâche dus au non-déterminisme
+#[derive(Clone, Default)]
+struct NondeterminismCollector {
+    failures: Arc<Mutex<Vec<String>>>,
+}
+
+#[async_trait::async_trait(?Send)]
+impl WorkerInterceptor for NondeterminismCollector {
+    async fn on_workflow_activation_completion(&self, completion: &WorkflowActivationCompletion) {
+        if let Some(workflow_activation_completion::Status::Failed(Failure { failure, force_cause })) =
+            &completion.status
+        {
+            if *force_cause == WorkflowTaskFailedCause::NonDeterministicError as i32 {
+                let message = failure
+                    .as_ref()
+                    .map(|f| f.message.clone())
+                    .unwrap_or_default();
+                self.failures
+                    .lock()
+                    .expect("nondeterminism collector poisoned")
+                    .push(format!("run {}: {}", completion.run_id, message));
+            }
+        }
+    }
+
+    fn on_shutdown(&self, _sdk_worker: &Worker) {}
+}
+
+// Rejouer des historiques contre le code actuel des workflows.
+// Échoue si l'un d'eux provoque une erreur de non-déterminisme.
+pub async fn replay_histories(histories: Vec<(String, History)>) -> Result<(), anyhow::Error> {
+    let worker_config = WorkerConfigBuilder::default()
+        .namespace("default")
+        .task_queue(REPLAY_TASK_QUEUE)
+        .versioning_strategy(WorkerVersioningStrategy::default())
+        .build()?;
+
+    let histories = histories
+        .into_iter()
+        .map(|(workflow_id, history)| HistoryForReplay::new(history, workflow_id));
+    let core_worker = init_replay_worker(ReplayWorkerInput::new(worker_config, stream::iter(histories)))?;
+
+    let collector = NondeterminismCollector::default();
+    let mut worker = Worker::new_from_core(Arc::new(core_worker), REPLAY_TASK_QUEUE);
+    for workflow in registry().workflows() {
+        (workflow.register)(&mut worker);
+    }
+    worker.set_worker_interceptor(collector.clone());
+    worker.run().await?;
+
+    let failures = collector
+        .failures
+        .lock()
+        .expect("nondeterminism collector poisoned");
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!("Nondeterminism detected during replay: {}", failures.join("; ")))
+    }
+}
@@ -0,0 +1,152 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::leader::instance_id;
+
+// Premier délai d'attente après une erreur transitoire, doublé à chaque échec
+const INITIAL_BACKOFF: Duration = Duration::from_secs(5);
+
+// Délai d'attente maximal entre deux tentatives sur une même exécution
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+// Compteurs de la synchronisation des statuts, exposés par /admin/scheduler
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct StatusSyncCounters {
+    // Exécutions dont le statut a été synchronisé
+    pub updated: u64,
+    // Exécutions passées en LOST (workflow introuvable)
+    pub lost: u64,
+    // Erreurs transitoires (réseau, serveur indisponible)
+    pub transient_errors: u64,
+    // Exécutions ignorées car en attente de leur prochaine tentative
+    pub backed_off: u64,
+    // Exécutions actuellement en attente
+    pub rows_in_backoff: usize,
+    // Passages abandonnés car le précédent n'était pas terminé
+    pub overlapping_passes: u64,
+    // Cette instance détenait le bail du scheduler au dernier tick
+    pub is_leader: bool,
+}
+
+struct RowBackoff {
+    failures: u32,
+    retry_at: Instant,
+}
+
+#[derive(Default)]
+struct TrackerState {
+    counters: StatusSyncCounters,
+    backoff: HashMap<Uuid, RowBackoff>,
+    pass_running: bool,
+}
+
+// Passage de synchronisation en cours, libéré à la destruction
+pub struct PassGuard<'a> {
+    tracker: &'a StatusTracker,
+}
+
+impl Drop for PassGuard<'_> {
+    fn drop(&mut self) {
+        self.tracker.state.lock().unwrap().pass_running = false;
+    }
+}
+
+// Suivi des tentatives de synchronisation: attente exponentielle par exécution
+// après une erreur transitoire, et compteurs de chaque cas
+pub struct StatusTracker {
+    // Identifiant de l'instance, détenteur du bail du scheduler lorsqu'elle est leader
+    instance_id: String,
+    state: Mutex<TrackerState>,
+}
+
+pub type SharedStatusTracker = Arc<StatusTracker>;
+
+impl Default for StatusTracker {
+    fn default() -> Self {
+        Self::with_instance_id(instance_id())
+    }
+}
+
+impl StatusTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_instance_id(instance_id: String) -> Self {
+        Self { instance_id, state: Mutex::default() }
+    }
+
+    pub fn instance_id(&self) -> &str {
+        &self.instance_id
+    }
+
+    pub fn is_leader(&self) -> bool {
+        self.state.lock().unwrap().counters.is_leader
+    }
+
+    pub fn set_leader(&self, is_leader: bool) {
+        self.state.lock().unwrap().counters.is_leader = is_leader;
+    }
+
+    pub fn snapshot(&self) -> StatusSyncCounters {
+        let state = self.state.lock().unwrap();
+        StatusSyncCounters {
+            rows_in_backoff: state.backoff.len(),
+            ..state.counters.clone()
+        }
+    }
+
+    // Démarrer un passage, ou renvoyer None si un passage est déjà en cours
+    pub fn try_begin_pass(&self) -> Option<PassGuard<'_>> {
+        let mut state = self.state.lock().unwrap();
+        if state.pass_running {
+            state.counters.overlapping_passes += 1;
+            return None;
+        }
+        state.pass_running = true;
+        Some(PassGuard { tracker: self })
+    }
+
+    // Exécutions dont la prochaine tentative n'est pas encore due, à exclure de ce passage
+    pub fn backed_off_ids(&self, now: Instant) -> Vec<Uuid> {
+        let mut state = self.state.lock().unwrap();
+        let ids: Vec<Uuid> = state
+            .backoff
+            .iter()
+            .filter(|(_, backoff)| backoff.retry_at > now)
+            .map(|(id, _)| *id)
+            .collect();
+        state.counters.backed_off += ids.len() as u64;
+        ids
+    }
+
+    pub fn record_updated(&self, id: Uuid) {
+        let mut state = self.state.lock().unwrap();
+        state.backoff.remove(&id);
+        state.counters.updated += 1;
+    }
+
+    pub fn record_lost(&self, id: Uuid) {
+        let mut state = self.state.lock().unwrap();
+        state.backoff.remove(&id);
+        state.counters.lost += 1;
+    }
+
+    // Enregistrer une erreur transitoire et renvoyer le délai avant la prochaine tentative
+    pub fn record_transient(&self, id: Uuid, now: Instant) -> Duration {
+        let mut state = self.state.lock().unwrap();
+        state.counters.transient_errors += 1;
+        let failures = state.backoff.get(&id).map_or(1, |backoff| backoff.failures + 1);
+        let delay = INITIAL_BACKOFF
+            .saturating_mul(2u32.saturating_pow(failures - 1))
+            .min(MAX_BACKOFF);
+        state.backoff.insert(id, RowBackoff { failures, retry_at: now + delay });
+        delay
+    }
+}
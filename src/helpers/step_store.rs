@@ -0,0 +1,139 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::{sea_query::OnConflict, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+
+use crate::{
+    execution_steps::{self, Entity as ExecutionStep, StepIds},
+    executions::{self, Entity as Execution},
+    pipeline::{CompensationState, StepState},
+};
+
+// Stockage de l'état des pipelines (étapes et compensation), en base en production
+// et en mémoire dans les tests
+#[async_trait]
+pub trait StepStore: Send + Sync {
+    // Créer ou mettre à jour les étapes d'un run
+    async fn save(&self, workflow_id: &str, run_id: &str, steps: &[StepState]) -> Result<(), anyhow::Error>;
+
+    // Enregistrer l'avancement de la compensation sur l'exécution du workflow
+    async fn save_compensation(&self, workflow_id: &str, compensation: &CompensationState) -> Result<(), anyhow::Error>;
+}
+
+pub type SharedStepStore = Arc<dyn StepStore>;
+
+// Étapes écrites dans la table execution_steps
+pub struct DbStepStore {
+    db: DatabaseConnection,
+}
+
+impl DbStepStore {
+    pub fn new(db: DatabaseConnection) -> Self {
+        DbStepStore { db }
+    }
+}
+
+#[async_trait]
+impl StepStore for DbStepStore {
+    async fn save(&self, workflow_id: &str, run_id: &str, steps: &[StepState]) -> Result<(), anyhow::Error> {
+        if steps.is_empty() {
+            return Ok(());
+        }
+        let now = Utc::now();
+        let rows = steps.iter().map(|step| execution_steps::ActiveModel {
+            workflow_id: Set(workflow_id.to_string()),
+            run_id: Set(run_id.to_string()),
+            step_id: Set(step.step_id.clone()),
+            activity_type: Set(step.activity_type.clone()),
+            depends_on: Set(StepIds(step.depends_on.clone())),
+            status: Set(step.status),
+            result: Set(step.result.clone()),
+            error: Set(step.error.clone()),
+            started_at: Set(step.started_at),
+            finished_at: Set(step.finished_at),
+            updated_at: Set(now),
+            ..Default::default()
+        });
+
+        ExecutionStep::insert_many(rows)
+            .on_conflict(
+                OnConflict::columns([
+                    execution_steps::Column::WorkflowId,
+                    execution_steps::Column::RunId,
+                    execution_steps::Column::StepId,
+                ])
+                .update_columns([
+                    execution_steps::Column::Status,
+                    execution_steps::Column::Result,
+                    execution_steps::Column::Error,
+                    execution_steps::Column::StartedAt,
+                    execution_steps::Column::FinishedAt,
+                    execution_steps::Column::UpdatedAt,
+                ])
+                .to_owned(),
+            )
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn save_compensation(&self, workflow_id: &str, compensation: &CompensationState) -> Result<(), anyhow::Error> {
+        Execution::update_many()
+            .set(executions::ActiveModel {
+                compensation: Set(Some(serde_json::to_value(compensation)?)),
+                ..Default::default()
+            })
+            .filter(executions::Column::WorkflowId.eq(workflow_id))
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+}
+
+// Étapes gardées en mémoire, par (workflow_id, run_id), pour les tests
+#[derive(Default)]
+pub struct MemoryStepStore {
+    runs: Mutex<HashMap<(String, String), Vec<StepState>>>,
+    compensations: Mutex<HashMap<String, CompensationState>>,
+}
+
+impl MemoryStepStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn steps(&self, workflow_id: &str, run_id: &str) -> Vec<StepState> {
+        self.runs
+            .lock()
+            .unwrap()
+            .get(&(workflow_id.to_string(), run_id.to_string()))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn compensation(&self, workflow_id: &str) -> Option<CompensationState> {
+        self.compensations.lock().unwrap().get(workflow_id).cloned()
+    }
+}
+
+#[async_trait]
+impl StepStore for MemoryStepStore {
+    async fn save(&self, workflow_id: &str, run_id: &str, steps: &[StepState]) -> Result<(), anyhow::Error> {
+        self.runs
+            .lock()
+            .unwrap()
+            .insert((workflow_id.to_string(), run_id.to_string()), steps.to_vec());
+        Ok(())
+    }
+    async fn save_compensation(&self, workflow_id: &str, compensation: &CompensationState) -> Result<(), anyhow::Error> {
+        self.compensations
+            .lock()
+            .unwrap()
+            .insert(workflow_id.to_string(), compensation.clone());
+        Ok(())
+    }
+}
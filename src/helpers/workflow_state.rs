@@ -0,0 +1,82 @@
+use std::{collections::HashMap, time::Duration};
+
+use temporal_sdk_core_protos::temporal::api::history::v1::{history_event::Attributes, History};
+
+use crate::workflows::{
+    ExtendSignal, ParametersUpdate, RepeatInput, RepeatPhase, RepeatState, EXTEND_SIGNAL, PAUSE_SIGNAL,
+    RESUME_SIGNAL, UPDATE_PARAMETERS,
+};
+
+// Reconstruire l'état de repeat_workflow à partir de son historique, pour les runs en cours
+// comme pour les runs terminés (GET /executions/{id}/state).
+pub fn repeat_state_from_history(history: &History) -> RepeatState {
+    let mut state = RepeatState::default();
+    // timer_id -> (durée prévue, heure de démarrage en secondes)
+    let mut timers: HashMap<String, (Duration, i64)> = HashMap::new();
+
+    for event in &history.events {
+        let event_secs = event.event_time.as_ref().map(|t| t.seconds).unwrap_or_default();
+        match &event.attributes {
+            Some(Attributes::WorkflowExecutionStartedEventAttributes(attrs)) => {
+                let input = attrs.input.as_ref().and_then(|input| input.payloads.first());
+                state = RepeatState::new(RepeatInput::from_payload(input));
+            }
+            Some(Attributes::TimerStartedEventAttributes(attrs)) => {
+                let duration = attrs
+                    .start_to_fire_timeout
+                    .as_ref()
+                    .map(|d| Duration::from_secs(d.seconds.max(0) as u64))
+                    .unwrap_or_default();
+                timers.insert(attrs.timer_id.clone(), (duration, event_secs));
+            }
+            Some(Attributes::TimerFiredEventAttributes(attrs)) => {
+                if let Some((duration, _)) = timers.remove(&attrs.timer_id) {
+                    state.advance(duration);
+                }
+            }
+            Some(Attributes::TimerCanceledEventAttributes(attrs)) => {
+                // Pause en cours de pas: seule la partie écoulée compte
+                if let Some((duration, started_secs)) = timers.remove(&attrs.timer_id) {
+                    let partial = Duration::from_secs((event_secs - started_secs).max(0) as u64);
+                    state.advance(partial.min(duration));
+                }
+            }
+            Some(Attributes::WorkflowExecutionSignaledEventAttributes(attrs)) => {
+                let signal_name = attrs.signal_name.as_str();
+                state.record_signal(signal_name);
+                match signal_name {
+                    PAUSE_SIGNAL => state.phase = RepeatPhase::Paused,
+                    RESUME_SIGNAL => state.phase = RepeatPhase::Running,
+                    EXTEND_SIGNAL => {
+                        let payload = attrs.input.as_ref().and_then(|input| input.payloads.first());
+                        if let Some(extend) = ExtendSignal::from_payload(payload) {
+                            state.extend(Duration::from_secs(extend.seconds));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Some(Attributes::WorkflowExecutionUpdateAcceptedEventAttributes(attrs)) => {
+                // Un update accepté a passé la validation: il est appliqué tel quel
+                let input = attrs.accepted_request.as_ref().and_then(|request| request.input.as_ref());
+                if let Some(input) = input.filter(|input| input.name == UPDATE_PARAMETERS) {
+                    let payload = input.args.as_ref().and_then(|args| args.payloads.first());
+                    if let Some(update) = ParametersUpdate::from_payload(payload) {
+                        state.apply_update(&update);
+                    }
+                }
+            }
+            Some(Attributes::WorkflowExecutionCompletedEventAttributes(_)) => {
+                state.phase = RepeatPhase::Completed;
+                state.advance(state.total());
+            }
+            Some(Attributes::WorkflowExecutionCanceledEventAttributes(_)) => state.phase = RepeatPhase::Cancelled,
+            Some(Attributes::WorkflowExecutionFailedEventAttributes(_)) => state.phase = RepeatPhase::Failed,
+            Some(Attributes::WorkflowExecutionTerminatedEventAttributes(_)) => state.phase = RepeatPhase::Terminated,
+            Some(Attributes::WorkflowExecutionTimedOutEventAttributes(_)) => state.phase = RepeatPhase::TimedOut,
+            _ => {}
+        }
+    }
+
+    state
+}
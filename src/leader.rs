@@ -0,0 +1,69 @@
+use std::{env, time::Duration};
+
+use chrono::{DateTime, Utc};
+use sea_orm::{ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait, QueryFilter, Set, SqlErr};
+use uuid::Uuid;
+
+use crate::leases::{self, Entity as Lease};
+
+// Bail du scheduler de statuts: une seule instance le détient à la fois
+pub const SCHEDULER_LEASE: &str = "execution_status_scheduler";
+
+// Durée du bail. Si le leader s'arrête, une autre instance prend le relais à l'expiration.
+pub const LEASE_TTL: Duration = Duration::from_secs(30);
+
+// Période de renouvellement du bail, par une tâche dédiée indépendante des passages du scheduler
+pub const LEASE_RENEW_PERIOD: Duration = Duration::from_secs(5);
+
+// Identifiant de cette instance: INSTANCE_ID, sinon le nom d'hôte suivi d'un UUID
+pub fn instance_id() -> String {
+    env::var("INSTANCE_ID").unwrap_or_else(|_| {
+        let host = env::var("HOSTNAME").unwrap_or_else(|_| "executor".to_string());
+        format!("{}-{}", host, Uuid::new_v4())
+    })
+}
+
+// Acquérir ou renouveler le bail `name` pour `owner` jusqu'à `now + ttl`.
+// Renvoie false si une autre instance détient un bail non expiré.
+pub async fn try_acquire_lease(db: &DatabaseConnection, name: &str, owner: &str, ttl: Duration, now: DateTime<Utc>) -> Result<bool, anyhow::Error> {
+    let expires_at = now + chrono::Duration::from_std(ttl)?;
+
+    // Mise à jour conditionnelle: un seul candidat peut reprendre un bail expiré
+    let renewed = Lease::update_many()
+        .set(leases::ActiveModel {
+            owner: Set(owner.to_string()),
+            expires_at: Set(expires_at),
+            ..Default::default()
+        })
+        .filter(leases::Column::Name.eq(name))
+        .filter(
+            Condition::any()
+                .add(leases::Column::Owner.eq(owner))
+                .add(leases::Column::ExpiresAt.lte(now)),
+        )
+        .exec(db)
+        .await?;
+    if renewed.rows_affected > 0 {
+        return Ok(true);
+    }
+
+    // Aucun bail pour ce nom: le premier à l'insérer devient leader
+    let lease = leases::ActiveModel {
+        name: Set(name.to_string()),
+        owner: Set(owner.to_string()),
+        expires_at: Set(expires_at),
+    };
+    match lease.insert(db).await {
+        Ok(_) => Ok(true),
+        Err(err) if matches!(err.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => Ok(false),
+        Err(err) => Err(err.into()),
+    }
+}
+
+// Détenteur actuel du bail `name`, s'il n'a pas expiré
+pub async fn current_leader(db: &DatabaseConnection, name: &str, now: DateTime<Utc>) -> Result<Option<leases::Model>, DbErr> {
+    Lease::find_by_id(name.to_string())
+        .filter(leases::Column::ExpiresAt.gt(now))
+        .one(db)
+        .await
+}
@@ -0,0 +1,19 @@
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+
+// Bail détenu par l'instance qui exécute une tâche de fond exclusive (ex: le scheduler)
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+#[sea_orm(table_name = "scheduler_leases")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub name: String,
+    // Instance détentrice du bail
+    pub owner: String,
+    // Au-delà, le bail peut être repris par une autre instance
+    pub expires_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
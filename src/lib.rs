@@ -1,8 +1,19 @@
 pub mod helpers;
 pub mod workers;
+pub mod activities;
+pub mod command_logs;
 pub mod controller;
 pub mod database;
-
-mod executions;
-mod service;
-mod workflows;
+pub mod drift_reports;
+pub mod execution_steps;
+pub mod executions;
+pub mod fan_out;
+pub mod leader;
+pub mod leases;
+pub mod pipeline;
+pub mod reconciler;
+pub mod registry;
+pub mod schedules;
+pub mod service;
+pub mod versioning;
+pub mod workflows;
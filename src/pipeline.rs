@@ -0,0 +1,383 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use temporal_sdk::ActivityOptions;
+use temporal_sdk_core_protos::coresdk::AsJsonPayloadExt;
+
+use crate::{
+    execution_steps::{self, StepStatus},
+    registry::registry,
+};
+
+// Politique de retry propre à une étape (remplace celle de l'activité)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StepRetry {
+    pub maximum_attempts: Option<i32>,
+    pub initial_interval_secs: Option<u64>,
+}
+
+// Étape d'un pipeline: une activité du registre et les étapes qui doivent la précéder
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PipelineStep {
+    pub id: String,
+    pub activity: String,
+    #[serde(default)]
+    pub input: Value,
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    #[serde(default)]
+    pub retry: Option<StepRetry>,
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    // Compensation exécutée en ordre inverse si une étape suivante échoue
+    #[serde(default)]
+    pub compensate: Option<Compensation>,
+}
+
+impl PipelineStep {
+    // Options de l'activité: valeurs par défaut du registre, puis retry et délai de l'étape.
+    // L'ID d'activité reprend celui de l'étape pour la retrouver dans l'historique.
+    pub fn activity_options(&self) -> Result<ActivityOptions, anyhow::Error> {
+        activity_options(&self.activity, &self.input, self.retry.as_ref(), self.timeout_secs, step_activity_id(&self.id))
+    }
+}
+
+// Activité annulant les effets d'une étape terminée, lancée si le pipeline échoue ou est annulé
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Compensation {
+    pub activity: String,
+    #[serde(default)]
+    pub input: Value,
+    #[serde(default)]
+    pub retry: Option<StepRetry>,
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+impl Compensation {
+    pub fn activity_options(&self, step_id: &str) -> Result<ActivityOptions, anyhow::Error> {
+        activity_options(&self.activity, &self.input, self.retry.as_ref(), self.timeout_secs, format!("compensate:{}", step_id))
+    }
+}
+
+fn activity_options(
+    activity_type: &str,
+    input: &Value,
+    retry: Option<&StepRetry>,
+    timeout_secs: Option<u64>,
+    activity_id: String,
+) -> Result<ActivityOptions, anyhow::Error> {
+    let activity = registry()
+        .activity(activity_type)
+        .ok_or_else(|| anyhow::anyhow!("Unknown activity {} for {}", activity_type, activity_id))?;
+    let mut defaults = activity.defaults;
+    if let Some(timeout_secs) = timeout_secs {
+        defaults.start_to_close_timeout_secs = timeout_secs;
+    }
+    if let Some(retry) = retry {
+        defaults.maximum_attempts = retry.maximum_attempts.unwrap_or(defaults.maximum_attempts);
+        defaults.initial_retry_interval_secs = retry.initial_interval_secs.unwrap_or(defaults.initial_retry_interval_secs);
+    }
+    let mut options = defaults.options(activity_type, input.as_json_payload()?);
+    options.activity_id = Some(activity_id);
+    Ok(options)
+}
+
+// Activités utilisables par une étape ou une compensation
+fn is_step_activity(activity_type: &str) -> bool {
+    registry().activity(activity_type).is_some_and(|activity| !activity.internal)
+}
+
+pub fn step_activity_id(step_id: &str) -> String {
+    format!("step:{}", step_id)
+}
+
+// Entrée de pipeline_workflow
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PipelineInput {
+    pub steps: Vec<PipelineStep>,
+}
+
+impl PipelineInput {
+    // Vérifier les IDs, les activités et l'absence de cycle entre les étapes
+    pub fn validate(&self) -> Result<(), String> {
+        if self.steps.is_empty() {
+            return Err("le pipeline ne contient aucune étape".to_string());
+        }
+        let mut ids = HashSet::new();
+        for step in &self.steps {
+            if step.id.is_empty() {
+                return Err("une étape n'a pas d'id".to_string());
+            }
+            if !ids.insert(step.id.as_str()) {
+                return Err(format!("étape {} déclarée deux fois", step.id));
+            }
+            if !is_step_activity(&step.activity) {
+                return Err(format!("activité inconnue pour l'étape {}: {}", step.id, step.activity));
+            }
+            if let Some(compensation) = step.compensate.as_ref().filter(|compensation| !is_step_activity(&compensation.activity)) {
+                return Err(format!("activité de compensation inconnue pour l'étape {}: {}", step.id, compensation.activity));
+            }
+        }
+        for step in &self.steps {
+            if let Some(dependency) = step.depends_on.iter().find(|dependency| !ids.contains(dependency.as_str())) {
+                return Err(format!("l'étape {} dépend d'une étape inconnue: {}", step.id, dependency));
+            }
+        }
+
+        // Tri topologique: les étapes restantes forment un cycle
+        let mut remaining: HashMap<&str, usize> = self.steps.iter().map(|step| (step.id.as_str(), step.depends_on.len())).collect();
+        let mut ready: Vec<&str> = remaining.iter().filter(|(_, count)| **count == 0).map(|(id, _)| *id).collect();
+        while let Some(done) = ready.pop() {
+            remaining.remove(done);
+            for step in &self.steps {
+                if step.depends_on.iter().any(|dependency| dependency == done) {
+                    if let Some(count) = remaining.get_mut(step.id.as_str()) {
+                        *count -= 1;
+                        if *count == 0 {
+                            ready.push(step.id.as_str());
+                        }
+                    }
+                }
+            }
+        }
+        if !remaining.is_empty() {
+            let mut cycle: Vec<&str> = remaining.into_keys().collect();
+            cycle.sort();
+            return Err(format!("cycle entre les étapes {}", cycle.join(", ")));
+        }
+        Ok(())
+    }
+}
+
+// Validation utilisée par le registre
+pub fn normalize_pipeline_input(input: Value) -> Result<Value, serde_json::Error> {
+    let pipeline = serde_json::from_value::<PipelineInput>(input)?;
+    pipeline.validate().map_err(serde::de::Error::custom)?;
+    serde_json::to_value(pipeline)
+}
+
+// État d'une étape, enregistré dans execution_steps à chaque transition et lu par l'API
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StepState {
+    pub step_id: String,
+    pub activity_type: String,
+    pub depends_on: Vec<String>,
+    pub status: StepStatus,
+    pub result: Option<Value>,
+    pub error: Option<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl From<execution_steps::Model> for StepState {
+    fn from(model: execution_steps::Model) -> Self {
+        StepState {
+            step_id: model.step_id,
+            activity_type: model.activity_type,
+            depends_on: model.depends_on.0,
+            status: model.status,
+            result: model.result,
+            error: model.error,
+            started_at: model.started_at,
+            finished_at: model.finished_at,
+        }
+    }
+}
+
+// État de toutes les étapes, dans l'ordre de déclaration
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PipelineState {
+    pub steps: Vec<StepState>,
+    // Indices des étapes terminées, dans l'ordre de fin (ordre inverse de compensation)
+    #[serde(skip)]
+    completed: Vec<usize>,
+}
+
+impl PipelineState {
+    pub fn new(input: &PipelineInput) -> Self {
+        PipelineState {
+            steps: input
+                .steps
+                .iter()
+                .map(|step| StepState {
+                    step_id: step.id.clone(),
+                    activity_type: step.activity.clone(),
+                    depends_on: step.depends_on.clone(),
+                    ..Default::default()
+                })
+                .collect(),
+            completed: Vec::new(),
+        }
+    }
+
+    // Étapes en attente dont toutes les dépendances sont terminées avec succès,
+    // dans l'ordre de déclaration (ordre de planification déterministe)
+    pub fn ready_steps(&self) -> Vec<usize> {
+        self.steps
+            .iter()
+            .enumerate()
+            .filter(|(_, step)| step.status == StepStatus::Pending)
+            .filter(|(_, step)| step.depends_on.iter().all(|dependency| self.status_of(dependency) == Some(StepStatus::Completed)))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    pub fn status_of(&self, step_id: &str) -> Option<StepStatus> {
+        self.steps.iter().find(|step| step.step_id == step_id).map(|step| step.status)
+    }
+
+    pub fn start(&mut self, index: usize, now: Option<DateTime<Utc>>) {
+        let step = &mut self.steps[index];
+        step.status = StepStatus::Running;
+        step.started_at = now;
+    }
+
+    pub fn complete(&mut self, index: usize, result: Value, now: Option<DateTime<Utc>>) {
+        self.finish(index, StepStatus::Completed, now);
+        self.steps[index].result = Some(result);
+        self.completed.push(index);
+    }
+
+    // Étape dont la compensation a réussi
+    pub fn compensated(&mut self, index: usize) {
+        self.steps[index].status = StepStatus::Compensated;
+    }
+
+    // Compensations à exécuter: étapes terminées qui en déclarent une, de la dernière terminée
+    // à la première. None si aucune étape n'est à compenser.
+    pub fn compensation_plan(&self, input: &PipelineInput, trigger: CompensationTrigger) -> Option<CompensationState> {
+        let steps: Vec<CompensationStep> = self
+            .completed
+            .iter()
+            .rev()
+            .filter_map(|index| {
+                let step = &input.steps[*index];
+                step.compensate.as_ref().map(|compensation| CompensationStep {
+                    step_index: *index,
+                    step_id: step.id.clone(),
+                    activity_type: compensation.activity.clone(),
+                    ..Default::default()
+                })
+            })
+            .collect();
+        if steps.is_empty() {
+            return None;
+        }
+        Some(CompensationState { status: CompensationStatus::Running, trigger, steps })
+    }
+
+    pub fn fail(&mut self, index: usize, error: String, now: Option<DateTime<Utc>>) {
+        self.finish(index, StepStatus::Failed, now);
+        self.steps[index].error = Some(error);
+    }
+
+    pub fn cancel(&mut self, index: usize, now: Option<DateTime<Utc>>) {
+        self.finish(index, StepStatus::Canceled, now);
+    }
+
+    // Les étapes jamais lancées passent au statut donné (SKIPPED ou CANCELED)
+    pub fn close_pending(&mut self, status: StepStatus) {
+        for step in self.steps.iter_mut().filter(|step| step.status == StepStatus::Pending) {
+            step.status = status;
+        }
+    }
+
+    pub fn has_failed(&self) -> bool {
+        self.steps.iter().any(|step| step.status == StepStatus::Failed)
+    }
+
+    pub fn failed_step_ids(&self) -> Vec<&str> {
+        self.steps
+            .iter()
+            .filter(|step| step.status == StepStatus::Failed)
+            .map(|step| step.step_id.as_str())
+            .collect()
+    }
+
+    fn finish(&mut self, index: usize, status: StepStatus, now: Option<DateTime<Utc>>) {
+        let step = &mut self.steps[index];
+        step.status = status;
+        step.finished_at = now;
+    }
+}
+
+// Origine d'une compensation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompensationTrigger {
+    Failure,
+    Cancellation,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CompensationStatus {
+    Running,
+    Completed,
+    // Au moins une compensation a échoué: les effets de l'étape restent en place
+    Failed,
+}
+
+// Compensation d'une étape
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CompensationStep {
+    // Position de l'étape dans la définition du pipeline
+    #[serde(skip)]
+    pub step_index: usize,
+    pub step_id: String,
+    pub activity_type: String,
+    pub status: StepStatus,
+    pub error: Option<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+// Avancement de la compensation, enregistré sur l'exécution (colonne compensation)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompensationState {
+    pub status: CompensationStatus,
+    pub trigger: CompensationTrigger,
+    // Dans l'ordre d'exécution
+    pub steps: Vec<CompensationStep>,
+}
+
+impl CompensationState {
+    pub fn start(&mut self, index: usize, now: Option<DateTime<Utc>>) {
+        let step = &mut self.steps[index];
+        step.status = StepStatus::Running;
+        step.started_at = now;
+    }
+
+    pub fn complete(&mut self, index: usize, now: Option<DateTime<Utc>>) {
+        let step = &mut self.steps[index];
+        step.status = StepStatus::Completed;
+        step.finished_at = now;
+    }
+
+    // Une compensation en échec n'arrête pas les suivantes
+    pub fn fail(&mut self, index: usize, error: String, now: Option<DateTime<Utc>>) {
+        let step = &mut self.steps[index];
+        step.status = StepStatus::Failed;
+        step.error = Some(error);
+        step.finished_at = now;
+    }
+
+    pub fn finish(&mut self) {
+        self.status = if self.failed_step_ids().is_empty() {
+            CompensationStatus::Completed
+        } else {
+            CompensationStatus::Failed
+        };
+    }
+
+    pub fn failed_step_ids(&self) -> Vec<&str> {
+        self.steps
+            .iter()
+            .filter(|step| step.status == StepStatus::Failed)
+            .map(|step| step.step_id.as_str())
+            .collect()
+    }
+}
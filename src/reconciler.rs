@@ -0,0 +1,249 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+use anyhow::Error;
+use chrono::{DateTime, Utc};
+use sea_orm::{sea_query::OnConflict, DatabaseConnection, EntityTrait, Set};
+use serde::{Deserialize, Serialize};
+use temporal_sdk_core_protos::temporal::api::workflow::v1::WorkflowExecutionInfo;
+use uuid::Uuid;
+
+use crate::{
+    drift_reports::{self, Entity as DriftReportRow},
+    executions::ExecutionStatus,
+    helpers::client::{is_not_found, started_by_service, StartOptions, TemporalClient, TASK_QUEUE},
+    service::{confirm_execution, create_or_get_execution, import_execution, list_executions, list_pending_executions, start_workflow, ExecutionInput},
+    workers::execution_status,
+};
+
+// Délai laissé à une requête POST /executions pour confirmer sa ligne PENDING
+pub const PENDING_GRACE: Duration = Duration::from_secs(30);
+
+// Résultat d'un passage du réconciliateur
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ReconcileReport {
+    // Lignes PENDING dont le workflow avait démarré (workflow orphelin)
+    pub confirmed: usize,
+    // Lignes PENDING dont le workflow a dû être (re)démarré
+    pub restarted: usize,
+}
+
+// Réparer les réservations PENDING abandonnées: processus arrêté entre l'insertion
+// et la confirmation, démarrage en erreur ou confirmation échouée.
+// Chaque workflow démarré par le service a une réservation: un workflow orphelin
+// est donc une ligne PENDING dont le workflow existe, et il suffit de la confirmer.
+// Sinon le workflow est démarré sous l'ID réservé.
+pub async fn reconcile_executions(client: &dyn TemporalClient, db: &DatabaseConnection, grace: Duration) -> Result<ReconcileReport, Error> {
+    let mut report = ReconcileReport::default();
+    let created_before = (Utc::now() - chrono::Duration::from_std(grace)?).naive_utc();
+
+    for execution in list_pending_executions(db, created_before).await? {
+        match client.describe(execution.workflow_id.clone(), None).await {
+            Ok(description) => {
+                if let Some(info) = description.workflow_execution_info {
+                    let run_id = info.execution.as_ref().map(|e| e.run_id.clone()).unwrap_or_default();
+                    confirm_execution(db, execution.id, run_id, execution_status(&info, Utc::now())).await?;
+                    report.confirmed += 1;
+                    continue;
+                }
+            }
+            // Seul un NotFound prouve que le workflow n'a pas démarré
+            Err(err) if is_not_found(&err) => {}
+            // Erreur transitoire: la réservation sera reprise au prochain passage
+            Err(err) => {
+                log::error!("❌ Failed to describe pending execution {}: {}", execution.id, err);
+                continue;
+            }
+        }
+
+        // Un démarrage différé dont l'heure est passée démarre immédiatement
+        let start_delay = execution.start_at.and_then(|start_at| (start_at - Utc::now()).to_std().ok());
+        let status = if start_delay.is_some() { ExecutionStatus::Scheduled } else { ExecutionStatus::Running };
+        let options = StartOptions { start_delay, ..Default::default() };
+        match start_workflow(client, &execution.workflow_type, execution.workflow_id.clone(), execution.input.clone(), options).await {
+            Ok((_, run_id)) => {
+                confirm_execution(db, execution.id, run_id, status).await?;
+                report.restarted += 1;
+            }
+            Err(err) => log::error!("❌ Failed to start pending execution {}: {}", execution.id, err),
+        }
+    }
+
+    Ok(report)
+}
+
+// Intervalle entre deux comparaisons avec la visibilité Temporal
+pub const VISIBILITY_RECONCILE_PERIOD: Duration = Duration::from_secs(60);
+
+// Nom de la ligne de drift_reports qui porte le rapport de visibilité
+pub const VISIBILITY_DRIFT_REPORT: &str = "visibility";
+
+// Exécution enregistrée dont le workflow n'existe plus côté Temporal
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MissingExecution {
+    pub id: Uuid,
+    pub workflow_id: String,
+    pub run_id: String,
+}
+
+// Exécution dont le statut en base diffère de celui de Temporal
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusMismatch {
+    pub id: Uuid,
+    pub workflow_id: String,
+    pub recorded: ExecutionStatus,
+    pub actual: ExecutionStatus,
+}
+
+// Écart entre la table executions et la visibilité Temporal
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DriftReport {
+    pub generated_at: DateTime<Utc>,
+    // Workflows listés sur notre file de tâches
+    pub visible_workflows: usize,
+    // Workflows démarrés ailleurs (ex: bin/client.rs), importés à ce passage
+    pub imported: Vec<String>,
+    // Workflows du service sans ligne en base (exécution supprimée, ou run de schedule
+    // pas encore enregistré)
+    pub untracked: Vec<String>,
+    pub missing: Vec<MissingExecution>,
+    pub status_mismatches: Vec<StatusMismatch>,
+}
+
+// Enregistrer le rapport en base, à la place du précédent: toutes les instances le servent
+pub async fn save_drift_report(db: &DatabaseConnection, report: &DriftReport) -> Result<(), Error> {
+    let row = drift_reports::ActiveModel {
+        name: Set(VISIBILITY_DRIFT_REPORT.to_string()),
+        report: Set(serde_json::to_value(report)?),
+        generated_at: Set(report.generated_at),
+    };
+    DriftReportRow::insert(row)
+        .on_conflict(
+            OnConflict::column(drift_reports::Column::Name)
+                .update_columns([drift_reports::Column::Report, drift_reports::Column::GeneratedAt])
+                .to_owned(),
+        )
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+// Dernier rapport enregistré, None si aucune réconciliation n'a encore eu lieu
+pub async fn latest_drift_report(db: &DatabaseConnection) -> Result<Option<DriftReport>, Error> {
+    let Some(row) = DriftReportRow::find_by_id(VISIBILITY_DRIFT_REPORT.to_string()).one(db).await? else {
+        return Ok(None);
+    };
+    Ok(Some(serde_json::from_value(row.report)?))
+}
+
+// Comparer la table executions à la visibilité Temporal: importer les workflows
+// démarrés en dehors du service et signaler les lignes sans workflow
+pub async fn reconcile_visibility(client: &dyn TemporalClient, db: &DatabaseConnection, grace: Duration) -> Result<DriftReport, Error> {
+    let now = Utc::now();
+    let query = format!("TaskQueue = '{}'", TASK_QUEUE);
+    let mut visible: HashMap<String, WorkflowExecutionInfo> = HashMap::new();
+    let mut page_token = vec![];
+    loop {
+        let response = client.list(query.clone(), page_token).await?;
+        for info in response.executions {
+            let Some(workflow_id) = info.execution.as_ref().map(|e| e.workflow_id.clone()) else { continue };
+            // Plusieurs runs par workflow ID (continue-as-new, retries): garder le plus récent
+            let newer = visible.get(&workflow_id).is_none_or(|known| start_key(&info) > start_key(known));
+            if newer {
+                visible.insert(workflow_id, info);
+            }
+        }
+        if response.next_page_token.is_empty() {
+            break;
+        }
+        page_token = response.next_page_token;
+    }
+
+    let executions = list_executions(db).await?;
+    let tracked: HashSet<&str> = executions.iter().map(|e| e.workflow_id.as_str()).collect();
+    let mut report = DriftReport {
+        generated_at: now,
+        visible_workflows: visible.len(),
+        imported: vec![],
+        untracked: vec![],
+        missing: vec![],
+        status_mismatches: vec![],
+    };
+
+    let mut untracked: Vec<&WorkflowExecutionInfo> = visible
+        .iter()
+        .filter(|(workflow_id, _)| !tracked.contains(workflow_id.as_str()))
+        .map(|(_, info)| info)
+        .collect();
+    untracked.sort_by_key(|info| info.execution.as_ref().map(|e| e.workflow_id.clone()));
+    for info in untracked {
+        let Some(execution) = info.execution.as_ref() else { continue };
+        // Enfant d'une exécution suivie dont le worker n'a pas enregistré le lien: l'importer sous son parent
+        let parent_id = info
+            .parent_execution
+            .as_ref()
+            .and_then(|parent| executions.iter().find(|e| e.workflow_id == parent.workflow_id))
+            .map(|parent| parent.id);
+        // Démarré par le service (exécution supprimée, run de schedule pas encore enregistré)
+        // ou enfant d'un parent non suivi: ne pas l'importer
+        if parent_id.is_none() && (started_by_service(info) || info.parent_execution.is_some()) {
+            report.untracked.push(execution.workflow_id.clone());
+            continue;
+        }
+        let input = ExecutionInput {
+            id: Uuid::new_v4(),
+            workflow_id: execution.workflow_id.clone(),
+            run_id: execution.run_id.clone(),
+            status: execution_status(info, now),
+            schedule_id: None,
+            start_at: None,
+            previous_run_ids: vec![],
+            workflow_type: info.r#type.as_ref().map(|t| t.name.clone()).unwrap_or_default(),
+            input: None,
+            parent_id,
+        };
+        // Un enfant rattaché à son parent vient du service: seul un workflow étranger est marqué importé
+        let created = if parent_id.is_some() { create_or_get_execution(db, input).await?.1 } else { import_execution(db, input).await?.1 };
+        if created {
+            report.imported.push(execution.workflow_id.clone());
+        }
+    }
+
+    let created_before = (now - chrono::Duration::from_std(grace)?).naive_utc();
+    // Les lignes LOST sont déjà connues comme sans workflow
+    for execution in executions.iter().filter(|e| !matches!(e.status, ExecutionStatus::Pending | ExecutionStatus::Lost)) {
+        match visible.get(&execution.workflow_id) {
+            Some(info) => {
+                let actual = execution_status(info, now);
+                if info.execution.as_ref().is_some_and(|e| e.run_id == execution.run_id) && actual != execution.status {
+                    report.status_mismatches.push(StatusMismatch {
+                        id: execution.id,
+                        workflow_id: execution.workflow_id.clone(),
+                        recorded: execution.status,
+                        actual,
+                    });
+                }
+            }
+            // Absente de la visibilité (cohérence à terme): confirmer avec describe
+            None if execution.created_at <= created_before => {
+                let described = client.describe(execution.workflow_id.clone(), Some(execution.run_id.clone())).await;
+                if described.is_err_and(|err| is_not_found(&err)) {
+                    report.missing.push(MissingExecution {
+                        id: execution.id,
+                        workflow_id: execution.workflow_id.clone(),
+                        run_id: execution.run_id.clone(),
+                    });
+                }
+            }
+            None => {}
+        }
+    }
+
+    Ok(report)
+}
+
+fn start_key(info: &WorkflowExecutionInfo) -> Option<(i64, i32)> {
+    info.start_time.as_ref().map(|t| (t.seconds, t.nanos))
+}
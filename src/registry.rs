@@ -0,0 +1,524 @@
+use std::{
+    sync::{Arc, LazyLock},
+    time::Duration,
+};
+
+use prost_wkt_types::Duration as ProstDuration;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+use temporal_sdk::{ActContext, ActivityOptions, Worker};
+use temporal_sdk_core_protos::temporal::api::common::v1::{Payload, RetryPolicy};
+
+use crate::{
+    activities::{
+        children::record_child_activity,
+        command::{run_command_activity, CommandInput, CommandPolicy, MAX_COMMAND_TIMEOUT},
+        http::{http_request_activity, HttpRequestInput, MAX_HTTP_TIMEOUT},
+        steps::{record_compensation_activity, record_steps_activity},
+    },
+    fan_out::normalize_fan_out_input,
+    helpers::{child_store::SharedChildStore, client::StartOptions, clock::Clock, log_store::SharedLogStore, step_store::SharedStepStore},
+    pipeline::normalize_pipeline_input,
+    versioning::{PatchStatus, WorkflowPatch},
+    workflows::{command_workflow, fan_out_workflow, http_workflow, pipeline_workflow, repeat_activity, repeat_workflow, RepeatInput, DURABLE_TIMERS_PATCH},
+};
+
+pub const REPEAT_WORKFLOW: &str = "repeat_workflow";
+pub const REPEAT_ACTIVITY: &str = "repeat_activity";
+pub const COMMAND_WORKFLOW: &str = "command_workflow";
+pub const RUN_COMMAND_ACTIVITY: &str = "run_command";
+pub const HTTP_WORKFLOW: &str = "http_workflow";
+pub const HTTP_REQUEST_ACTIVITY: &str = "http_request";
+pub const PIPELINE_WORKFLOW: &str = "pipeline_workflow";
+pub const RECORD_STEPS_ACTIVITY: &str = "record_pipeline_steps";
+pub const RECORD_COMPENSATION_ACTIVITY: &str = "record_pipeline_compensation";
+pub const FAN_OUT_WORKFLOW: &str = "fan_out_workflow";
+pub const RECORD_CHILD_ACTIVITY: &str = "record_child_execution";
+
+// Dépendances injectées dans les activités à leur enregistrement sur le worker
+#[derive(Clone)]
+pub struct ActivityDeps {
+    pub clock: Arc<dyn Clock>,
+    // Sortie des commandes lancées par run_command
+    pub logs: SharedLogStore,
+    // Commandes que run_command accepte de lancer
+    pub commands: Arc<CommandPolicy>,
+    // Client partagé par les appels de http_request
+    pub http: reqwest::Client,
+    // État des étapes des pipelines, lu par l'API
+    pub steps: SharedStepStore,
+    // Liens parent/enfant des fan-outs, lus par l'API
+    pub children: SharedChildStore,
+}
+
+// Options par défaut d'une activité, appliquées par les workflows qui la planifient
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ActivityDefaults {
+    pub start_to_close_timeout_secs: u64,
+    // Sans heartbeat dans ce délai, l'activité est relancée sur un autre worker
+    pub heartbeat_timeout_secs: Option<u64>,
+    pub initial_retry_interval_secs: u64,
+    pub maximum_attempts: i32,
+}
+
+impl ActivityDefaults {
+    pub fn options(&self, activity_type: &str, input: Payload) -> ActivityOptions {
+        ActivityOptions {
+            activity_type: activity_type.to_string(),
+            input,
+            retry_policy: Some(RetryPolicy {
+                initial_interval: Some(ProstDuration {
+                    seconds: self.initial_retry_interval_secs as i64,
+                    nanos: 0,
+                }),
+                maximum_attempts: self.maximum_attempts,
+                ..Default::default()
+            }),
+            start_to_close_timeout: Some(Duration::from_secs(self.start_to_close_timeout_secs)),
+            heartbeat_timeout: self.heartbeat_timeout_secs.map(Duration::from_secs),
+            ..Default::default()
+        }
+    }
+}
+
+// Options de démarrage par défaut d'un workflow
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct WorkflowDefaults {
+    // Durée maximale de l'exécution, continue-as-new et retries compris
+    pub execution_timeout_secs: Option<u64>,
+}
+
+// Activité déclarée dans le registre
+#[derive(Clone, Serialize)]
+pub struct ActivityType {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub input_schema: Value,
+    pub defaults: ActivityDefaults,
+    // Activité locale réservée aux workflows du service, pas utilisable comme étape de pipeline
+    pub internal: bool,
+    #[serde(skip)]
+    pub register: fn(&mut Worker, &ActivityDeps),
+}
+
+// Workflow déclaré dans le registre, démarrable via POST /executions
+#[derive(Clone, Serialize)]
+pub struct WorkflowType {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub input_schema: Value,
+    // Entrée utilisée quand la requête n'en fournit pas
+    pub default_input: Value,
+    pub defaults: WorkflowDefaults,
+    // Activités planifiées par le workflow
+    pub activities: Vec<&'static str>,
+    // Patches du code du workflow, du plus ancien au plus récent
+    pub patches: Vec<WorkflowPatch>,
+    #[serde(skip)]
+    pub register: fn(&mut Worker),
+    // Désérialise l'entrée dans le type attendu et la renvoie complétée des valeurs par défaut
+    #[serde(skip)]
+    pub validate: fn(Value) -> Result<Value, serde_json::Error>,
+}
+
+impl WorkflowType {
+    // Valider l'entrée d'une exécution (absente: entrée par défaut, qui peut être
+    // nulle pour les types dont l'entrée est obligatoire)
+    pub fn input(&self, input: Option<Value>) -> Result<Value, String> {
+        let input = match input {
+            None | Some(Value::Null) => self.default_input.clone(),
+            Some(input) => input,
+        };
+        let input = (self.validate)(input).map_err(|e| format!("Entrée invalide pour {}: {}", self.name, e))?;
+        check_schema(&self.input_schema, &input, "input").map_err(|e| format!("Entrée invalide pour {}: {}", self.name, e))?;
+        Ok(input)
+    }
+
+    // Compléter les options de démarrage avec les valeurs par défaut du type
+    pub fn start_options(&self, options: StartOptions) -> StartOptions {
+        StartOptions {
+            execution_timeout: options
+                .execution_timeout
+                .or(self.defaults.execution_timeout_secs.map(Duration::from_secs)),
+            ..options
+        }
+    }
+}
+
+// Valider une entrée en la faisant passer par son type Rust
+pub fn normalize_input<I: Serialize + DeserializeOwned>(input: Value) -> Result<Value, serde_json::Error> {
+    serde_json::to_value(serde_json::from_value::<I>(input)?)
+}
+
+// Vérifier les contraintes du schéma publié que le type Rust n'exprime pas
+// (minimum, maximum, minItems, enum), sur l'entrée déjà complétée des valeurs par défaut.
+// Les champs absents ou nuls sont ignorés: leur présence est vérifiée par la désérialisation.
+pub fn check_schema(schema: &Value, value: &Value, path: &str) -> Result<(), String> {
+    if value.is_null() {
+        return Ok(());
+    }
+    if let Some(minimum) = schema.get("minimum").and_then(Value::as_f64) {
+        if value.as_f64().is_some_and(|v| v < minimum) {
+            return Err(format!("{} doit être supérieur ou égal à {}", path, minimum));
+        }
+    }
+    if let Some(maximum) = schema.get("maximum").and_then(Value::as_f64) {
+        if value.as_f64().is_some_and(|v| v > maximum) {
+            return Err(format!("{} doit être inférieur ou égal à {}", path, maximum));
+        }
+    }
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            return Err(format!("{} doit valoir l'une des valeurs {}", path, Value::Array(allowed.clone())));
+        }
+    }
+    if let Some(items) = value.as_array() {
+        if let Some(min_items) = schema.get("minItems").and_then(Value::as_u64) {
+            if (items.len() as u64) < min_items {
+                return Err(format!("{} doit contenir au moins {} élément(s)", path, min_items));
+            }
+        }
+        if let Some(item_schema) = schema.get("items") {
+            for (i, item) in items.iter().enumerate() {
+                check_schema(item_schema, item, &format!("{}[{}]", path, i))?;
+            }
+        }
+    }
+    if let Some(fields) = value.as_object() {
+        let properties = schema.get("properties").and_then(Value::as_object);
+        for (key, field) in fields {
+            let field_schema = properties.and_then(|p| p.get(key)).or_else(|| schema.get("additionalProperties"));
+            if let Some(field_schema) = field_schema {
+                check_schema(field_schema, field, &format!("{}.{}", path, key))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// Workflows et activités connus du service: le worker les enregistre tous
+pub struct Registry {
+    workflows: Vec<WorkflowType>,
+    activities: Vec<ActivityType>,
+}
+
+impl Registry {
+    pub fn builtin() -> Self {
+        Registry {
+            workflows: vec![
+                WorkflowType {
+                    name: REPEAT_WORKFLOW,
+                    description: "Attend total_secs par pas de interval_secs (timers durables), avec pause, reprise et prolongation par signaux",
+                    input_schema: repeat_input_schema(),
+                    default_input: serde_json::to_value(RepeatInput::default()).unwrap_or_default(),
+                    defaults: WorkflowDefaults::default(),
+                    activities: vec![REPEAT_ACTIVITY],
+                    patches: vec![WorkflowPatch {
+                        id: DURABLE_TIMERS_PATCH,
+                        description: "Attente par timers durables au lieu de repeat_activity",
+                        status: PatchStatus::Active,
+                    }],
+                    register: |worker| worker.register_wf(REPEAT_WORKFLOW, repeat_workflow),
+                    validate: normalize_input::<RepeatInput>,
+                },
+                WorkflowType {
+                    name: COMMAND_WORKFLOW,
+                    description: "Lance une commande autorisée via run_command et échoue si elle ne se termine pas avec le code 0",
+                    input_schema: command_input_schema(),
+                    default_input: Value::Null,
+                    defaults: WorkflowDefaults::default(),
+                    activities: vec![RUN_COMMAND_ACTIVITY],
+                    patches: vec![],
+                    register: |worker| worker.register_wf(COMMAND_WORKFLOW, command_workflow),
+                    validate: normalize_input::<CommandInput>,
+                },
+                WorkflowType {
+                    name: HTTP_WORKFLOW,
+                    description: "Appelle un endpoint via http_request et renvoie la réponse comme résultat",
+                    input_schema: http_input_schema(),
+                    default_input: Value::Null,
+                    defaults: WorkflowDefaults::default(),
+                    activities: vec![HTTP_REQUEST_ACTIVITY],
+                    patches: vec![],
+                    register: |worker| worker.register_wf(HTTP_WORKFLOW, http_workflow),
+                    validate: normalize_input::<HttpRequestInput>,
+                },
+                WorkflowType {
+                    name: PIPELINE_WORKFLOW,
+                    description: "Exécute un graphe d'étapes (activités du registre), en parallèle dès que leurs dépendances sont terminées, et compense les étapes terminées en cas d'échec ou d'annulation",
+                    input_schema: pipeline_input_schema(),
+                    default_input: Value::Null,
+                    defaults: WorkflowDefaults::default(),
+                    // Les étapes peuvent utiliser toute activité du registre
+                    activities: vec![REPEAT_ACTIVITY, RUN_COMMAND_ACTIVITY, HTTP_REQUEST_ACTIVITY, RECORD_STEPS_ACTIVITY, RECORD_COMPENSATION_ACTIVITY],
+                    patches: vec![],
+                    register: |worker| worker.register_wf(PIPELINE_WORKFLOW, pipeline_workflow),
+                    validate: normalize_pipeline_input,
+                },
+                WorkflowType {
+                    name: FAN_OUT_WORKFLOW,
+                    description: "Lance des workflows enfants du registre avec une concurrence limitée et agrège leurs résultats",
+                    input_schema: fan_out_input_schema(),
+                    default_input: Value::Null,
+                    defaults: WorkflowDefaults::default(),
+                    activities: vec![RECORD_CHILD_ACTIVITY],
+                    patches: vec![],
+                    register: |worker| worker.register_wf(FAN_OUT_WORKFLOW, fan_out_workflow),
+                    validate: normalize_fan_out_input,
+                },
+            ],
+            activities: vec![
+                ActivityType {
+                    name: REPEAT_ACTIVITY,
+                    description: "Attend total_secs par pas de interval_secs en envoyant un heartbeat à chaque pas",
+                    input_schema: repeat_input_schema(),
+                    // Heartbeat et nouvelles tentatives permettent à une exécution de survivre à
+                    // l'arrêt du worker: sans heartbeat, la tentative perdue n'est détectée qu'au bout
+                    // des 70s, et avec une seule tentative l'exécution échoue (voir tests/e2e.rs).
+                    // L'activité reprend au dernier pas enregistré dans le heartbeat.
+                    defaults: ActivityDefaults {
+                        start_to_close_timeout_secs: 70,
+                        heartbeat_timeout_secs: Some(15),
+                        initial_retry_interval_secs: 1,
+                        maximum_attempts: 3,
+                    },
+                    internal: false,
+                    register: |worker, deps| {
+                        let clock = deps.clock.clone();
+                        worker.register_activity(REPEAT_ACTIVITY, move |ctx: ActContext, input: Value| {
+                            repeat_activity(clock.clone(), ctx, input)
+                        });
+                    },
+                },
+                ActivityType {
+                    name: RUN_COMMAND_ACTIVITY,
+                    description: "Lance une commande de ALLOWED_COMMANDS, enregistre sa sortie et renvoie son code de sortie",
+                    input_schema: command_input_schema(),
+                    defaults: ActivityDefaults {
+                        start_to_close_timeout_secs: 3600,
+                        heartbeat_timeout_secs: Some(30),
+                        initial_retry_interval_secs: 1,
+                        // Une commande n'est pas forcément idempotente: pas de nouvelle tentative
+                        maximum_attempts: 1,
+                    },
+                    internal: false,
+                    register: |worker, deps| {
+                        let policy = deps.commands.clone();
+                        let logs = deps.logs.clone();
+                        worker.register_activity(RUN_COMMAND_ACTIVITY, move |ctx: ActContext, input: Value| {
+                            run_command_activity(policy.clone(), logs.clone(), ctx, input)
+                        });
+                    },
+                },
+                ActivityType {
+                    name: HTTP_REQUEST_ACTIVITY,
+                    description: "Envoie une requête HTTP: 408, 429, 5xx et erreurs réseau sont retentés, les autres échecs non",
+                    input_schema: http_input_schema(),
+                    defaults: ActivityDefaults {
+                        start_to_close_timeout_secs: 60,
+                        heartbeat_timeout_secs: None,
+                        initial_retry_interval_secs: 1,
+                        maximum_attempts: 5,
+                    },
+                    internal: false,
+                    register: |worker, deps| {
+                        let client = deps.http.clone();
+                        worker.register_activity(HTTP_REQUEST_ACTIVITY, move |ctx: ActContext, input: Value| {
+                            http_request_activity(client.clone(), ctx, input)
+                        });
+                    },
+                },
+                ActivityType {
+                    name: RECORD_STEPS_ACTIVITY,
+                    description: "Activité locale de pipeline_workflow: enregistre l'état des étapes dans execution_steps",
+                    input_schema: json!({ "type": "array", "items": { "type": "object" } }),
+                    defaults: ActivityDefaults {
+                        start_to_close_timeout_secs: 10,
+                        heartbeat_timeout_secs: None,
+                        initial_retry_interval_secs: 1,
+                        maximum_attempts: 3,
+                    },
+                    internal: true,
+                    register: |worker, deps| {
+                        let store = deps.steps.clone();
+                        worker.register_activity(RECORD_STEPS_ACTIVITY, move |ctx: ActContext, input: Value| {
+                            record_steps_activity(store.clone(), ctx, input)
+                        });
+                    },
+                },
+                ActivityType {
+                    name: RECORD_COMPENSATION_ACTIVITY,
+                    description: "Activité locale de pipeline_workflow: enregistre l'avancement de la compensation sur l'exécution",
+                    input_schema: json!({ "type": "object" }),
+                    defaults: ActivityDefaults {
+                        start_to_close_timeout_secs: 10,
+                        heartbeat_timeout_secs: None,
+                        initial_retry_interval_secs: 1,
+                        maximum_attempts: 3,
+                    },
+                    internal: true,
+                    register: |worker, deps| {
+                        let store = deps.steps.clone();
+                        worker.register_activity(RECORD_COMPENSATION_ACTIVITY, move |ctx: ActContext, input: Value| {
+                            record_compensation_activity(store.clone(), ctx, input)
+                        });
+                    },
+                },
+                ActivityType {
+                    name: RECORD_CHILD_ACTIVITY,
+                    description: "Activité locale de fan_out_workflow: rattache un workflow enfant démarré à l'exécution parente",
+                    input_schema: json!({ "type": "object" }),
+                    defaults: ActivityDefaults {
+                        start_to_close_timeout_secs: 10,
+                        heartbeat_timeout_secs: None,
+                        initial_retry_interval_secs: 1,
+                        maximum_attempts: 3,
+                    },
+                    internal: true,
+                    register: |worker, deps| {
+                        let store = deps.children.clone();
+                        worker.register_activity(RECORD_CHILD_ACTIVITY, move |ctx: ActContext, input: Value| {
+                            record_child_activity(store.clone(), ctx, input)
+                        });
+                    },
+                },
+            ],
+        }
+    }
+
+    pub fn workflows(&self) -> &[WorkflowType] {
+        &self.workflows
+    }
+
+    pub fn activities(&self) -> &[ActivityType] {
+        &self.activities
+    }
+
+    pub fn workflow(&self, name: &str) -> Option<&WorkflowType> {
+        self.workflows.iter().find(|workflow| workflow.name == name)
+    }
+
+    pub fn activity(&self, name: &str) -> Option<&ActivityType> {
+        self.activities.iter().find(|activity| activity.name == name)
+    }
+
+    // Enregistrer tous les workflows et activités sur le worker
+    pub fn register_all(&self, worker: &mut Worker, deps: &ActivityDeps) {
+        for workflow in &self.workflows {
+            (workflow.register)(worker);
+        }
+        for activity in &self.activities {
+            (activity.register)(worker, deps);
+        }
+    }
+}
+
+static REGISTRY: LazyLock<Registry> = LazyLock::new(Registry::builtin);
+
+// Registre partagé par le worker et l'API
+pub fn registry() -> &'static Registry {
+    &REGISTRY
+}
+
+fn repeat_input_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "interval_secs": { "type": "integer", "minimum": 1, "default": 5 },
+            "total_secs": { "type": "integer", "minimum": 0, "default": 60 }
+        }
+    })
+}
+
+fn command_input_schema() -> Value {
+    json!({
+        "type": "object",
+        "required": ["command"],
+        "properties": {
+            "command": { "type": "string" },
+            "args": { "type": "array", "items": { "type": "string" }, "default": [] },
+            "env": { "type": "object", "additionalProperties": { "type": "string" }, "default": {} },
+            "working_dir": { "type": "string" },
+            "timeout_secs": { "type": "integer", "minimum": 1, "maximum": MAX_COMMAND_TIMEOUT.as_secs(), "default": 600 }
+        }
+    })
+}
+
+fn http_input_schema() -> Value {
+    json!({
+        "type": "object",
+        "required": ["url"],
+        "properties": {
+            "method": { "type": "string", "default": "GET" },
+            "url": { "type": "string", "format": "uri" },
+            "headers": { "type": "object", "additionalProperties": { "type": "string" }, "default": {} },
+            "body": {},
+            "timeout_secs": { "type": "integer", "minimum": 1, "maximum": MAX_HTTP_TIMEOUT.as_secs(), "default": 30 }
+        }
+    })
+}
+
+fn pipeline_input_schema() -> Value {
+    json!({
+        "type": "object",
+        "required": ["steps"],
+        "properties": {
+            "steps": {
+                "type": "array",
+                "minItems": 1,
+                "items": {
+                    "type": "object",
+                    "required": ["id", "activity"],
+                    "properties": {
+                        "id": { "type": "string" },
+                        "activity": { "type": "string" },
+                        "input": {},
+                        "depends_on": { "type": "array", "items": { "type": "string" }, "default": [] },
+                        "retry": {
+                            "type": "object",
+                            "properties": {
+                                "maximum_attempts": { "type": "integer", "minimum": 1 },
+                                "initial_interval_secs": { "type": "integer", "minimum": 1 }
+                            }
+                        },
+                        "timeout_secs": { "type": "integer", "minimum": 1 },
+                        "compensate": {
+                            "type": "object",
+                            "required": ["activity"],
+                            "properties": {
+                                "activity": { "type": "string" },
+                                "input": {},
+                                "retry": { "type": "object" },
+                                "timeout_secs": { "type": "integer", "minimum": 1 }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    })
+}
+
+fn fan_out_input_schema() -> Value {
+    json!({
+        "type": "object",
+        "required": ["children"],
+        "properties": {
+            "children": {
+                "type": "array",
+                "minItems": 1,
+                "items": {
+                    "type": "object",
+                    "required": ["workflow_type"],
+                    "properties": {
+                        "workflow_type": { "type": "string" },
+                        "input": {},
+                        "count": { "type": "integer", "minimum": 1, "default": 1 }
+                    }
+                }
+            },
+            "concurrency": { "type": "integer", "minimum": 1, "default": 10 },
+            "parent_close_policy": { "type": "string", "enum": ["terminate", "request_cancel", "abandon"], "default": "terminate" }
+        }
+    })
+}
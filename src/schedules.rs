@@ -0,0 +1,289 @@
+use std::time::Duration;
+
+use anyhow::Error;
+use chrono::{DateTime, SecondsFormat, Utc};
+use prost_wkt_types::Duration as ProstDuration;
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use temporal_sdk_core_protos::{
+    coresdk::AsJsonPayloadExt,
+    temporal::api::{
+        common::v1::{Payloads, WorkflowType},
+        enums::v1::{ScheduleOverlapPolicy, TaskQueueKind},
+        schedule::v1::{
+            schedule_action, IntervalSpec, Schedule, ScheduleAction, SchedulePatch, SchedulePolicies, ScheduleSpec,
+            ScheduleState, TriggerImmediatelyRequest,
+        },
+        taskqueue::v1::TaskQueue,
+        workflow::v1::NewWorkflowExecutionInfo,
+    },
+};
+use uuid::Uuid;
+
+use crate::{
+    helpers::client::{origin_memo, visibility_literal, TemporalClient, TASK_QUEUE},
+    registry::{registry, REPEAT_WORKFLOW},
+    service::{create_or_get_execution, validate_workflow_id, ExecutionInput},
+    workers::execution_status,
+    workflows::RepeatInput,
+};
+
+// Politique appliquée quand une échéance survient alors que le run précédent tourne encore
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlapPolicy {
+    #[default]
+    Skip,
+    BufferOne,
+    BufferAll,
+    CancelOther,
+    TerminateOther,
+    AllowAll,
+}
+
+impl From<OverlapPolicy> for ScheduleOverlapPolicy {
+    fn from(policy: OverlapPolicy) -> Self {
+        match policy {
+            OverlapPolicy::Skip => ScheduleOverlapPolicy::Skip,
+            OverlapPolicy::BufferOne => ScheduleOverlapPolicy::BufferOne,
+            OverlapPolicy::BufferAll => ScheduleOverlapPolicy::BufferAll,
+            OverlapPolicy::CancelOther => ScheduleOverlapPolicy::CancelOther,
+            OverlapPolicy::TerminateOther => ScheduleOverlapPolicy::TerminateOther,
+            OverlapPolicy::AllowAll => ScheduleOverlapPolicy::AllowAll,
+        }
+    }
+}
+
+// Corps de POST /schedules
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScheduleInput {
+    pub schedule_id: Option<String>,
+    #[serde(default)]
+    pub cron: Vec<String>,
+    pub interval_secs: Option<u64>,
+    #[serde(default)]
+    pub overlap_policy: OverlapPolicy,
+    pub jitter_secs: Option<u64>,
+    // Entrée transmise à chaque repeat_workflow démarré
+    #[serde(default)]
+    pub input: RepeatInput,
+    #[serde(default)]
+    pub paused: bool,
+}
+
+impl ScheduleInput {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.cron.is_empty() && self.interval_secs.is_none() {
+            return Err("Un schedule nécessite au moins une expression cron ou un interval_secs".to_string());
+        }
+        if self.interval_secs == Some(0) {
+            return Err("interval_secs doit être supérieur à 0".to_string());
+        }
+        // L'ID des runs est dérivé du schedule_id: mêmes règles que pour un workflow_id
+        if let Some(schedule_id) = &self.schedule_id {
+            validate_workflow_id(&format!("wf-{}", schedule_id)).map_err(|e| format!("schedule_id invalide: {}", e))?;
+        }
+        let input = serde_json::to_value(&self.input).map_err(|e| e.to_string())?;
+        registry()
+            .workflow(REPEAT_WORKFLOW)
+            .ok_or_else(|| format!("Type de workflow inconnu: {}", REPEAT_WORKFLOW))?
+            .input(Some(input))?;
+        Ok(())
+    }
+}
+
+// Schedule tel que renvoyé par l'API
+#[derive(Debug, Clone, Serialize)]
+pub struct ScheduleSummary {
+    pub schedule_id: String,
+    pub paused: bool,
+    pub cron: Vec<String>,
+    pub interval_secs: Vec<u64>,
+}
+
+fn seconds(secs: u64) -> ProstDuration {
+    ProstDuration {
+        seconds: secs as i64,
+        nanos: 0,
+    }
+}
+
+fn summary(schedule_id: String, spec: Option<&ScheduleSpec>, paused: bool) -> ScheduleSummary {
+    ScheduleSummary {
+        schedule_id,
+        paused,
+        cron: spec.map(|spec| spec.cron_string.clone()).unwrap_or_default(),
+        interval_secs: spec
+            .map(|spec| {
+                spec.interval
+                    .iter()
+                    .filter_map(|interval| interval.interval.as_ref().map(|d| d.seconds.max(0) as u64))
+                    .collect()
+            })
+            .unwrap_or_default(),
+    }
+}
+
+// Créer un schedule Temporal démarrant repeat_workflow selon la spécification donnée
+pub async fn create_schedule(client: &dyn TemporalClient, input: ScheduleInput) -> Result<ScheduleSummary, Error> {
+    let schedule_id = input
+        .schedule_id
+        .clone()
+        .unwrap_or_else(|| format!("sched-{}", Uuid::new_v4()));
+
+    let spec = ScheduleSpec {
+        cron_string: input.cron.clone(),
+        interval: input
+            .interval_secs
+            .map(|secs| IntervalSpec {
+                interval: Some(seconds(secs)),
+                phase: None,
+            })
+            .into_iter()
+            .collect(),
+        jitter: input.jitter_secs.map(seconds),
+        ..Default::default()
+    };
+
+    let action = NewWorkflowExecutionInfo {
+        // Temporal suffixe cet ID avec l'heure prévue de chaque run
+        workflow_id: format!("wf-{}", schedule_id),
+        workflow_type: Some(WorkflowType {
+            name: REPEAT_WORKFLOW.to_string(),
+        }),
+        task_queue: Some(TaskQueue {
+            name: TASK_QUEUE.to_string(),
+            kind: TaskQueueKind::Normal as i32,
+            ..Default::default()
+        }),
+        input: Some(Payloads {
+            payloads: vec![input.input.as_json_payload()?],
+        }),
+        memo: Some(origin_memo()),
+        ..Default::default()
+    };
+
+    let schedule = Schedule {
+        spec: Some(spec.clone()),
+        action: Some(ScheduleAction {
+            action: Some(schedule_action::Action::StartWorkflow(action)),
+        }),
+        policies: Some(SchedulePolicies {
+            overlap_policy: ScheduleOverlapPolicy::from(input.overlap_policy) as i32,
+            ..Default::default()
+        }),
+        state: Some(ScheduleState {
+            paused: input.paused,
+            ..Default::default()
+        }),
+    };
+
+    client.create_schedule(schedule_id.clone(), schedule).await?;
+    Ok(summary(schedule_id, Some(&spec), input.paused))
+}
+
+// Lister tous les schedules du namespace
+pub async fn list_schedules(client: &dyn TemporalClient) -> Result<Vec<ScheduleSummary>, Error> {
+    let mut schedules = vec![];
+    let mut page_token = vec![];
+
+    loop {
+        let response = client.list_schedules(page_token).await?;
+        schedules.extend(response.schedules.into_iter().map(|entry| {
+            let info = entry.info.unwrap_or_default();
+            summary(entry.schedule_id, info.spec.as_ref(), info.paused)
+        }));
+        if response.next_page_token.is_empty() {
+            break;
+        }
+        page_token = response.next_page_token;
+    }
+
+    Ok(schedules)
+}
+
+// Mettre en pause ou reprendre un schedule
+pub async fn set_schedule_paused(client: &dyn TemporalClient, schedule_id: String, paused: bool) -> Result<(), Error> {
+    let note = "Modifié via l'API".to_string();
+    let patch = if paused {
+        SchedulePatch { pause: note, ..Default::default() }
+    } else {
+        SchedulePatch { unpause: note, ..Default::default() }
+    };
+    client.patch_schedule(schedule_id, patch).await
+}
+
+// Déclencher immédiatement un run du schedule
+pub async fn trigger_schedule(client: &dyn TemporalClient, schedule_id: String) -> Result<(), Error> {
+    let patch = SchedulePatch {
+        trigger_immediately: Some(TriggerImmediatelyRequest::default()),
+        ..Default::default()
+    };
+    client.patch_schedule(schedule_id, patch).await
+}
+
+pub async fn delete_schedule(client: &dyn TemporalClient, schedule_id: String) -> Result<(), Error> {
+    client.delete_schedule(schedule_id).await
+}
+
+// Intervalle entre deux enregistrements des runs de schedules
+pub const SCHEDULED_RUNS_PERIOD: Duration = Duration::from_secs(30);
+
+// Recouvrement entre deux passages, pour les runs apparus en retard dans la visibilité
+pub const SCHEDULED_RUNS_OVERLAP: Duration = Duration::from_secs(60);
+
+// Enregistrer dans la table `executions` les runs démarrés par les schedules, listés
+// via la visibilité (TemporalScheduledById). Avec `since`, seuls les runs démarrés
+// depuis sont listés. Un schedule en erreur est ignoré jusqu'au passage suivant.
+// Retourne le nombre d'exécutions ajoutées.
+pub async fn record_scheduled_runs(client: &dyn TemporalClient, db: &DatabaseConnection, since: Option<DateTime<Utc>>) -> Result<usize, Error> {
+    let mut recorded = 0;
+
+    for schedule in list_schedules(client).await? {
+        match record_schedule_runs(client, db, &schedule.schedule_id, since).await {
+            Ok(count) => recorded += count,
+            Err(err) => log::error!("❌ Failed to record runs of schedule {}: {:?}", schedule.schedule_id, err),
+        }
+    }
+
+    Ok(recorded)
+}
+
+async fn record_schedule_runs(client: &dyn TemporalClient, db: &DatabaseConnection, schedule_id: &str, since: Option<DateTime<Utc>>) -> Result<usize, Error> {
+    let mut query = format!("TemporalScheduledById = {}", visibility_literal(schedule_id));
+    if let Some(since) = since {
+        let since = since.to_rfc3339_opts(SecondsFormat::Secs, true);
+        query = format!("{} AND StartTime >= {}", query, visibility_literal(&since));
+    }
+
+    let now = Utc::now();
+    let mut recorded = 0;
+    let mut page_token = vec![];
+    loop {
+        let response = client.list(query.clone(), page_token).await?;
+        for info in response.executions {
+            let Some(run) = info.execution.as_ref() else { continue };
+            let input = ExecutionInput {
+                id: Uuid::new_v4(),
+                workflow_id: run.workflow_id.clone(),
+                run_id: run.run_id.clone(),
+                status: execution_status(&info, now),
+                schedule_id: Some(schedule_id.to_string()),
+                start_at: None,
+                previous_run_ids: vec![],
+                workflow_type: info.r#type.as_ref().map(|t| t.name.clone()).unwrap_or_else(|| REPEAT_WORKFLOW.to_string()),
+                input: None,
+                parent_id: None,
+            };
+            if create_or_get_execution(db, input).await?.1 {
+                recorded += 1;
+            }
+        }
+        if response.next_page_token.is_empty() {
+            break;
+        }
+        page_token = response.next_page_token;
+    }
+
+    Ok(recorded)
+}
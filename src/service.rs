@@ -1,9 +1,15 @@
+use std::collections::{HashMap, HashSet};
+
 use anyhow::Error;
-use sea_orm::{ ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, Set};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use sea_orm::{ ActiveModelTrait, ActiveValue::NotSet, ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait, Iterable, QueryFilter, QueryOrder, Set, SqlErr, TransactionTrait};
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use crate::{executions::{self, Entity as Execution}, helpers::client::get_client};
-use temporal_client::{WorkflowClientTrait, WorkflowOptions};
-use temporal_sdk_core_protos::coresdk::AsJsonPayloadExt;
+use crate::{command_logs::{self, Entity as CommandLog}, execution_steps::{self, Entity as ExecutionStep}, executions::{self, Entity as Execution, ExecutionStatus, RunIds}, fan_out::ChildExecution, helpers::{client::{StartOptions, TemporalClient, UpdateOutcome}, workflow_state::repeat_state_from_history}, pipeline::StepState, registry::{registry, REPEAT_WORKFLOW}, versioning::ExecutionVersion, workflows::RepeatState};
+use temporal_sdk_core_protos::{
+    coresdk::{AsJsonPayloadExt, FromJsonPayloadExt},
+    temporal::api::{common::v1::Payloads, enums::v1::{WorkflowIdConflictPolicy, WorkflowIdReusePolicy}, history::v1::History, workflowservice::v1::GetWorkflowExecutionHistoryResponse},
+};
 
 // Structure pour les données d'entrée lors de la création ou mise à jour
 #[derive(Debug)]
@@ -11,22 +17,135 @@ pub struct ExecutionInput {
     pub id: Uuid,
     pub workflow_id: String,
     pub run_id: String,
-    pub status: String,
+    pub status: ExecutionStatus,
+    pub schedule_id: Option<String>,
+    pub start_at: Option<DateTime<Utc>>,
+    pub previous_run_ids: Vec<String>,
+    pub workflow_type: String,
+    pub input: Option<serde_json::Value>,
+    pub parent_id: Option<Uuid>,
+}
+
+// Délai de démarrage maximal accepté par POST /executions (un an)
+pub const MAX_START_DELAY_SECS: u64 = 365 * 24 * 3600;
+
+// Corps optionnel de POST /executions
+#[derive(Debug, Default, Deserialize)]
+pub struct NewExecution {
+    // Heure de démarrage souhaitée (RFC 3339)
+    pub start_at: Option<DateTime<Utc>>,
+    // Délai de démarrage en secondes, alternative à start_at
+    pub start_delay_secs: Option<u64>,
+    // Workflow ID explicite, alternative à l'en-tête Idempotency-Key
+    pub workflow_id: Option<String>,
+    // Type de workflow du registre, repeat_workflow par défaut
+    pub workflow_type: Option<String>,
+    // Entrée du workflow, validée selon son type
+    pub input: Option<serde_json::Value>,
+}
+
+impl NewExecution {
+    // Calculer l'heure de démarrage planifiée, None pour un démarrage immédiat
+    pub fn planned_start(&self, now: DateTime<Utc>) -> Result<Option<DateTime<Utc>>, String> {
+        match (self.start_at, self.start_delay_secs) {
+            (Some(_), Some(_)) => Err("start_at et start_delay_secs sont exclusifs".to_string()),
+            (Some(start_at), None) if start_at <= now => Err("start_at doit être dans le futur".to_string()),
+            (Some(start_at), None) => Ok(Some(start_at)),
+            (None, Some(0)) | (None, None) => Ok(None),
+            (None, Some(secs)) if secs > MAX_START_DELAY_SECS => {
+                Err(format!("start_delay_secs ne peut pas dépasser {} secondes", MAX_START_DELAY_SECS))
+            }
+            (None, Some(secs)) => i64::try_from(secs)
+                .ok()
+                .and_then(chrono::Duration::try_seconds)
+                .and_then(|delay| now.checked_add_signed(delay))
+                .map(Some)
+                .ok_or_else(|| "start_delay_secs est hors limites".to_string()),
+        }
+    }
+
+    // Workflow ID demandé par le client, None pour un ID généré
+    pub fn requested_workflow_id(&self, idempotency_key: Option<&str>) -> Result<Option<String>, String> {
+        match (idempotency_key, self.workflow_id.as_deref()) {
+            (Some(_), Some(_)) => Err("Idempotency-Key et workflow_id sont exclusifs".to_string()),
+            (Some(""), None) | (None, Some("")) => Err("La clé d'idempotence ne peut pas être vide".to_string()),
+            (Some(key), None) => Ok(Some(idempotent_workflow_id(key))),
+            (None, Some(workflow_id)) => validate_workflow_id(workflow_id).map(|()| Some(workflow_id.to_string())),
+            (None, None) => Ok(None),
+        }
+    }
+}
+
+// Longueur maximale d'un workflow ID fourni par le client
+pub const MAX_WORKFLOW_ID_LEN: usize = 200;
+
+// Un workflow ID fourni par le client ne contient que des lettres et chiffres ASCII et `-_.:`
+pub fn validate_workflow_id(workflow_id: &str) -> Result<(), String> {
+    if workflow_id.len() > MAX_WORKFLOW_ID_LEN {
+        return Err(format!("workflow_id ne doit pas dépasser {} caractères", MAX_WORKFLOW_ID_LEN));
+    }
+    if !workflow_id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':')) {
+        return Err("workflow_id ne peut contenir que des lettres, des chiffres et les caractères - _ . :".to_string());
+    }
+    Ok(())
+}
+
+// Workflow ID déterministe dérivé d'une clé d'idempotence
+pub fn idempotent_workflow_id(key: &str) -> String {
+    format!("wf-{}", Uuid::new_v5(&Uuid::NAMESPACE_OID, key.as_bytes()))
 }
 
 
 // Créer une nouvelle exécution
 pub async fn create_execution(db: &DatabaseConnection, input: ExecutionInput) -> Result<executions::Model, DbErr> {
+    insert_execution(db, input, false).await
+}
+
+async fn insert_execution(db: &DatabaseConnection, input: ExecutionInput, imported: bool) -> Result<executions::Model, DbErr> {
     let execution = executions::ActiveModel {
         id: Set(input.id),
         workflow_id: Set(input.workflow_id),
         run_id: Set(input.run_id),
         status: Set(input.status),
+        schedule_id: Set(input.schedule_id),
+        start_at: Set(input.start_at),
+        previous_run_ids: Set(RunIds(input.previous_run_ids)),
+        workflow_type: Set(input.workflow_type),
+        input: Set(input.input),
+        parent_id: Set(input.parent_id),
+        imported: Set(imported),
+        ..Default::default()
     };
     let result = execution.insert(db).await?;
     Ok(result)
 }
 
+// Créer une exécution, ou renvoyer celle qui porte déjà ce workflow ID.
+// Le booléen indique si l'exécution vient d'être créée.
+pub async fn create_or_get_execution(db: &DatabaseConnection, input: ExecutionInput) -> Result<(executions::Model, bool), DbErr> {
+    insert_or_get_execution(db, input, false).await
+}
+
+// Comme create_or_get_execution, pour un workflow démarré hors du service: la ligne
+// créée est marquée importée
+pub async fn import_execution(db: &DatabaseConnection, input: ExecutionInput) -> Result<(executions::Model, bool), DbErr> {
+    insert_or_get_execution(db, input, true).await
+}
+
+async fn insert_or_get_execution(db: &DatabaseConnection, input: ExecutionInput, imported: bool) -> Result<(executions::Model, bool), DbErr> {
+    let workflow_id = input.workflow_id.clone();
+    match insert_execution(db, input, imported).await {
+        Ok(execution) => Ok((execution, true)),
+        Err(err) if matches!(err.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => {
+            match get_execution_by_workflow_id(db, &workflow_id).await? {
+                Some(existing) => Ok((existing, false)),
+                None => Err(err),
+            }
+        }
+        Err(err) => Err(err),
+    }
+}
+
 // Récupérer une exécution par son ID
 pub async fn get_execution_by_id(db: &DatabaseConnection, id: Uuid) -> Result<Option<executions::Model>, DbErr> {
     Execution::find_by_id(id).one(db).await
@@ -39,10 +158,18 @@ pub async fn update_execution(db: &DatabaseConnection, id: Uuid, input: Executio
         return Err(DbErr::RecordNotFound(format!("Execution with id {} not found", id)));
     }
 
-    let mut execution: executions::ActiveModel = execution.unwrap().into();
+    let execution = execution.unwrap();
+    check_transition(execution.status, input.status)?;
+
+    let mut execution: executions::ActiveModel = execution.into();
     execution.workflow_id = Set(input.workflow_id);
     execution.run_id = Set(input.run_id);
     execution.status = Set(input.status);
+    execution.schedule_id = Set(input.schedule_id);
+    execution.start_at = Set(input.start_at);
+    execution.previous_run_ids = Set(RunIds(input.previous_run_ids));
+    execution.workflow_type = Set(input.workflow_type);
+    execution.input = Set(input.input);
     let result = execution.update(db).await?;
     Ok(result)
 }
@@ -53,38 +180,384 @@ pub async fn delete_execution(db: &DatabaseConnection, id: Uuid) -> Result<u64,
     Ok(result.rows_affected)
 }
 
+// Récupérer une exécution par son workflow ID
+pub async fn get_execution_by_workflow_id(db: &DatabaseConnection, workflow_id: &str) -> Result<Option<executions::Model>, DbErr> {
+    Execution::find()
+        .filter(executions::Column::WorkflowId.eq(workflow_id))
+        .one(db)
+        .await
+}
+
 // Lister toutes les exécutions
 pub async fn list_executions(db: &DatabaseConnection) -> Result<Vec<executions::Model>, DbErr> {
     Execution::find().all(db).await
 }
 
-pub async fn list_incomplete_executions(db: &DatabaseConnection) -> Result<Vec<executions::Model>, DbErr> {
+// Enregistrer un workflow enfant sous l'exécution de son parent. Si l'enfant est déjà
+// suivi (ex: importé par le réconciliateur), seul le lien vers le parent est complété.
+pub async fn record_child_execution(db: &DatabaseConnection, parent_workflow_id: &str, child: &ChildExecution) -> Result<executions::Model, DbErr> {
+    let parent = get_execution_by_workflow_id(db, parent_workflow_id)
+        .await?
+        .ok_or_else(|| DbErr::RecordNotFound(format!("Execution with workflow id {} not found", parent_workflow_id)))?;
+    let input = ExecutionInput {
+        id: Uuid::new_v4(),
+        workflow_id: child.workflow_id.clone(),
+        run_id: child.run_id.clone(),
+        status: ExecutionStatus::Running,
+        schedule_id: None,
+        start_at: None,
+        previous_run_ids: vec![],
+        workflow_type: child.workflow_type.clone(),
+        input: Some(child.input.clone()),
+        parent_id: Some(parent.id),
+    };
+    let (execution, created) = create_or_get_execution(db, input).await?;
+    if created || execution.parent_id == Some(parent.id) {
+        return Ok(execution);
+    }
+    let mut execution: executions::ActiveModel = execution.into();
+    execution.parent_id = Set(Some(parent.id));
+    execution.update(db).await
+}
+
+// Lister les enfants directs d'une exécution, dans l'ordre de création
+pub async fn list_child_executions(db: &DatabaseConnection, parent_id: Uuid) -> Result<Vec<executions::Model>, DbErr> {
     Execution::find()
-        .filter(executions::Column::Status.eq("RUNNING"))
+        .filter(executions::Column::ParentId.eq(parent_id))
+        .order_by_asc(executions::Column::CreatedAt)
+        .order_by_asc(executions::Column::WorkflowId)
         .all(db)
         .await
 }
 
-// initier la tache 
-pub async fn init_workflow() -> Result<(String, String), Error> {
-    // Obtenir le client et déballer le Result
-    let client = get_client().await?;
+// Exécution et ses descendants
+#[derive(Debug, Clone, Serialize)]
+pub struct ExecutionTree {
+    #[serde(flatten)]
+    pub execution: executions::Model,
+    pub children: Vec<ExecutionTree>,
+}
 
-    // Générer un ID unique pour le workflow
-    let workflow_id = format!("wf-{}", Uuid::new_v4());
+// Construire l'arbre d'une exécution à partir des liens parent/enfant enregistrés
+pub async fn get_execution_tree(db: &DatabaseConnection, id: Uuid) -> Result<Option<ExecutionTree>, DbErr> {
+    let Some(root) = get_execution_by_id(db, id).await? else {
+        return Ok(None);
+    };
 
-    // Démarrer le workflow
-    let handle = client
-        .start_workflow(
-            vec!["".as_json_payload().expect("Failed to create payload")],
-            "repeat-task-queue".to_string(),
-            workflow_id.clone(),
-            "repeat_workflow".to_string(),
-            None,
-            WorkflowOptions::default(),
+    // Parcours en largeur, niveau par niveau; les IDs déjà vus protègent d'un cycle
+    let mut seen = HashSet::from([root.id]);
+    let mut levels: Vec<Vec<executions::Model>> = Vec::new();
+    let mut parent_ids = vec![root.id];
+    loop {
+        let children: Vec<executions::Model> = Execution::find()
+            .filter(executions::Column::ParentId.is_in(parent_ids))
+            .order_by_asc(executions::Column::CreatedAt)
+            .order_by_asc(executions::Column::WorkflowId)
+            .all(db)
+            .await?
+            .into_iter()
+            .filter(|child| seen.insert(child.id))
+            .collect();
+        if children.is_empty() {
+            break;
+        }
+        parent_ids = children.iter().map(|child| child.id).collect();
+        levels.push(children);
+    }
+
+    // Assembler depuis les feuilles, en gardant l'ordre de chaque niveau
+    let mut subtrees: HashMap<Uuid, Vec<ExecutionTree>> = HashMap::new();
+    while let Some(level) = levels.pop() {
+        let mut parents: HashMap<Uuid, Vec<ExecutionTree>> = HashMap::new();
+        for execution in level {
+            let children = subtrees.remove(&execution.id).unwrap_or_default();
+            if let Some(parent_id) = execution.parent_id {
+                parents.entry(parent_id).or_default().push(ExecutionTree { execution, children });
+            }
+        }
+        subtrees = parents;
+    }
+    let children = subtrees.remove(&root.id).unwrap_or_default();
+    Ok(Some(ExecutionTree { execution: root, children }))
+}
+
+// Lister la sortie des commandes d'un workflow, dans l'ordre d'écriture
+pub async fn list_command_logs(db: &DatabaseConnection, workflow_id: &str) -> Result<Vec<command_logs::Model>, DbErr> {
+    CommandLog::find()
+        .filter(command_logs::Column::WorkflowId.eq(workflow_id))
+        .order_by_asc(command_logs::Column::Id)
+        .all(db)
+        .await
+}
+
+// Refuser une transition interdite par la machine à états des statuts
+fn check_transition(from: ExecutionStatus, to: ExecutionStatus) -> Result<(), DbErr> {
+    if from.can_transition_to(to) {
+        Ok(())
+    } else {
+        Err(DbErr::Custom(format!("Transition de statut invalide: {} -> {}", from, to)))
+    }
+}
+
+// Confirmer une exécution PENDING une fois son workflow démarré
+pub async fn confirm_execution(db: &DatabaseConnection, id: Uuid, run_id: String, status: ExecutionStatus) -> Result<executions::Model, DbErr> {
+    let execution = Execution::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or_else(|| DbErr::RecordNotFound(format!("Execution with id {} not found", id)))?;
+    check_transition(execution.status, status)?;
+    let mut execution: executions::ActiveModel = execution.into();
+    execution.run_id = Set(run_id);
+    execution.status = Set(status);
+    execution.update(db).await
+}
+
+// Résultat de la synchronisation d'une exécution avec Temporal
+#[derive(Debug)]
+pub enum StatusSync {
+    // Run courant, runs précédents, statut et build ID du dernier worker relevés côté Temporal
+    Synced { id: Uuid, run_id: String, previous_run_ids: Vec<String>, status: ExecutionStatus, build_id: Option<String> },
+    // Workflow inconnu du serveur: l'exécution passe en LOST
+    Lost { id: Uuid, reason: String },
+}
+
+// Enregistrer un lot de synchronisations dans une seule transaction.
+// Une exécution supprimée entre-temps, ou dont le statut actuel ne permet pas la
+// transition, est ignorée.
+pub async fn apply_status_syncs(db: &DatabaseConnection, syncs: &[StatusSync], synced_at: DateTime<Utc>) -> Result<(), DbErr> {
+    let txn = db.begin().await?;
+    for sync in syncs {
+        let (id, status, execution) = match sync {
+            StatusSync::Synced { id, run_id, previous_run_ids, status, build_id } => (*id, *status, executions::ActiveModel {
+                run_id: Set(run_id.clone()),
+                status: Set(*status),
+                previous_run_ids: Set(RunIds(previous_run_ids.clone())),
+                // Pas encore de tâche traitée: garder le build ID connu
+                build_id: build_id.clone().map_or(NotSet, |build_id| Set(Some(build_id))),
+                synced_at: Set(Some(synced_at)),
+                ..Default::default()
+            }),
+            StatusSync::Lost { id, reason } => (*id, ExecutionStatus::Lost, executions::ActiveModel {
+                status: Set(ExecutionStatus::Lost),
+                status_reason: Set(Some(reason.clone())),
+                synced_at: Set(Some(synced_at)),
+                ..Default::default()
+            }),
+        };
+        Execution::update_many()
+            .set(execution)
+            .filter(executions::Column::Id.eq(id))
+            .filter(executions::Column::Status.is_in(ExecutionStatus::predecessors(status)))
+            .exec(&txn)
+            .await?;
+    }
+    txn.commit().await
+}
+
+// Lister les exécutions restées PENDING depuis avant `created_before`
+pub async fn list_pending_executions(db: &DatabaseConnection, created_before: NaiveDateTime) -> Result<Vec<executions::Model>, DbErr> {
+    Execution::find()
+        .filter(executions::Column::Status.eq(ExecutionStatus::Pending))
+        .filter(executions::Column::CreatedAt.lte(created_before))
+        .all(db)
+        .await
+}
+
+// Lister les exécutions non terminales (planifiées, en cours ou de statut inconnu), à
+// synchroniser avec Temporal, en écartant celles synchronisées après `synced_before` et
+// celles de `excluded` (en attente après une erreur transitoire).
+// CONTINUED_AS_NEW couvre les lignes enregistrées avant le suivi des chaînes de runs.
+pub async fn list_incomplete_executions(db: &DatabaseConnection, synced_before: DateTime<Utc>, excluded: &[Uuid]) -> Result<Vec<executions::Model>, DbErr> {
+    Execution::find()
+        .filter(executions::Column::Status.is_in(ExecutionStatus::iter().filter(|status| status.is_active())))
+        .filter(executions::Column::Id.is_not_in(excluded.iter().copied()))
+        .filter(
+            Condition::any()
+                .add(executions::Column::SyncedAt.is_null())
+                .add(executions::Column::SyncedAt.lte(synced_before)),
         )
+        .all(db)
+        .await
+}
+
+// Générer un workflow ID pour une exécution sans clé d'idempotence
+pub fn new_workflow_id() -> String {
+    format!("wf-{}", Uuid::new_v4())
+}
+
+// initier la tache: repeat_workflow avec son entrée par défaut
+pub async fn init_workflow(client: &dyn TemporalClient, workflow_id: String, options: StartOptions) -> Result<(String, String), Error> {
+    start_workflow(client, REPEAT_WORKFLOW, workflow_id, None, options).await
+}
+
+// Démarrer un workflow du registre, avec son entrée validée et ses options par défaut.
+// Le démarrage est idempotent: un nouvel essai se rattache au workflow déjà en cours
+// et un workflow terminé n'est jamais relancé sous le même ID.
+pub async fn start_workflow(
+    client: &dyn TemporalClient,
+    workflow_type: &str,
+    workflow_id: String,
+    input: Option<serde_json::Value>,
+    options: StartOptions,
+) -> Result<(String, String), Error> {
+    let definition = registry()
+        .workflow(workflow_type)
+        .ok_or_else(|| anyhow::anyhow!("Type de workflow inconnu: {}", workflow_type))?;
+    let input = definition.input(input).map_err(|e| anyhow::anyhow!(e))?;
+    let options = StartOptions {
+        id_reuse_policy: WorkflowIdReusePolicy::RejectDuplicate,
+        id_conflict_policy: WorkflowIdConflictPolicy::UseExisting,
+        ..definition.start_options(options)
+    };
+
+    // Démarrer le workflow
+    let run_id = client
+        .start(workflow_type.to_string(), workflow_id.clone(), vec![input.as_json_payload()?], options)
         .await?;
 
-    Ok((workflow_id, handle.run_id))
+    Ok((workflow_id, run_id))
+}
+
+// Demander l'annulation du workflow d'une exécution
+pub async fn cancel_workflow(client: &dyn TemporalClient, workflow_id: String, run_id: String) -> Result<(), Error> {
+    client
+        .cancel(workflow_id, Some(run_id), "Annulation demandée via l'API".to_string())
+        .await
+}
+
+// Envoyer un signal au workflow d'une exécution, avec un contenu JSON optionnel
+pub async fn signal_workflow(
+    client: &dyn TemporalClient,
+    workflow_id: String,
+    run_id: String,
+    signal_name: String,
+    payload: Option<serde_json::Value>,
+) -> Result<(), Error> {
+    let input = match payload {
+        Some(value) => Some(Payloads {
+            payloads: vec![value.as_json_payload()?],
+        }),
+        None => None,
+    };
+    client.signal(workflow_id, run_id, signal_name, input).await
+}
+
+// Résultat d'un update, tel que renvoyé par l'API
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "outcome", rename_all = "lowercase")]
+pub enum UpdateResult {
+    Accepted { result: serde_json::Value },
+    Rejected { reason: String },
+}
+
+// Exécuter un update sur le workflow d'une exécution et attendre sa réponse
+pub async fn update_workflow(
+    client: &dyn TemporalClient,
+    workflow_id: String,
+    run_id: String,
+    update_name: String,
+    payload: serde_json::Value,
+) -> Result<UpdateResult, Error> {
+    let args = Payloads {
+        payloads: vec![payload.as_json_payload()?],
+    };
+    match client.update(workflow_id, run_id, update_name, Some(args)).await? {
+        UpdateOutcome::Completed(result) => {
+            let result = result
+                .and_then(|result| result.payloads.into_iter().next())
+                .map(|payload| serde_json::Value::from_json_payload(&payload))
+                .transpose()?
+                .unwrap_or_default();
+            Ok(UpdateResult::Accepted { result })
+        }
+        UpdateOutcome::Rejected(reason) => Ok(UpdateResult::Rejected { reason }),
+    }
+}
+
+// Récupérer une page de l'historique Temporal d'une exécution
+pub async fn get_workflow_history_page(
+    client: &dyn TemporalClient,
+    workflow_id: String,
+    run_id: String,
+    page_token: Vec<u8>,
+) -> Result<GetWorkflowExecutionHistoryResponse, Error> {
+    client.history_page(workflow_id, Some(run_id), page_token).await
+}
+
+// Récupérer l'historique complet (toutes les pages) d'une exécution
+pub async fn get_full_workflow_history(client: &dyn TemporalClient, workflow_id: String, run_id: String) -> Result<History, Error> {
+    let mut history = History::default();
+    let mut page_token = vec![];
+
+    loop {
+        let response = client
+            .history_page(workflow_id.clone(), Some(run_id.clone()), page_token)
+            .await?;
+        if let Some(page) = response.history {
+            history.events.extend(page.events);
+        }
+        if response.next_page_token.is_empty() {
+            break;
+        }
+        page_token = response.next_page_token;
+    }
+
+    Ok(history)
+}
+
+// Origine de l'état renvoyé par get_execution_state et get_pipeline_steps
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StateSource {
+    // Reconstruit à partir de l'historique
+    History,
+    // Lu dans la table execution_steps, tenue à jour par le worker
+    Database,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct ExecutionState {
+    pub source: StateSource,
+    pub state: RepeatState,
+}
+
+// Récupérer l'état courant du workflow d'une exécution, reconstruit à partir de son historique
+pub async fn get_execution_state(client: &dyn TemporalClient, workflow_id: String, run_id: String) -> Result<ExecutionState, Error> {
+    let history = get_full_workflow_history(client, workflow_id, run_id).await?;
+    Ok(ExecutionState {
+        source: StateSource::History,
+        state: repeat_state_from_history(&history),
+    })
+}
+
+// Version d'exécution d'un run: build IDs des workers et patches relevés dans son historique
+pub async fn get_execution_version(client: &dyn TemporalClient, workflow_id: String, run_id: String) -> Result<ExecutionVersion, Error> {
+    let history = get_full_workflow_history(client, workflow_id, run_id).await?;
+    Ok(ExecutionVersion::from_history(&history))
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PipelineSteps {
+    pub source: StateSource,
+    pub steps: Vec<StepState>,
+}
+
+// Étapes enregistrées pour un run, dans l'ordre de déclaration du pipeline
+pub async fn list_execution_steps(db: &DatabaseConnection, workflow_id: &str, run_id: &str) -> Result<Vec<StepState>, DbErr> {
+    let steps = ExecutionStep::find()
+        .filter(execution_steps::Column::WorkflowId.eq(workflow_id))
+        .filter(execution_steps::Column::RunId.eq(run_id))
+        .order_by_asc(execution_steps::Column::Id)
+        .all(db)
+        .await?;
+    Ok(steps.into_iter().map(StepState::from).collect())
+}
+
+// Récupérer l'état des étapes d'un pipeline depuis la table execution_steps, que le worker
+// met à jour à chaque transition
+pub async fn get_pipeline_steps(db: &DatabaseConnection, workflow_id: String, run_id: String) -> Result<PipelineSteps, Error> {
+    Ok(PipelineSteps {
+        source: StateSource::Database,
+        steps: list_execution_steps(db, &workflow_id, &run_id).await?,
+    })
+}
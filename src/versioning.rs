@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+use temporal_sdk_core_protos::{
+    coresdk::FromJsonPayloadExt,
+    temporal::api::{
+        history::v1::{history_event::Attributes, History},
+        workflow::v1::WorkflowExecutionInfo,
+    },
+};
+
+// Variable d'environnement fixant le build ID du worker
+pub const BUILD_ID_ENV: &str = "WORKER_BUILD_ID";
+
+// Marqueur écrit dans l'historique par ctx.patched() / ctx.deprecate_patch()
+pub const PATCH_MARKER_NAME: &str = "core_patch";
+const PATCH_DETAILS_KEY: &str = "patch_data";
+
+// Build ID annoncé par le worker: WORKER_BUILD_ID, sinon la version du crate
+pub fn build_id() -> String {
+    std::env::var(BUILD_ID_ENV)
+        .ok()
+        .filter(|build_id| !build_id.is_empty())
+        .unwrap_or_else(|| format!("{}@{}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")))
+}
+
+// Étape du cycle de vie d'un patch (voir la section versionnement du readme)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PatchStatus {
+    // ctx.patched(): l'ancienne branche est gardée pour rejouer les exécutions antérieures
+    Active,
+    // ctx.deprecate_patch(): l'ancienne branche est supprimée, le marqueur reste écrit
+    Deprecated,
+}
+
+// Patch déclaré dans le registre pour un type de workflow
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct WorkflowPatch {
+    pub id: &'static str,
+    pub description: &'static str,
+    pub status: PatchStatus,
+}
+
+// Contenu du marqueur de patch
+#[derive(Deserialize)]
+struct PatchMarkerData {
+    id: String,
+}
+
+// Version d'exécution d'un run, relevée dans son historique
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ExecutionVersion {
+    pub workflow_type: Option<String>,
+    // Build IDs des workers ayant traité ses tâches, dans l'ordre
+    pub build_ids: Vec<String>,
+    // Patches pris par le run, dans l'ordre de l'historique
+    pub patches: Vec<String>,
+}
+
+impl ExecutionVersion {
+    pub fn from_history(history: &History) -> Self {
+        let mut version = ExecutionVersion::default();
+        for event in &history.events {
+            match &event.attributes {
+                Some(Attributes::WorkflowExecutionStartedEventAttributes(attrs)) => {
+                    version.workflow_type = attrs.workflow_type.as_ref().map(|t| t.name.clone());
+                }
+                Some(Attributes::WorkflowTaskCompletedEventAttributes(attrs)) => {
+                    let build_id = attrs.worker_version.as_ref().map(|v| v.build_id.clone()).unwrap_or_default();
+                    if !build_id.is_empty() && version.build_ids.last() != Some(&build_id) {
+                        version.build_ids.push(build_id);
+                    }
+                }
+                Some(Attributes::MarkerRecordedEventAttributes(attrs)) if attrs.marker_name == PATCH_MARKER_NAME => {
+                    let patch = attrs
+                        .details
+                        .get(PATCH_DETAILS_KEY)
+                        .and_then(|details| details.payloads.first())
+                        .and_then(|payload| PatchMarkerData::from_json_payload(payload).ok());
+                    match patch {
+                        Some(patch) if !version.patches.contains(&patch.id) => version.patches.push(patch.id),
+                        Some(_) => {}
+                        None => log::warn!("Ignoring unreadable patch marker {}", event.event_id),
+                    }
+                }
+                _ => {}
+            }
+        }
+        version
+    }
+
+    // Build ID du dernier worker ayant traité une tâche
+    pub fn build_id(&self) -> Option<&str> {
+        self.build_ids.last().map(String::as_str)
+    }
+}
+
+// Build ID relevé par le serveur sur la dernière tâche du workflow
+pub fn build_id_from_info(info: &WorkflowExecutionInfo) -> Option<String> {
+    info.most_recent_worker_version_stamp
+        .as_ref()
+        .map(|stamp| stamp.build_id.clone())
+        .filter(|build_id| !build_id.is_empty())
+}
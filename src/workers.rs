@@ -1,8 +1,9 @@
-use std::{sync::Arc, time::Duration};
+use std::{sync::Arc, time::{Duration, Instant}};
 use actix_web::web::Data;
+use chrono::{DateTime, Utc};
 use sea_orm::DatabaseConnection;
 use serde::{Deserialize, Serialize};
-use temporal_client::WorkflowClientTrait;
+use temporal_client::{Client, RetryClient};
 use temporal_sdk::Worker;
 use temporal_sdk_core::{init_worker, CoreRuntime};
 use temporal_sdk_core_api::{
@@ -10,31 +11,39 @@ use temporal_sdk_core_api::{
     worker::{WorkerConfigBuilder, WorkerVersioningStrategy},
 };
 use log::info;
-use temporal_sdk_core_protos::temporal::api::enums::v1::WorkflowExecutionStatus;
-use tokio::time::interval;
+use temporal_sdk_core_protos::temporal::api::{enums::v1::WorkflowExecutionStatus, workflow::v1::WorkflowExecutionInfo};
+use futures::{stream, StreamExt};
+use tokio::time::{interval, MissedTickBehavior};
 
-use crate::{helpers::client::get_client, service::{list_incomplete_executions, update_execution, ExecutionInput}, workflows::{repeat_activity, repeat_workflow}};
+use crate::{executions::{self, ExecutionStatus}, helpers::{client::{is_not_found, visibility_literal, SharedClient, TemporalClient, NAMESPACE, TASK_QUEUE}, status_tracker::{SharedStatusTracker, StatusTracker}}, leader::{try_acquire_lease, LEASE_RENEW_PERIOD, LEASE_TTL, SCHEDULER_LEASE}, reconciler::{reconcile_executions, reconcile_visibility, save_drift_report, ReconcileReport, PENDING_GRACE, VISIBILITY_RECONCILE_PERIOD}, schedules::{record_scheduled_runs, SCHEDULED_RUNS_OVERLAP, SCHEDULED_RUNS_PERIOD}, service::{apply_status_syncs, list_incomplete_executions, StatusSync}, registry::{registry, ActivityDeps}, versioning::{build_id, build_id_from_info}};
+
+// Nombre maximal de describe simultanés lors d'un passage
+const SYNC_CONCURRENCY: usize = 16;
+
+// Délai minimal entre deux synchronisations d'une même exécution
+const RESYNC_AFTER: Duration = Duration::from_secs(10);
 
 // Structure pour la tâche de mise à jour des statuts
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct UpdateExecutionStatusJob;
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateExecutionStatusJob {
+    // Nombre maximal de describe simultanés
+    pub concurrency: usize,
+    // Les exécutions synchronisées depuis moins longtemps sont ignorées
+    pub resync_after: Duration,
+}
+
+impl Default for UpdateExecutionStatusJob {
+    fn default() -> Self {
+        Self { concurrency: SYNC_CONCURRENCY, resync_after: RESYNC_AFTER }
+    }
+}
 
-pub async fn start_worker() -> Result<(), Box<dyn std::error::Error>> {
+pub async fn start_worker(deps: ActivityDeps) -> Result<(), Box<dyn std::error::Error>> {
     let client = crate::helpers::client::get_client().await?;
 
     let telemetry_options = TelemetryOptionsBuilder::default().build()?;
     let runtime = CoreRuntime::new_assume_tokio(telemetry_options)?;
-    let worker_config = WorkerConfigBuilder::default()
-        .namespace("default")
-        .task_queue("repeat-task-queue")
-        .versioning_strategy(WorkerVersioningStrategy::default())
-        .build()?;
-
-    let core_worker = init_worker(&runtime, worker_config, client)?;
-    let mut worker = Worker::new_from_core(Arc::new(core_worker), "repeat-task-queue");
-
-    worker.register_activity("repeat_activity", repeat_activity);
-    worker.register_wf("repeat_workflow", repeat_workflow);
+    let mut worker = build_worker(&runtime, client, deps)?;
 
     info!("🎧 Worker running and waiting for tasks...");
     worker.run().await.map_err(|e| {
@@ -45,72 +54,278 @@ pub async fn start_worker() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-// Convertit un statut Temporal en chaîne
-fn workflow_status_to_string(status: WorkflowExecutionStatus) -> &'static str {
+// Construire le worker avec les workflows et activités du registre.
+// L'horloge est injectée dans les activités (horloge factice en test).
+pub fn build_worker(
+    runtime: &CoreRuntime,
+    client: RetryClient<Client>,
+    deps: ActivityDeps,
+) -> Result<Worker, Box<dyn std::error::Error>> {
+    // Build ID relevé par le serveur sur chaque tâche traitée, sans règles de routage:
+    // la compatibilité entre versions repose sur les patches (voir versioning)
+    let build_id = build_id();
+    info!("🏷️ Worker build ID: {}", build_id);
+    let worker_config = WorkerConfigBuilder::default()
+        .namespace(NAMESPACE)
+        .task_queue(TASK_QUEUE)
+        .versioning_strategy(WorkerVersioningStrategy::None { build_id })
+        .build()?;
+
+    let core_worker = init_worker(runtime, worker_config, client)?;
+    let mut worker = Worker::new_from_core(Arc::new(core_worker), TASK_QUEUE);
+
+    registry().register_all(&mut worker, &deps);
+
+    Ok(worker)
+}
+
+// Convertit un statut Temporal en statut d'exécution
+pub fn workflow_status(status: WorkflowExecutionStatus) -> ExecutionStatus {
     match status {
-        WorkflowExecutionStatus::Running => "RUNNING",
-        WorkflowExecutionStatus::Completed => "COMPLETE",
-        WorkflowExecutionStatus::Failed => "FAILED",
-        WorkflowExecutionStatus::Canceled => "CANCELED",
-        WorkflowExecutionStatus::Terminated => "TERMINATED",
-        WorkflowExecutionStatus::ContinuedAsNew => "CONTINUED_AS_NEW",
-        WorkflowExecutionStatus::TimedOut => "TIMED_OUT",
-        _ => "UNKNOWN",
+        WorkflowExecutionStatus::Running => ExecutionStatus::Running,
+        WorkflowExecutionStatus::Completed => ExecutionStatus::Completed,
+        WorkflowExecutionStatus::Failed => ExecutionStatus::Failed,
+        WorkflowExecutionStatus::Canceled => ExecutionStatus::Canceled,
+        WorkflowExecutionStatus::Terminated => ExecutionStatus::Terminated,
+        WorkflowExecutionStatus::ContinuedAsNew => ExecutionStatus::ContinuedAsNew,
+        WorkflowExecutionStatus::TimedOut => ExecutionStatus::TimedOut,
+        _ => ExecutionStatus::Unknown,
     }
 }
 
-// Worker qui met à jour le statut des exécutions
-async fn update_execution_status_worker(
-    _job: UpdateExecutionStatusJob,
+// Statut d'une exécution d'après Temporal: un workflow dont le démarrage différé
+// n'est pas encore échu reste SCHEDULED
+pub fn execution_status(info: &WorkflowExecutionInfo, now: DateTime<Utc>) -> ExecutionStatus {
+    let status = info.status();
+    let pending_start = info
+        .execution_time
+        .as_ref()
+        .is_some_and(|execution_time| execution_time.seconds > now.timestamp());
+    if status == WorkflowExecutionStatus::Running && pending_start {
+        return ExecutionStatus::Scheduled;
+    }
+    workflow_status(status)
+}
+
+// Worker qui met à jour le statut des exécutions: describe en parallèle (borné par
+// `job.concurrency`), puis écriture du lot dans une seule transaction
+pub async fn update_execution_status_worker(
+    job: UpdateExecutionStatusJob,
     db: Data<Arc<DatabaseConnection>>,
+    client: &dyn TemporalClient,
+    tracker: &StatusTracker,
 ) -> Result<(), anyhow::Error> {
+    // Un passage trop long ne doit pas chevaucher le suivant
+    let Some(_pass) = tracker.try_begin_pass() else {
+        log::warn!("⏳ Previous execution status update still running, skipping this pass");
+        return Ok(());
+    };
+
     log::info!("Starting execution status update job");
 
-    let client = get_client().await?;
-    let executions = list_incomplete_executions(&db).await?;
-
-    for exec in executions {
-        match client.describe_workflow_execution(exec.workflow_id.clone(), Some(exec.run_id.clone())).await {
-            Ok(description) => {
-                let status = description
-                    .workflow_execution_info
-                    .as_ref()
-                    .map(|info| info.status())
-                    .unwrap_or(WorkflowExecutionStatus::Unspecified);
-
-                let input = ExecutionInput {
-                    id: exec.id,
-                    workflow_id: exec.workflow_id,
-                    run_id: exec.run_id,
-                    status: workflow_status_to_string(status).to_string(),
-                };
+    let synced_before = Utc::now() - chrono::Duration::from_std(job.resync_after)?;
+    let backed_off = tracker.backed_off_ids(Instant::now());
+    let executions = list_incomplete_executions(&db, synced_before, &backed_off).await?;
 
-                match update_execution(&db, exec.id, input).await {
-                    Ok(updated_exec) => log::info!("Updated execution {} to status {}", exec.id, updated_exec.status),
-                    Err(err) => log::error!("Failed to update execution {}: {}", exec.id, err),
+    let described: Vec<_> = stream::iter(executions)
+        .map(|exec| async move {
+            let described = describe_current_run(client, &exec).await;
+            (exec, described)
+        })
+        .buffer_unordered(job.concurrency.max(1))
+        .collect()
+        .await;
+
+    let mut syncs = Vec::with_capacity(described.len());
+    for (exec, described) in described {
+        match described {
+            Ok((run_id, previous_run_ids, info)) => {
+                if run_id != exec.run_id {
+                    log::info!("🔗 Execution {} moved from run {} to run {}", exec.id, exec.run_id, run_id);
+                }
+
+                // Statut de l'exécution logique: un run clos par continue-as-new dont le
+                // successeur n'est pas encore visible reste en cours
+                let status = match info.as_ref() {
+                    Some(info) if info.status() == WorkflowExecutionStatus::ContinuedAsNew => ExecutionStatus::Running,
+                    Some(info) => execution_status(info, Utc::now()),
+                    None => workflow_status(WorkflowExecutionStatus::Unspecified),
+                };
+                if !exec.status.can_transition_to(status) {
+                    log::warn!("⚠️ Ignoring invalid transition of execution {} from {} to {}", exec.id, exec.status, status);
+                    continue;
+                }
+                if status != exec.status {
+                    log::info!("Execution {} moves from {} to {}", exec.id, exec.status, status);
                 }
+
+                let build_id = info.as_ref().and_then(build_id_from_info);
+                syncs.push(StatusSync::Synced { id: exec.id, run_id, previous_run_ids, status, build_id });
+            }
+            // Workflow supprimé par la rétention ou jamais démarré: inutile de réessayer
+            Err(err) if is_not_found(&err) => {
+                log::warn!("👻 Execution {} marked as LOST: workflow {} not found", exec.id, exec.workflow_id);
+                let reason = format!("Workflow introuvable sur le serveur Temporal: {}", err);
+                syncs.push(StatusSync::Lost { id: exec.id, reason });
             }
             Err(err) => {
-                log::error!("Failed to describe workflow {}: {}", exec.workflow_id, err);
-                continue;
+                let delay = tracker.record_transient(exec.id, Instant::now());
+                log::error!("Failed to describe workflow {} (retry in {:?}): {}", exec.workflow_id, delay, err);
             }
         }
     }
 
+    if syncs.is_empty() {
+        return Ok(());
+    }
+
+    apply_status_syncs(&db, &syncs, Utc::now()).await?;
+    for sync in &syncs {
+        match sync {
+            StatusSync::Synced { id, .. } => tracker.record_updated(*id),
+            StatusSync::Lost { id, .. } => tracker.record_lost(*id),
+        }
+    }
+    log::info!("Synchronized {} executions", syncs.len());
+
     Ok(())
 }
 
-pub async fn start_execution_status_scheduler(db: Arc<DatabaseConnection>) {
-    let job = UpdateExecutionStatusJob;
+// Décrire le run courant d'une exécution en suivant sa chaîne (continue-as-new, retries).
+// Le run enregistré est décrit d'abord: en cours, il est le run courant. Clos, le dernier
+// run du workflow ID est retenu s'il descend du même premier run; sinon (fin de chaîne ou
+// workflow ID réutilisé par une autre chaîne) la première description est la bonne.
+// Renvoie le run courant, les runs précédents et la description du run courant.
+async fn describe_current_run(
+    client: &dyn TemporalClient,
+    exec: &executions::Model,
+) -> Result<(String, Vec<String>, Option<WorkflowExecutionInfo>), anyhow::Error> {
+    let recorded = client.describe(exec.workflow_id.clone(), Some(exec.run_id.clone())).await?.workflow_execution_info;
+    let running = recorded.as_ref().is_some_and(|info| info.status() == WorkflowExecutionStatus::Running);
+    if running {
+        return Ok((exec.run_id.clone(), exec.previous_run_ids.0.clone(), recorded));
+    }
+
+    let first_run_id = exec.previous_run_ids.0.first().unwrap_or(&exec.run_id).clone();
+    let latest = client.describe(exec.workflow_id.clone(), None).await?.workflow_execution_info;
+    if let Some(info) = latest {
+        let latest_run_id = info.execution.as_ref().map(|e| e.run_id.clone()).unwrap_or_default();
+        if latest_run_id != exec.run_id && info.first_run_id == first_run_id {
+            let previous_run_ids = chain_previous_runs(client, exec, &first_run_id, &latest_run_id).await;
+            return Ok((latest_run_id, previous_run_ids, Some(info)));
+        }
+    }
+
+    Ok((exec.run_id.clone(), exec.previous_run_ids.0.clone(), recorded))
+}
+
+// Runs précédents de la chaîne, du plus ancien au plus récent: ceux déjà connus, le run
+// enregistré, puis les runs intermédiaires trouvés via la visibilité
+async fn chain_previous_runs(client: &dyn TemporalClient, exec: &executions::Model, first_run_id: &str, current_run_id: &str) -> Vec<String> {
+    let mut previous_run_ids = exec.previous_run_ids.0.clone();
+    previous_run_ids.push(exec.run_id.clone());
+
+    let query = format!("WorkflowId = {}", visibility_literal(&exec.workflow_id));
+    match client.list(query, vec![]).await {
+        Ok(response) => {
+            let mut runs: Vec<_> = response
+                .executions
+                .into_iter()
+                .filter(|info| info.first_run_id == first_run_id)
+                .filter_map(|info| info.execution.map(|e| (info.start_time.map(|t| (t.seconds, t.nanos)), e.run_id)))
+                .collect();
+            runs.sort();
+            for (_, run_id) in runs {
+                if run_id != current_run_id && !previous_run_ids.contains(&run_id) {
+                    previous_run_ids.push(run_id);
+                }
+            }
+        }
+        Err(err) => log::warn!("⚠️ Failed to list runs of workflow {}: {}", exec.workflow_id, err),
+    }
+
+    previous_run_ids
+}
+
+// Renouveler le bail du scheduler dans une tâche dédiée, indépendante de la durée des
+// passages: un passage plus long que LEASE_TTL ne laisse pas une autre instance devenir
+// leader pendant qu'il écrit. Le tracker indique en continu si cette instance est leader.
+pub async fn start_lease_heartbeat(db: Arc<DatabaseConnection>, tracker: SharedStatusTracker) {
+    tokio::spawn(async move {
+        let mut interval = interval(LEASE_RENEW_PERIOD);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        loop {
+            interval.tick().await;
+
+            let is_leader = match try_acquire_lease(&db, SCHEDULER_LEASE, tracker.instance_id(), LEASE_TTL, Utc::now()).await {
+                Ok(is_leader) => is_leader,
+                Err(err) => {
+                    log::error!("❌ Failed to acquire scheduler lease: {:?}", err);
+                    false
+                }
+            };
+            if is_leader != tracker.is_leader() {
+                log::info!("👑 Instance {} {} the scheduler lease", tracker.instance_id(), if is_leader { "acquired" } else { "lost" });
+            }
+            tracker.set_leader(is_leader);
+        }
+    });
+}
+
+// Boucle du scheduler, active seulement tant que le bail est détenu (voir start_lease_heartbeat).
+// Le bail est revérifié avant chaque étape qui écrit en base.
+pub async fn start_execution_status_scheduler(db: Arc<DatabaseConnection>, client: SharedClient, tracker: SharedStatusTracker) {
+    let job = UpdateExecutionStatusJob::default();
     let db_data = Data::new(db);
 
     tokio::spawn(async move {
         let mut interval = interval(Duration::from_secs(5));
+        // Après un passage long, reprendre au rythme normal plutôt que d'enchaîner les ticks manqués
+        interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        // Début du dernier enregistrement réussi des runs de schedules
+        let mut last_scheduled_pass: Option<DateTime<Utc>> = None;
 
         loop {
             interval.tick().await;
 
-            if let Err(err) = update_execution_status_worker(job.clone(), db_data.clone()).await {
+            // Une seule instance synchronise à la fois: les autres attendent l'expiration du bail
+            if !tracker.is_leader() {
+                continue;
+            }
+
+            // Enregistrer les runs démarrés par les schedules avant de synchroniser les statuts.
+            // Moins souvent que la synchronisation, et seulement les runs récents après un
+            // premier passage complet.
+            let now = Utc::now();
+            let due = last_scheduled_pass.is_none_or(|last| (now - last).to_std().unwrap_or_default() >= SCHEDULED_RUNS_PERIOD);
+            if due {
+                let since = last_scheduled_pass.map(|last| last - chrono::Duration::from_std(SCHEDULED_RUNS_OVERLAP).unwrap_or_default());
+                match record_scheduled_runs(client.as_ref(), &db_data, since).await {
+                    Ok(recorded) => {
+                        last_scheduled_pass = Some(now);
+                        if recorded > 0 {
+                            log::info!("📅 Recorded {} scheduled executions", recorded);
+                        }
+                    }
+                    Err(err) => log::error!("❌ Failed to record scheduled executions: {:?}", err),
+                }
+            }
+
+            // Réparer les démarrages interrompus avant de synchroniser les statuts
+            if !tracker.is_leader() {
+                continue;
+            }
+            match reconcile_executions(client.as_ref(), &db_data, PENDING_GRACE).await {
+                Ok(report) if report == ReconcileReport::default() => {}
+                Ok(report) => log::info!("🩹 Reconciled executions: {:?}", report),
+                Err(err) => log::error!("❌ Failed to reconcile executions: {:?}", err),
+            }
+
+            if !tracker.is_leader() {
+                continue;
+            }
+            if let Err(err) = update_execution_status_worker(job.clone(), db_data.clone(), client.as_ref(), &tracker).await {
                 log::error!("❌ Failed to run update_execution_status_worker: {:?}", err);
             } else {
                 log::info!("✅ update_execution_status_worker ran successfully");
@@ -118,3 +333,36 @@ pub async fn start_execution_status_scheduler(db: Arc<DatabaseConnection>) {
         }
     });
 }
+
+// Comparer périodiquement la base à la visibilité Temporal et enregistrer le rapport de dérive.
+// Comme le scheduler, seule l'instance qui détient le bail importe des exécutions; le rapport
+// est stocké en base pour que toutes les instances puissent le servir.
+pub async fn start_visibility_reconciler(db: Arc<DatabaseConnection>, client: SharedClient, tracker: SharedStatusTracker) {
+    tokio::spawn(async move {
+        let mut interval = interval(VISIBILITY_RECONCILE_PERIOD);
+
+        loop {
+            interval.tick().await;
+            if !tracker.is_leader() {
+                continue;
+            }
+
+            match reconcile_visibility(client.as_ref(), &db, PENDING_GRACE).await {
+                Ok(report) => {
+                    if !report.imported.is_empty() || !report.missing.is_empty() {
+                        log::warn!(
+                            "🔍 Drift detected: {} imported, {} missing, {} untracked",
+                            report.imported.len(),
+                            report.missing.len(),
+                            report.untracked.len()
+                        );
+                    }
+                    if let Err(err) = save_drift_report(&db, &report).await {
+                        log::error!("❌ Failed to store drift report: {:?}", err);
+                    }
+                }
+                Err(err) => log::error!("❌ Failed to reconcile with Temporal visibility: {:?}", err),
+            }
+        }
+    });
+}
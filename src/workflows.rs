@@ -1,27 +1,256 @@
-use std::time::Duration;
+use std::{future::Future, ops::ControlFlow, pin::Pin, sync::{Arc, Mutex}, task::Poll, time::Duration};
+use chrono::{DateTime, Utc};
+use futures::{future, StreamExt};
 use log::{debug, info, warn};
-use temporal_sdk::{ActContext, ActExitValue, ActivityError, ActivityOptions, WfContext, WfExitValue, WorkflowResult};
+use serde::{Deserialize, Serialize};
+use temporal_client::WorkflowOptions;
+use temporal_sdk::{ActContext, ActExitValue, ActivityError, ActivityOptions, CancellableFuture, ChildWorkflowOptions, LocalActivityOptions, SignalData, UpdateContext, UpdateInfo, WfContext, WfExitValue, WorkflowResult};
 use anyhow::Result;
-use temporal_sdk_core_protos::{coresdk::AsJsonPayloadExt, temporal::api::common::v1::RetryPolicy};
-use tokio::time::sleep;
-use prost_wkt_types::Duration as ProstDuration;
+use temporal_sdk_core_protos::{coresdk::{activity_result::ActivityResolution, child_workflow::ChildWorkflowResult, workflow_commands::ActivityCancellationType, AsJsonPayloadExt, FromJsonPayloadExt}, temporal::api::common::v1::{Payload, RetryPolicy}};
 
-use crate::helpers::parse_activity_result::parse_activity_result;
+use crate::{activities::{command::{CommandInput, CommandOutput, KILL_GRACE}, http::{HttpRequestInput, HttpResponseOutput}}, execution_steps::StepStatus, fan_out::{child_workflow_id, ChildExecution, FanOutInput, FanOutResult}, helpers::{clock::Clock, parse_activity_result::parse_activity_result}, pipeline::{CompensationState, CompensationStatus, CompensationTrigger, PipelineInput, PipelineState}, registry::{registry, ActivityDefaults, HTTP_REQUEST_ACTIVITY, RECORD_CHILD_ACTIVITY, RECORD_COMPENSATION_ACTIVITY, RECORD_STEPS_ACTIVITY, REPEAT_ACTIVITY, RUN_COMMAND_ACTIVITY}};
+
+// Marqueur de version: attente par timers durables au lieu d'une activité qui dort.
+// Déclaré dans le registre: voir la section versionnement du readme avant de le retirer
+pub const DURABLE_TIMERS_PATCH: &str = "repeat-durable-timers";
+
+// Signaux acceptés par repeat_workflow
+pub const PAUSE_SIGNAL: &str = "pause";
+pub const RESUME_SIGNAL: &str = "resume";
+pub const EXTEND_SIGNAL: &str = "extend";
+pub const REPEAT_SIGNALS: [&str; 3] = [PAUSE_SIGNAL, RESUME_SIGNAL, EXTEND_SIGNAL];
+
+// Contenu du signal `extend`: durée ajoutée à l'exécution
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExtendSignal {
+    pub seconds: u64,
+}
+
+impl ExtendSignal {
+    pub fn from_payload(payload: Option<&Payload>) -> Option<Self> {
+        payload.and_then(|payload| ExtendSignal::from_json_payload(payload).ok())
+    }
+
+    fn from_signal(signal: &SignalData) -> Option<Self> {
+        Self::from_payload(signal.input.first())
+    }
+}
+
+// Update (requête/réponse) modifiant les paramètres d'une exécution en cours
+pub const UPDATE_PARAMETERS: &str = "update_parameters";
+pub const REPEAT_UPDATES: [&str; 1] = [UPDATE_PARAMETERS];
+
+// Contenu de l'update `update_parameters`: seuls les champs fournis sont modifiés
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ParametersUpdate {
+    pub interval_secs: Option<u64>,
+    pub total_secs: Option<u64>,
+}
+
+impl ParametersUpdate {
+    pub fn from_payload(payload: Option<&Payload>) -> Option<Self> {
+        payload.and_then(|payload| ParametersUpdate::from_json_payload(payload).ok())
+    }
+}
+
+// Phase d'une exécution de repeat_workflow
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RepeatPhase {
+    #[default]
+    Running,
+    Paused,
+    Completed,
+    Cancelled,
+    Failed,
+    Terminated,
+    TimedOut,
+}
+
+// État observable de repeat_workflow, renvoyé par l'update `update_parameters` et reconstruit
+// à partir de l'historique par GET /executions/{id}/state
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RepeatState {
+    pub phase: RepeatPhase,
+    // Paramètres de démarrage
+    pub parameters: RepeatInput,
+    // Intervalle et durée totale courants (signaux `extend`, update `update_parameters`)
+    pub interval_secs: u64,
+    pub total_secs: u64,
+    pub elapsed_secs: u64,
+    // Avancement en pourcentage
+    pub progress: f64,
+    pub signals_received: Vec<String>,
+}
+
+impl RepeatState {
+    pub fn new(parameters: RepeatInput) -> Self {
+        RepeatState {
+            interval_secs: parameters.interval_secs,
+            total_secs: parameters.total_secs,
+            parameters,
+            ..Default::default()
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_secs(self.elapsed_secs)
+    }
+
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs.max(1))
+    }
+
+    pub fn total(&self) -> Duration {
+        Duration::from_secs(self.total_secs)
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.elapsed_secs >= self.total_secs
+    }
+
+    pub fn advance(&mut self, duration: Duration) {
+        self.elapsed_secs = self.elapsed_secs.saturating_add(duration.as_secs()).min(self.total_secs);
+        self.update_progress();
+    }
+
+    // Sans débordement: des signaux répétés ou énormes plafonnent la durée à u64::MAX,
+    // de la même façon dans le workflow et lors de la reconstruction depuis l'historique
+    pub fn extend(&mut self, duration: Duration) {
+        self.total_secs = self.total_secs.saturating_add(duration.as_secs());
+        self.update_progress();
+    }
+
+    pub fn record_signal(&mut self, signal_name: &str) {
+        self.signals_received.push(signal_name.to_string());
+    }
+
+    // Refuser un update qui ne peut pas s'appliquer à l'état courant
+    pub fn validate_update(&self, update: &ParametersUpdate) -> Result<(), anyhow::Error> {
+        if self.is_done() {
+            anyhow::bail!("Execution already finished");
+        }
+        if update.interval_secs == Some(0) {
+            anyhow::bail!("interval_secs must be greater than 0");
+        }
+        if let Some(total_secs) = update.total_secs {
+            if total_secs < self.elapsed_secs {
+                anyhow::bail!(
+                    "total_secs ({}) cannot be shorter than the elapsed time ({})",
+                    total_secs,
+                    self.elapsed_secs
+                );
+            }
+        }
+        Ok(())
+    }
+
+    pub fn apply_update(&mut self, update: &ParametersUpdate) {
+        if let Some(interval_secs) = update.interval_secs {
+            self.interval_secs = interval_secs;
+        }
+        if let Some(total_secs) = update.total_secs {
+            self.total_secs = total_secs;
+        }
+        self.update_progress();
+    }
+
+    fn update_progress(&mut self) {
+        self.progress = if self.total_secs == 0 {
+            100.0
+        } else {
+            self.elapsed_secs as f64 * 100.0 / self.total_secs as f64
+        };
+    }
+}
+
+// Paramètres d'une exécution de repeat_workflow / repeat_activity
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RepeatInput {
+    pub interval_secs: u64,
+    pub total_secs: u64,
+}
+
+impl Default for RepeatInput {
+    fn default() -> Self {
+        RepeatInput {
+            interval_secs: 5,
+            total_secs: 60,
+        }
+    }
+}
+
+impl RepeatInput {
+    // Les anciens démarrages passent "" en entrée: on retombe sur les valeurs par défaut
+    pub fn from_payload(payload: Option<&Payload>) -> Self {
+        payload
+            .and_then(|payload| RepeatInput::from_json_payload(payload).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs.max(1))
+    }
+
+    pub fn total(&self) -> Duration {
+        Duration::from_secs(self.total_secs)
+    }
+}
+
+// Attendre `total` par pas de `interval` selon l'horloge fournie, à partir de `elapsed`.
+// `on_tick` est appelé après chaque pas et peut interrompre la boucle; `cancelled`
+// l'interrompt au milieu d'un pas, sans attendre la fin de l'attente en cours.
+pub async fn run_repeat_loop(
+    clock: &dyn Clock,
+    input: &RepeatInput,
+    mut elapsed: Duration,
+    cancelled: impl Future<Output = ()>,
+    mut on_tick: impl FnMut(Duration) -> ControlFlow<()>,
+) -> Duration {
+    tokio::pin!(cancelled);
+    while elapsed < input.total() {
+        let step = input.interval().min(input.total() - elapsed);
+        tokio::select! {
+            _ = clock.sleep(step) => {}
+            _ = &mut cancelled => break,
+        }
+        elapsed += step;
+        if on_tick(elapsed).is_break() {
+            break;
+        }
+    }
+    elapsed
+}
 
 // activité
 pub async fn repeat_activity(
-    _ctx: ActContext,
-    _payload: Option<String>,
+    clock: Arc<dyn Clock>,
+    ctx: ActContext,
+    payload: serde_json::Value,
 ) -> Result<ActExitValue<String>, ActivityError> {
     log::info!("🚀 Starting repeat_activity");
-    let mut elapsed = Duration::ZERO;
-    let interval = Duration::from_secs(5);
-    let total_duration = Duration::from_secs(60);
+    let input = serde_json::from_value::<RepeatInput>(payload).unwrap_or_default();
+    // Reprendre là où la tentative précédente s'est arrêtée (ex: worker redémarré)
+    let resumed_from = ctx
+        .get_heartbeat_details()
+        .first()
+        .and_then(|details| u64::from_json_payload(details).ok())
+        .map(Duration::from_secs)
+        .unwrap_or(Duration::ZERO);
 
-    while elapsed < total_duration {
-        sleep(interval).await;
-        elapsed += interval;
+    let elapsed = run_repeat_loop(clock.as_ref(), &input, resumed_from, ctx.cancelled(), |elapsed| {
+        if let Ok(details) = elapsed.as_secs().as_json_payload() {
+            ctx.record_heartbeat(vec![details]);
+        }
         info!("{} seconds passed", elapsed.as_secs());
+        ControlFlow::Continue(())
+    })
+    .await;
+
+    if ctx.is_cancelled() {
+        warn!("🛑 repeat_activity cancelled after {} seconds", elapsed.as_secs());
+        return Err(ActivityError::Cancelled { details: None });
     }
 
     let result = format!("⏱ Done after {} seconds", elapsed.as_secs());
@@ -30,25 +259,140 @@ pub async fn repeat_activity(
 
 
 pub async fn repeat_workflow(ctx: WfContext) -> WorkflowResult<()> {
-    use log::{debug, info, warn};
     debug!("🚀 Starting repeat_workflow");
 
-    let activity_result = ctx
-        .activity(ActivityOptions {
-            activity_type: "repeat_activity".to_string(),
-            input: "".as_json_payload()?,
-            retry_policy: Some(RetryPolicy {
-                initial_interval: Some(ProstDuration {
-                    seconds: 1,
-                    nanos: 0,
-                }),
-                maximum_attempts: 1,
-                ..Default::default()
-            }),
-            start_to_close_timeout: Some(Duration::from_secs(70)),
-            ..Default::default()
-        })
-        .await;
+    // Les exécutions démarrées avant les timers durables rejouent l'ancienne version
+    if !ctx.patched(DURABLE_TIMERS_PATCH) {
+        return repeat_workflow_with_activity(ctx).await;
+    }
+
+    // L'état est tenu à jour à chaque étape pour les updates; l'API reconstruit ce même
+    // état à partir de l'historique.
+    let state = Arc::new(Mutex::new(RepeatState::new(RepeatInput::from_payload(ctx.get_args().first()))));
+    let mut pause = ctx.make_signal_channel(PAUSE_SIGNAL);
+    let mut resume = ctx.make_signal_channel(RESUME_SIGNAL);
+    let mut extend = ctx.make_signal_channel(EXTEND_SIGNAL);
+
+    let validator_state = state.clone();
+    let handler_state = state.clone();
+    ctx.update_handler(
+        UPDATE_PARAMETERS,
+        move |_: &UpdateInfo, update: &ParametersUpdate| validator_state.lock().unwrap().validate_update(update),
+        move |_: UpdateContext, update: ParametersUpdate| {
+            let state = handler_state.clone();
+            async move {
+                let mut state = state.lock().unwrap();
+                state.apply_update(&update);
+                info!("🔧 repeat_workflow parameters updated: {:?}", update);
+                Ok(state.clone())
+            }
+        },
+    );
+
+    while !state.lock().unwrap().is_done() {
+        if state.lock().unwrap().phase == RepeatPhase::Paused {
+            // En pause: aucun timer, on attend `resume` (ou une annulation)
+            tokio::select! {
+                biased;
+                _ = ctx.cancelled() => {
+                    info!("🛑 repeat_workflow cancelled while paused after {} seconds", state.lock().unwrap().elapsed_secs);
+                    return Ok(WfExitValue::Cancelled);
+                }
+                Some(signal) = extend.next() => apply_extend(&mut state.lock().unwrap(), &signal),
+                Some(_) = pause.next() => state.lock().unwrap().record_signal(PAUSE_SIGNAL),
+                Some(_) = resume.next() => {
+                    let mut state = state.lock().unwrap();
+                    state.record_signal(RESUME_SIGNAL);
+                    state.phase = RepeatPhase::Running;
+                    info!("▶️ repeat_workflow resumed after {} seconds", state.elapsed_secs);
+                }
+            }
+            continue;
+        }
+
+        let step = {
+            let state = state.lock().unwrap();
+            state.interval().min(state.total() - state.elapsed())
+        };
+        let step_started_at = ctx.workflow_time();
+        let timer = ctx.timer(step);
+        tokio::pin!(timer);
+
+        // Attendre la fin du pas en traitant les signaux reçus entre-temps
+        let completed = loop {
+            tokio::select! {
+                biased;
+                _ = ctx.cancelled() => {
+                    info!("🛑 repeat_workflow cancelled after {} seconds", state.lock().unwrap().elapsed_secs);
+                    timer.cancel(&ctx);
+                    return Ok(WfExitValue::Cancelled);
+                }
+                Some(signal) = extend.next() => apply_extend(&mut state.lock().unwrap(), &signal),
+                Some(_) = pause.next() => {
+                    state.lock().unwrap().record_signal(PAUSE_SIGNAL);
+                    timer.cancel(&ctx);
+                    break false;
+                }
+                Some(_) = resume.next() => state.lock().unwrap().record_signal(RESUME_SIGNAL),
+                _ = &mut timer => break true,
+            }
+        };
+
+        let mut state = state.lock().unwrap();
+        if completed {
+            state.advance(step);
+            info!("{} seconds passed", state.elapsed_secs);
+        } else {
+            // Comptabiliser la partie du pas déjà écoulée avant la pause
+            let partial = match (step_started_at, ctx.workflow_time()) {
+                (Some(started), Some(now)) => now.duration_since(started).unwrap_or_default().min(step),
+                _ => Duration::ZERO,
+            };
+            state.advance(partial);
+            state.phase = RepeatPhase::Paused;
+            info!("⏸️ repeat_workflow paused after {} seconds", state.elapsed_secs);
+        }
+    }
+
+    info!("✅ repeat_workflow done after {} seconds", state.lock().unwrap().elapsed_secs);
+    Ok(WfExitValue::Normal(()))
+}
+
+// Appliquer un signal `extend` (ignoré s'il est invalide)
+fn apply_extend(state: &mut RepeatState, signal: &SignalData) {
+    state.record_signal(EXTEND_SIGNAL);
+    match ExtendSignal::from_signal(signal) {
+        Some(extend) => {
+            info!("⏩ repeat_workflow extended by {} seconds", extend.seconds);
+            state.extend(Duration::from_secs(extend.seconds));
+        }
+        None => warn!("Ignoring invalid extend signal: {:?}", signal.input),
+    }
+}
+
+// Options figées de la version d'origine (sans heartbeat, une seule tentative): les runs en
+// cours gardent leur comportement, même si les valeurs par défaut du registre changent
+pub const LEGACY_REPEAT_ACTIVITY: ActivityDefaults = ActivityDefaults {
+    start_to_close_timeout_secs: 70,
+    heartbeat_timeout_secs: None,
+    initial_retry_interval_secs: 1,
+    maximum_attempts: 1,
+};
+
+// Version d'origine: toute l'attente est faite par repeat_activity
+async fn repeat_workflow_with_activity(ctx: WfContext) -> WorkflowResult<()> {
+    let activity = ctx.activity(LEGACY_REPEAT_ACTIVITY.options(REPEAT_ACTIVITY, "".as_json_payload()?));
+    tokio::pin!(activity);
+
+    let activity_result = tokio::select! {
+        biased;
+        result = &mut activity => result,
+        _ = ctx.cancelled() => {
+            info!("🛑 repeat_workflow cancelled");
+            activity.cancel(&ctx);
+            return Ok(WfExitValue::Cancelled);
+        }
+    };
 
     match parse_activity_result::<String>(&activity_result) {
         Ok(result) => {
@@ -61,3 +405,355 @@ pub async fn repeat_workflow(ctx: WfContext) -> WorkflowResult<()> {
         }
     }
 }
+
+// Planifier une seule activité et attendre son résultat. Renvoie None si le workflow
+// est annulé: l'activité est annulée et on attend qu'elle s'arrête.
+async fn run_single_activity<T: serde::de::DeserializeOwned>(ctx: &WfContext, mut options: ActivityOptions) -> Result<Option<T>, anyhow::Error> {
+    options.cancellation_type = ActivityCancellationType::WaitCancellationCompleted;
+    let activity = ctx.activity(options);
+    tokio::pin!(activity);
+
+    let activity_result = tokio::select! {
+        biased;
+        result = &mut activity => result,
+        _ = ctx.cancelled() => {
+            activity.cancel(ctx);
+            let _ = activity.await;
+            return Ok(None);
+        }
+    };
+    parse_activity_result::<T>(&activity_result).map(Some)
+}
+
+// Lancer une commande via run_command et échouer si elle ne se termine pas avec le code 0
+pub async fn command_workflow(ctx: WfContext) -> WorkflowResult<CommandOutput> {
+    let input = ctx
+        .get_args()
+        .first()
+        .and_then(|payload| CommandInput::from_json_payload(payload).ok())
+        .ok_or_else(|| anyhow::anyhow!("command_workflow requires a command input"))?;
+    debug!("🚀 Starting command_workflow for {}", input.command);
+
+    let defaults = registry().activity(RUN_COMMAND_ACTIVITY).expect("run_command is registered").defaults;
+    let mut options = defaults.options(RUN_COMMAND_ACTIVITY, input.as_json_payload()?);
+    // Le délai de la commande est géré par l'activité: Temporal n'intervient qu'au-delà
+    if input.timeout_secs.is_some() {
+        options.start_to_close_timeout = Some(input.timeout().saturating_add(KILL_GRACE * 2));
+    }
+
+    let Some(output) = run_single_activity::<CommandOutput>(&ctx, options).await? else {
+        info!("🛑 command_workflow cancelled");
+        return Ok(WfExitValue::Cancelled);
+    };
+    if !output.succeeded() {
+        return Err(anyhow::anyhow!(
+            "{} failed (exit code: {:?}, timed out: {})",
+            input.command,
+            output.exit_code,
+            output.timed_out
+        ));
+    }
+    info!("✅ command_workflow done: {}", input.command);
+    Ok(WfExitValue::Normal(output))
+}
+
+// Appeler un endpoint via http_request; la réponse devient le résultat du workflow.
+// Les nouvelles tentatives sont décidées par Temporal selon la classe du statut HTTP.
+pub async fn http_workflow(ctx: WfContext) -> WorkflowResult<HttpResponseOutput> {
+    let input = ctx
+        .get_args()
+        .first()
+        .and_then(|payload| HttpRequestInput::from_json_payload(payload).ok())
+        .ok_or_else(|| anyhow::anyhow!("http_workflow requires a request input"))?;
+    debug!("🚀 Starting http_workflow: {} {}", input.method, input.url);
+
+    let defaults = registry().activity(HTTP_REQUEST_ACTIVITY).expect("http_request is registered").defaults;
+    let mut options = defaults.options(HTTP_REQUEST_ACTIVITY, input.as_json_payload()?);
+    // Chaque tentative dispose du délai de la requête, plus une marge
+    options.start_to_close_timeout = Some(input.timeout().saturating_add(Duration::from_secs(5)));
+
+    let Some(response) = run_single_activity::<HttpResponseOutput>(&ctx, options).await? else {
+        info!("🛑 http_workflow cancelled");
+        return Ok(WfExitValue::Cancelled);
+    };
+    info!("✅ http_workflow done: {} {} -> {}", input.method, input.url, response.status);
+    Ok(WfExitValue::Normal(response))
+}
+
+// Activité d'une étape de pipeline en cours
+type StepFuture = Pin<Box<dyn CancellableFuture<ActivityResolution> + Send>>;
+
+// Exécuter les étapes d'un pipeline en parallèle dès que leurs dépendances sont terminées.
+// Après un échec, aucune nouvelle étape n'est lancée: celles en cours vont à leur terme,
+// les autres passent en SKIPPED, les étapes terminées sont compensées et le workflow échoue.
+pub async fn pipeline_workflow(ctx: WfContext) -> WorkflowResult<PipelineState> {
+    let input = ctx
+        .get_args()
+        .first()
+        .and_then(|payload| PipelineInput::from_json_payload(payload).ok())
+        .ok_or_else(|| anyhow::anyhow!("pipeline_workflow requires a pipeline definition"))?;
+    input.validate().map_err(|e| anyhow::anyhow!("Invalid pipeline: {}", e))?;
+    debug!("🚀 Starting pipeline_workflow with {} steps", input.steps.len());
+
+    // L'état des étapes est enregistré à chaque transition dans execution_steps, où l'API le lit.
+    let mut state = PipelineState::new(&input);
+    record_steps(&ctx, &state).await;
+    let mut running: Vec<(usize, StepFuture)> = Vec::new();
+
+    loop {
+        if !state.has_failed() {
+            let ready = state.ready_steps();
+            for index in &ready {
+                let options = input.steps[*index].activity_options()?;
+                state.start(*index, workflow_now(&ctx));
+                running.push((*index, Box::pin(ctx.activity(options))));
+            }
+            if !ready.is_empty() {
+                record_steps(&ctx, &state).await;
+            }
+        }
+        if running.is_empty() {
+            break;
+        }
+
+        let (index, resolution) = tokio::select! {
+            biased;
+            _ = ctx.cancelled() => {
+                info!("🛑 pipeline_workflow cancelled");
+                for (_, activity) in &running {
+                    activity.cancel(&ctx);
+                }
+                for (index, activity) in running.drain(..) {
+                    let _ = activity.await;
+                    state.cancel(index, workflow_now(&ctx));
+                }
+                state.close_pending(StepStatus::Canceled);
+                record_steps(&ctx, &state).await;
+                compensate(&ctx, &input, &mut state, CompensationTrigger::Cancellation).await;
+                return Ok(WfExitValue::Cancelled);
+            }
+            finished = next_finished(&mut running) => finished,
+        };
+
+        let step_id = state.steps[index].step_id.clone();
+        match parse_activity_result::<serde_json::Value>(&resolution) {
+            Ok(result) => {
+                info!("✅ Pipeline step {} completed", step_id);
+                state.complete(index, result, workflow_now(&ctx));
+            }
+            Err(err) => {
+                warn!("❌ Pipeline step {} failed: {}", step_id, err);
+                state.fail(index, err.to_string(), workflow_now(&ctx));
+            }
+        }
+        record_steps(&ctx, &state).await;
+    }
+
+    if state.has_failed() {
+        state.close_pending(StepStatus::Skipped);
+        record_steps(&ctx, &state).await;
+        let failed = state.failed_step_ids().join(", ");
+        let compensation = compensate(&ctx, &input, &mut state, CompensationTrigger::Failure).await;
+        return Err(match compensation.filter(|compensation| compensation.status == CompensationStatus::Failed) {
+            Some(compensation) => anyhow::anyhow!(
+                "Pipeline steps failed: {} (compensation failed for: {})",
+                failed,
+                compensation.failed_step_ids().join(", ")
+            ),
+            None => anyhow::anyhow!("Pipeline steps failed: {}", failed),
+        });
+    }
+    info!("✅ pipeline_workflow done");
+    Ok(WfExitValue::Normal(state))
+}
+
+// Saga: exécuter une à une les compensations des étapes terminées, de la dernière terminée
+// à la première. Une compensation en échec est enregistrée et n'empêche pas les suivantes.
+async fn compensate(ctx: &WfContext, input: &PipelineInput, state: &mut PipelineState, trigger: CompensationTrigger) -> Option<CompensationState> {
+    let mut compensation = state.compensation_plan(input, trigger)?;
+    info!("↩️ Compensating {} pipeline steps", compensation.steps.len());
+    record_compensation(ctx, &compensation).await;
+
+    for index in 0..compensation.steps.len() {
+        let step_index = compensation.steps[index].step_index;
+        let step = &input.steps[step_index];
+        let Some(options) = step.compensate.as_ref().and_then(|compensate| compensate.activity_options(&step.id).ok()) else {
+            compensation.fail(index, "Invalid compensation".to_string(), workflow_now(ctx));
+            continue;
+        };
+        compensation.start(index, workflow_now(ctx));
+        record_compensation(ctx, &compensation).await;
+
+        // Pas d'annulation possible: la compensation doit aller à son terme
+        let resolution = ctx.activity(options).await;
+        match parse_activity_result::<serde_json::Value>(&resolution) {
+            Ok(_) => {
+                info!("↩️ Pipeline step {} compensated", step.id);
+                compensation.complete(index, workflow_now(ctx));
+                state.compensated(step_index);
+            }
+            Err(err) => {
+                warn!("❌ Compensation of pipeline step {} failed: {}", step.id, err);
+                compensation.fail(index, err.to_string(), workflow_now(ctx));
+            }
+        }
+        record_compensation(ctx, &compensation).await;
+        record_steps(ctx, state).await;
+    }
+
+    compensation.finish();
+    record_compensation(ctx, &compensation).await;
+    Some(compensation)
+}
+
+// Résultat d'un workflow enfant en cours
+type ChildFuture = Pin<Box<dyn CancellableFuture<ChildWorkflowResult> + Send>>;
+
+// Lancer des workflows enfants, au plus `concurrency` à la fois, et agréger leurs résultats.
+// Le parent se termine normalement même si des enfants échouent: le résultat les détaille.
+// S'il est annulé, la politique de fermeture choisie s'applique aux enfants encore en cours.
+pub async fn fan_out_workflow(ctx: WfContext) -> WorkflowResult<FanOutResult> {
+    let input = ctx
+        .get_args()
+        .first()
+        .and_then(|payload| FanOutInput::from_json_payload(payload).ok())
+        .ok_or_else(|| anyhow::anyhow!("fan_out_workflow requires a children definition"))?;
+    let input = input.normalize().map_err(|e| anyhow::anyhow!("Invalid fan-out: {}", e))?;
+    let parent_workflow_id = ctx.workflow_initial_info().workflow_id.clone();
+    let children = input.expand();
+    debug!("🚀 Starting fan_out_workflow with {} children", children.len());
+
+    let mut result = FanOutResult::new(&parent_workflow_id, &input);
+    let mut running: Vec<(usize, ChildFuture)> = Vec::new();
+    let mut next = 0;
+
+    loop {
+        while running.len() < input.concurrency() && next < children.len() {
+            let index = next;
+            next += 1;
+            let (workflow_type, child_input) = children[index];
+            let workflow_id = child_workflow_id(&parent_workflow_id, index);
+            let execution_timeout = registry()
+                .workflow(workflow_type)
+                .and_then(|workflow| workflow.defaults.execution_timeout_secs)
+                .map(Duration::from_secs);
+            let child = ctx.child_workflow(ChildWorkflowOptions {
+                workflow_id: workflow_id.clone(),
+                workflow_type: workflow_type.to_string(),
+                input: vec![child_input.as_json_payload()?],
+                options: WorkflowOptions {
+                    execution_timeout,
+                    ..Default::default()
+                },
+                parent_close_policy: input.parent_close_policy.to_proto(),
+                ..Default::default()
+            });
+
+            let pending = child.start(&ctx).await;
+            let start_status = format!("{:?}", pending.status);
+            let Some(started) = pending.into_started() else {
+                warn!("❌ Child workflow {} failed to start: {}", workflow_id, start_status);
+                result.start_failed(index, format!("Échec du démarrage: {}", start_status));
+                continue;
+            };
+            result.started(index, started.run_id.clone());
+            record_child(
+                &ctx,
+                &ChildExecution {
+                    workflow_id,
+                    run_id: started.run_id.clone(),
+                    workflow_type: workflow_type.to_string(),
+                    input: child_input.clone(),
+                },
+            )
+            .await;
+            running.push((index, Box::pin(started.result())));
+        }
+        if running.is_empty() {
+            break;
+        }
+
+        let (index, child_result) = tokio::select! {
+            biased;
+            _ = ctx.cancelled() => {
+                info!("🛑 fan_out_workflow cancelled with {} children running", running.len());
+                return Ok(WfExitValue::Cancelled);
+            }
+            finished = next_finished(&mut running) => finished,
+        };
+        result.finish(index, &child_result);
+        info!("Child workflow {} finished: {:?}", result.children[index].workflow_id, result.children[index].status);
+    }
+
+    info!(
+        "✅ fan_out_workflow done: {} completed, {} failed, {} canceled",
+        result.completed, result.failed, result.canceled
+    );
+    Ok(WfExitValue::Normal(result))
+}
+
+// Attendre la prochaine tâche terminée (étape, enfant) parmi celles en cours et la retirer de la liste
+fn next_finished<F: Future + Unpin>(running: &mut Vec<(usize, F)>) -> impl Future<Output = (usize, F::Output)> + '_ {
+    future::poll_fn(move |cx| {
+        for position in 0..running.len() {
+            if let Poll::Ready(resolution) = Pin::new(&mut running[position].1).poll(cx) {
+                let (index, _) = running.remove(position);
+                return Poll::Ready((index, resolution));
+            }
+        }
+        Poll::Pending
+    })
+}
+
+// Heure du workflow, rejouée à l'identique
+fn workflow_now(ctx: &WfContext) -> Option<DateTime<Utc>> {
+    ctx.workflow_time().map(DateTime::<Utc>::from)
+}
+
+// Enregistrer l'avancement de la compensation sur l'exécution, via une activité locale
+async fn record_compensation(ctx: &WfContext, compensation: &CompensationState) {
+    let Ok(input) = compensation.as_json_payload() else {
+        return;
+    };
+    let result = ctx.local_activity(record_options(RECORD_COMPENSATION_ACTIVITY, input)).await;
+    if !result.completed_ok() {
+        warn!("Failed to record pipeline compensation: {:?}", result.status);
+    }
+}
+
+// Enregistrer l'état des étapes dans execution_steps via une activité locale. Un échec
+// n'interrompt pas le pipeline: l'API sert l'état précédent jusqu'à la transition suivante.
+async fn record_steps(ctx: &WfContext, state: &PipelineState) {
+    let Ok(input) = state.steps.as_json_payload() else {
+        return;
+    };
+    let result = ctx.local_activity(record_options(RECORD_STEPS_ACTIVITY, input)).await;
+    if !result.completed_ok() {
+        warn!("Failed to record pipeline steps: {:?}", result.status);
+    }
+}
+
+// Rattacher un enfant démarré à l'exécution parente, via une activité locale.
+// En cas d'échec, le réconciliateur retrouve le lien depuis la visibilité Temporal.
+async fn record_child(ctx: &WfContext, child: &ChildExecution) {
+    let Ok(input) = child.as_json_payload() else {
+        return;
+    };
+    let result = ctx.local_activity(record_options(RECORD_CHILD_ACTIVITY, input)).await;
+    if !result.completed_ok() {
+        warn!("Failed to record child workflow {}: {:?}", child.workflow_id, result.status);
+    }
+}
+
+fn record_options(activity_type: &str, input: Payload) -> LocalActivityOptions {
+    LocalActivityOptions {
+        activity_type: activity_type.to_string(),
+        input,
+        retry_policy: RetryPolicy {
+            maximum_attempts: 3,
+            ..Default::default()
+        },
+        start_to_close_timeout: Some(Duration::from_secs(10)),
+        ..Default::default()
+    }
+}
@@ -0,0 +1,235 @@
+use std::{
+    future::pending,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use actix_web::{test, web, App};
+use chrono::Utc;
+use executor::{
+    activities::command::{execute_command, CommandError, CommandInput, CommandPolicy, CommandProgress, MAX_LINE_BYTES},
+    command_logs,
+    controller,
+    executions::ExecutionStatus,
+    helpers::log_store::{DbLogStore, LogLine, LogStore, LogStream, LogTarget, MemoryLogStore},
+    registry::{registry, COMMAND_WORKFLOW},
+    service::{create_execution, ExecutionInput},
+};
+use migration::{Migrator, MigratorTrait};
+use sea_orm::{Database, DatabaseConnection, EntityTrait, PaginatorTrait};
+use serde_json::json;
+use uuid::Uuid;
+
+async fn setup_db() -> DatabaseConnection {
+    let db = Database::connect("sqlite::memory:").await.expect("Failed to open in-memory database");
+    Migrator::up(&db, None).await.expect("Failed to run migrations");
+    db
+}
+
+fn target() -> LogTarget {
+    LogTarget { workflow_id: "wf-command".to_string(), run_id: "run-1".to_string(), activity_id: "1".to_string() }
+}
+
+fn shell(script: &str, timeout_secs: Option<u64>) -> CommandInput {
+    CommandInput {
+        command: "/bin/sh".to_string(),
+        args: vec!["-c".to_string(), script.to_string()],
+        timeout_secs,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn command_output_is_logged_and_returned() {
+    let logs = MemoryLogStore::new();
+    let heartbeats = Mutex::new(Vec::<CommandProgress>::new());
+    let mut input = shell("echo hello; echo \"$GREETING\" >&2; exit 3", None);
+    input.env.insert("GREETING".to_string(), "bonjour".to_string());
+
+    let output = execute_command(
+        &CommandPolicy::new(["/bin/sh"]),
+        &input,
+        &logs,
+        &target(),
+        |progress| heartbeats.lock().unwrap().push(progress.clone()),
+        pending(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(output.exit_code, Some(3));
+    assert!(!output.succeeded());
+    let mut lines: Vec<_> = output.output_tail.iter().map(|line| (line.stream, line.line.as_str())).collect();
+    lines.sort_by_key(|(stream, _)| stream.as_str());
+    assert_eq!(lines, vec![(LogStream::Stderr, "bonjour"), (LogStream::Stdout, "hello")]);
+    assert_eq!(logs.lines().len(), 2);
+    assert!(logs.lines().iter().all(|line| line.target == target()));
+    assert_eq!(heartbeats.lock().unwrap().last().unwrap().lines, 2);
+}
+
+#[tokio::test]
+async fn commands_outside_the_allow_list_are_rejected() {
+    let logs = MemoryLogStore::new();
+
+    let result = execute_command(&CommandPolicy::new(["/bin/echo"]), &shell("echo hello", None), &logs, &target(), |_| {}, pending()).await;
+
+    assert!(matches!(result, Err(CommandError::NotAllowed(command)) if command == "/bin/sh"));
+    assert!(logs.lines().is_empty());
+    assert!(CommandPolicy::default().check("/bin/echo").is_err());
+    // Seuls les chemins absolus sont retenus
+    assert!(CommandPolicy::new(["sh"]).check("sh").is_err());
+}
+
+#[tokio::test]
+async fn commands_run_with_a_fixed_environment() {
+    let logs = MemoryLogStore::new();
+    let policy = CommandPolicy::new(["/bin/sh"]);
+
+    for key in ["PATH", "LD_PRELOAD", "DYLD_INSERT_LIBRARIES"] {
+        let mut input = shell("true", None);
+        input.env.insert(key.to_string(), "/tmp".to_string());
+        let result = execute_command(&policy, &input, &logs, &target(), |_| {}, pending()).await;
+        assert!(matches!(result, Err(CommandError::ForbiddenEnv(forbidden)) if forbidden == key));
+    }
+
+    // L'environnement du worker n'est pas transmis: seules les variables de base et celles du shell restent
+    let mut input = shell("env", None);
+    input.env.insert("CI".to_string(), "1".to_string());
+    let output = execute_command(&policy, &input, &logs, &target(), |_| {}, pending()).await.unwrap();
+    let vars: Vec<_> = output.output_tail.iter().map(|line| line.line.as_str()).collect();
+    assert!(vars.contains(&"PATH=/usr/local/bin:/usr/bin:/bin"));
+    assert!(vars.contains(&"CI=1"));
+    for var in vars {
+        let name = var.split('=').next().unwrap();
+        assert!(["PATH", "LANG", "CI", "PWD", "SHLVL", "_"].contains(&name), "{} leaked into the command", name);
+    }
+}
+
+#[tokio::test]
+async fn every_output_line_is_stored_in_batches() {
+    let db = setup_db().await;
+    let logs = DbLogStore::new(db.clone());
+
+    let output = execute_command(&CommandPolicy::new(["/bin/sh"]), &shell("seq 1 5000", None), &logs, &target(), |_| {}, pending())
+        .await
+        .unwrap();
+
+    assert!(output.succeeded());
+    assert_eq!(output.output_tail.last().unwrap().line, "5000");
+    assert_eq!(command_logs::Entity::find().count(&db).await.unwrap(), 5000);
+}
+
+#[tokio::test]
+async fn invalid_and_oversized_lines_do_not_stop_the_output() {
+    let logs = MemoryLogStore::new();
+    let script = "printf 'caf\\351\\n'; head -c 100000 /dev/zero | tr '\\0' x; echo; echo after";
+
+    let output = execute_command(&CommandPolicy::new(["/bin/sh"]), &shell(script, None), &logs, &target(), |_| {}, pending())
+        .await
+        .unwrap();
+
+    let lines: Vec<_> = logs.lines().into_iter().map(|line| line.line).collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0], "caf\u{FFFD}");
+    assert_eq!(lines[1].len(), MAX_LINE_BYTES);
+    assert_eq!(lines[2], "after");
+    assert!(output.succeeded());
+}
+
+#[tokio::test]
+async fn timeout_kills_the_whole_process_group() {
+    let logs = MemoryLogStore::new();
+    let started = Instant::now();
+
+    // Le sous-processus en arrière-plan garde stdout ouvert: il doit être tué avec le shell
+    let output = execute_command(
+        &CommandPolicy::new(["/bin/sh"]),
+        &shell("sleep 30 & echo started; wait", Some(1)),
+        &logs,
+        &target(),
+        |_| {},
+        pending(),
+    )
+    .await
+    .unwrap();
+
+    assert!(output.timed_out);
+    assert_eq!(output.exit_code, None);
+    assert_eq!(output.output_tail[0].line, "started");
+    assert!(started.elapsed() < Duration::from_secs(5), "Process group was not killed");
+}
+
+#[tokio::test]
+async fn cancellation_kills_the_command() {
+    let logs = MemoryLogStore::new();
+    let started = Instant::now();
+
+    let result = execute_command(
+        &CommandPolicy::new(["/bin/sh"]),
+        &shell("sleep 30", None),
+        &logs,
+        &target(),
+        |_| {},
+        tokio::time::sleep(Duration::from_millis(200)),
+    )
+    .await;
+
+    assert!(matches!(result, Err(CommandError::Cancelled)));
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+fn command_workflow_requires_a_command() {
+    let workflow = registry().workflow(COMMAND_WORKFLOW).unwrap();
+
+    assert!(workflow.input(None).is_err());
+    let input = workflow.input(Some(json!({ "command": "/bin/echo", "args": ["hello"] }))).unwrap();
+    assert_eq!(input["args"], json!(["hello"]));
+    assert_eq!(input["env"], json!({}));
+
+    // Délai hors limites: refusé par l'API plutôt que de déborder dans le workflow
+    let err = workflow.input(Some(json!({ "command": "/bin/echo", "timeout_secs": u64::MAX }))).unwrap_err();
+    assert!(err.contains("timeout_secs"), "{}", err);
+}
+
+#[actix_web::test]
+async fn logs_endpoint_returns_stored_output() {
+    let db = setup_db().await;
+    let id = Uuid::new_v4();
+    create_execution(
+        &db,
+        ExecutionInput {
+            id,
+            workflow_id: "wf-command".to_string(),
+            run_id: "run-1".to_string(),
+            status: ExecutionStatus::Running,
+            schedule_id: None,
+            start_at: None,
+            previous_run_ids: vec![],
+            workflow_type: COMMAND_WORKFLOW.to_string(),
+            input: Some(json!({ "command": "/bin/echo" })),
+            parent_id: None,
+        },
+    )
+    .await
+    .unwrap();
+
+    let store: Arc<dyn LogStore> = Arc::new(DbLogStore::new(db.clone()));
+    for (stream, line) in [(LogStream::Stdout, "first"), (LogStream::Stderr, "second")] {
+        store
+            .append(LogLine { target: target(), stream, line: line.to_string(), logged_at: Utc::now() })
+            .await
+            .unwrap();
+    }
+
+    let app = test::init_service(App::new().app_data(web::Data::new(db.clone())).configure(controller::configure)).await;
+    let request = test::TestRequest::get().uri(&format!("/executions/{}/logs", id)).to_request();
+    let response = test::call_service(&app, request).await;
+    assert!(response.status().is_success());
+    let body: serde_json::Value = test::read_body_json(response).await;
+    assert_eq!(body[0]["line"], "first");
+    assert_eq!(body[1]["stream"], "stderr");
+
+    let request = test::TestRequest::get().uri(&format!("/executions/{}/logs", Uuid::new_v4())).to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), 404);
+}
@@ -0,0 +1,192 @@
+// Tests de bout en bout: serveur Temporal de dev local + binaire `main`.
+// Nécessitent la CLI `temporal` (ou TEMPORAL_CLI) et sont ignorés par défaut:
+//     cargo test --test e2e -- --ignored
+use std::{
+    net::{TcpListener, TcpStream},
+    path::Path,
+    process::{Child, Command, Stdio},
+    time::{Duration, Instant},
+};
+
+use serde_json::Value;
+use tempfile::TempDir;
+use tokio::time::sleep;
+
+// Processus tué à la fin du test, même en cas d'échec
+struct ChildGuard(Child);
+
+impl ChildGuard {
+    fn kill(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+impl Drop for ChildGuard {
+    fn drop(&mut self) {
+        self.kill();
+    }
+}
+
+fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .map(|addr| addr.port())
+        .expect("No free port available")
+}
+
+async fn wait_for_port(port: u16, timeout: Duration) {
+    let deadline = Instant::now() + timeout;
+    while TcpStream::connect(("127.0.0.1", port)).is_err() {
+        assert!(Instant::now() < deadline, "Port {} never opened", port);
+        sleep(Duration::from_millis(200)).await;
+    }
+}
+
+// Serveur Temporal de dev démarré sur un port aléatoire
+struct TemporalDevServer {
+    _process: ChildGuard,
+    port: u16,
+}
+
+impl TemporalDevServer {
+    async fn start(dir: &Path) -> Self {
+        let cli = std::env::var("TEMPORAL_CLI").unwrap_or_else(|_| "temporal".to_string());
+        let port = free_port();
+        let process = Command::new(cli)
+            .args(["server", "start-dev", "--headless", "--log-level", "error"])
+            .args(["--port", &port.to_string()])
+            .args(["--ui-port", &free_port().to_string()])
+            .args(["--db-filename", &dir.join("temporal.db").to_string_lossy()])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("Failed to start the Temporal dev server");
+        let server = TemporalDevServer { _process: ChildGuard(process), port };
+        wait_for_port(port, Duration::from_secs(30)).await;
+        server
+    }
+}
+
+// Instance de l'exécuteur (serveur HTTP + worker + scheduler)
+struct Executor {
+    process: ChildGuard,
+    base_url: String,
+    http: reqwest::Client,
+}
+
+impl Executor {
+    async fn start(temporal: &TemporalDevServer, dir: &Path) -> Self {
+        let port = free_port();
+        let process = Command::new(env!("CARGO_BIN_EXE_main"))
+            .env("TEMPORAL_URL", format!("http://127.0.0.1:{}", temporal.port))
+            .env("SERVER_URL", format!("127.0.0.1:{}", port))
+            .env("DATABASE_URL", format!("sqlite:{}?mode=rwc", dir.join("db.sqlite").display()))
+            .env("RUST_LOG", "warn")
+            .stdout(Stdio::null())
+            .spawn()
+            .expect("Failed to start the executor");
+        let executor = Executor {
+            process: ChildGuard(process),
+            base_url: format!("http://127.0.0.1:{}", port),
+            http: reqwest::Client::new(),
+        };
+        wait_for_port(port, Duration::from_secs(30)).await;
+        executor
+    }
+
+    fn stop(&mut self) {
+        self.process.kill();
+    }
+
+    async fn create_execution(&self) -> Value {
+        let response = self
+            .http
+            .post(format!("{}/executions", self.base_url))
+            .send()
+            .await
+            .unwrap();
+        assert!(response.status().is_success(), "POST /executions failed: {}", response.status());
+        response.json().await.unwrap()
+    }
+
+    async fn cancel_execution(&self, id: &str) {
+        let response = self
+            .http
+            .post(format!("{}/executions/{}/cancel", self.base_url, id))
+            .send()
+            .await
+            .unwrap();
+        assert!(response.status().is_success(), "Cancel failed: {}", response.status());
+    }
+
+    async fn status(&self, id: &str) -> String {
+        let execution: Value = self
+            .http
+            .get(format!("{}/executions/{}", self.base_url, id))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        execution["status"].as_str().unwrap_or_default().to_string()
+    }
+
+    async fn wait_for_status(&self, id: &str, expected: &str, timeout: Duration) {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let status = self.status(id).await;
+            if status == expected {
+                return;
+            }
+            assert!(Instant::now() < deadline, "Execution {} stuck in {} (expected {})", id, status, expected);
+            sleep(Duration::from_secs(1)).await;
+        }
+    }
+}
+
+#[tokio::test]
+#[ignore = "requires the Temporal CLI"]
+async fn execution_runs_to_completion() {
+    let dir = TempDir::new().unwrap();
+    let temporal = TemporalDevServer::start(dir.path()).await;
+    let executor = Executor::start(&temporal, dir.path()).await;
+
+    let execution = executor.create_execution().await;
+    let id = execution["id"].as_str().unwrap();
+    assert_eq!(execution["status"], "RUNNING");
+
+    executor.wait_for_status(id, "COMPLETED", Duration::from_secs(120)).await;
+}
+
+#[tokio::test]
+#[ignore = "requires the Temporal CLI"]
+async fn execution_can_be_cancelled() {
+    let dir = TempDir::new().unwrap();
+    let temporal = TemporalDevServer::start(dir.path()).await;
+    let executor = Executor::start(&temporal, dir.path()).await;
+
+    let execution = executor.create_execution().await;
+    let id = execution["id"].as_str().unwrap();
+    executor.cancel_execution(id).await;
+
+    executor.wait_for_status(id, "CANCELED", Duration::from_secs(30)).await;
+}
+
+#[tokio::test]
+#[ignore = "requires the Temporal CLI"]
+async fn execution_survives_worker_restart() {
+    let dir = TempDir::new().unwrap();
+    let temporal = TemporalDevServer::start(dir.path()).await;
+    let mut executor = Executor::start(&temporal, dir.path()).await;
+
+    let execution = executor.create_execution().await;
+    let id = execution["id"].as_str().unwrap().to_string();
+    // Laisser le worker prendre l'activité avant de l'arrêter
+    sleep(Duration::from_secs(10)).await;
+    executor.stop();
+
+    let executor = Executor::start(&temporal, dir.path()).await;
+    executor.wait_for_status(&id, "COMPLETED", Duration::from_secs(180)).await;
+}
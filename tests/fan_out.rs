@@ -0,0 +1,234 @@
+use std::sync::Arc;
+
+use actix_web::{test, web, App};
+use executor::{
+    controller,
+    executions::ExecutionStatus,
+    fan_out::{child_workflow_id, ChildClosePolicy, ChildExecution, FanOutInput, FanOutResult, MAX_CHILDREN},
+    helpers::{
+        child_store::{ChildStore, DbChildStore, MemoryChildStore},
+        client::SharedClient,
+        fake_client::FakeTemporalClient,
+    },
+    registry::{registry, FAN_OUT_WORKFLOW, REPEAT_WORKFLOW},
+    service::{create_execution, get_execution_by_workflow_id, list_child_executions, ExecutionInput},
+};
+use migration::{Migrator, MigratorTrait};
+use sea_orm::{Database, DatabaseConnection};
+use serde_json::{json, Value};
+use temporal_sdk_core_protos::{
+    coresdk::{
+        child_workflow::{child_workflow_result::Status, Cancellation, ChildWorkflowResult, Failure, Success},
+        AsJsonPayloadExt,
+    },
+    temporal::api::{enums::v1::ParentClosePolicy, failure::v1::Failure as TemporalFailure},
+};
+use uuid::Uuid;
+
+async fn setup_db() -> DatabaseConnection {
+    let db = Database::connect("sqlite::memory:").await.expect("Failed to open in-memory database");
+    Migrator::up(&db, None).await.expect("Failed to run migrations");
+    db
+}
+
+async fn insert_execution(db: &DatabaseConnection, workflow_id: &str, parent_id: Option<Uuid>) -> Uuid {
+    let id = Uuid::new_v4();
+    let input = ExecutionInput {
+        id,
+        workflow_id: workflow_id.to_string(),
+        run_id: "run".to_string(),
+        status: ExecutionStatus::Running,
+        schedule_id: None,
+        start_at: None,
+        previous_run_ids: vec![],
+        workflow_type: REPEAT_WORKFLOW.to_string(),
+        input: None,
+        parent_id,
+    };
+    create_execution(db, input).await.unwrap();
+    id
+}
+
+fn three_repeats() -> Value {
+    json!({
+        "children": [{ "workflow_type": REPEAT_WORKFLOW, "input": { "interval_secs": 1, "total_secs": 3 }, "count": 3 }],
+        "concurrency": 2
+    })
+}
+
+#[test]
+fn fan_out_definitions_are_validated() {
+    let workflow = registry().workflow(FAN_OUT_WORKFLOW).unwrap();
+    assert!(workflow.input(Some(three_repeats())).is_ok());
+
+    for (input, reason) in [
+        (json!({ "children": [] }), "aucun enfant"),
+        (json!({ "children": [{ "workflow_type": REPEAT_WORKFLOW, "count": 0 }] }), "aucun enfant"),
+        (json!({ "children": [{ "workflow_type": "send_email" }] }), "type de workflow inconnu"),
+        (json!({ "children": [{ "workflow_type": "command_workflow" }] }), "Entrée invalide pour command_workflow"),
+        (json!({ "children": [{ "workflow_type": REPEAT_WORKFLOW, "count": MAX_CHILDREN + 1 }] }), "au plus"),
+        (json!({ "children": [{ "workflow_type": REPEAT_WORKFLOW }], "concurrency": 0 }), "concurrency"),
+        (json!({ "children": [{ "workflow_type": REPEAT_WORKFLOW }], "parent_close_policy": "ignore" }), "unknown variant"),
+    ] {
+        let err = workflow.input(Some(input)).unwrap_err();
+        assert!(err.contains(reason), "{} should mention {}", err, reason);
+    }
+}
+
+#[test]
+fn child_inputs_are_completed_with_their_defaults() {
+    let workflow = registry().workflow(FAN_OUT_WORKFLOW).unwrap();
+    let input = workflow.input(Some(json!({ "children": [{ "workflow_type": REPEAT_WORKFLOW, "count": 2 }] }))).unwrap();
+    let input: FanOutInput = serde_json::from_value(input).unwrap();
+
+    assert_eq!(input.children[0].input, json!({ "interval_secs": 5, "total_secs": 60 }));
+    assert_eq!(input.concurrency(), 10);
+    assert_eq!(input.parent_close_policy, ChildClosePolicy::Terminate);
+    assert_eq!(input.parent_close_policy.to_proto(), ParentClosePolicy::Terminate);
+    assert_eq!(input.expand().len(), 2);
+}
+
+#[test]
+fn child_results_are_aggregated() {
+    let input: FanOutInput = serde_json::from_value(three_repeats()).unwrap();
+    let mut result = FanOutResult::new("wf-parent", &input);
+    assert_eq!(result.children[2].workflow_id, child_workflow_id("wf-parent", 2));
+    assert!(result.children.iter().all(|child| child.status == ExecutionStatus::Pending));
+
+    for index in 0..3 {
+        result.started(index, format!("run-{}", index));
+    }
+    result.finish(
+        0,
+        &ChildWorkflowResult {
+            status: Some(Status::Completed(Success {
+                result: Some(json!("done").as_json_payload().unwrap()),
+            })),
+        },
+    );
+    result.finish(
+        1,
+        &ChildWorkflowResult {
+            status: Some(Status::Failed(Failure {
+                failure: Some(TemporalFailure {
+                    message: "Child workflow execution failed".to_string(),
+                    cause: Some(Box::new(TemporalFailure {
+                        message: "Activity timed out".to_string(),
+                        ..Default::default()
+                    })),
+                    ..Default::default()
+                }),
+            })),
+        },
+    );
+    result.finish(2, &ChildWorkflowResult { status: Some(Status::Cancelled(Cancellation { failure: None })) });
+
+    assert_eq!((result.completed, result.failed, result.canceled), (1, 1, 1));
+    assert_eq!(result.children[0].status, ExecutionStatus::Completed);
+    assert_eq!(result.children[0].result, Some(json!("done")));
+    assert_eq!(result.children[0].run_id.as_deref(), Some("run-0"));
+    assert_eq!(result.children[1].status, ExecutionStatus::Failed);
+    assert_eq!(result.children[1].error.as_deref(), Some("Activity timed out"));
+    assert_eq!(result.children[2].status, ExecutionStatus::Canceled);
+}
+
+#[tokio::test]
+async fn memory_store_ignores_retried_records() {
+    let store = MemoryChildStore::new();
+    let child = ChildExecution {
+        workflow_id: child_workflow_id("wf-parent", 0),
+        run_id: "run-0".to_string(),
+        workflow_type: REPEAT_WORKFLOW.to_string(),
+        input: json!({}),
+    };
+    store.record_child("wf-parent", &child).await.unwrap();
+    store.record_child("wf-parent", &child).await.unwrap();
+
+    assert_eq!(store.children("wf-parent"), vec![child]);
+    assert!(store.children("wf-other").is_empty());
+}
+
+#[actix_web::test]
+async fn execution_tree_lists_children_recursively() {
+    let db = setup_db().await;
+    let client: SharedClient = Arc::new(FakeTemporalClient::new());
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.clone()))
+            .app_data(web::Data::new(client))
+            .configure(controller::configure),
+    )
+    .await;
+
+    let request = test::TestRequest::post()
+        .uri("/executions")
+        .set_json(json!({ "workflow_type": FAN_OUT_WORKFLOW, "input": three_repeats() }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert!(response.status().is_success());
+    let execution: Value = test::read_body_json(response).await;
+    let parent_workflow_id = execution["workflow_id"].as_str().unwrap();
+
+    // Ce que fait record_child_execution au démarrage de chaque enfant, le premier étant lui-même un fan-out
+    let store = DbChildStore::new(db.clone());
+    let child = |workflow_id: String, workflow_type: &str| ChildExecution {
+        workflow_id,
+        run_id: "run".to_string(),
+        workflow_type: workflow_type.to_string(),
+        input: json!({}),
+    };
+    let nested_id = child_workflow_id(parent_workflow_id, 0);
+    store.record_child(parent_workflow_id, &child(nested_id.clone(), FAN_OUT_WORKFLOW)).await.unwrap();
+    store.record_child(parent_workflow_id, &child(child_workflow_id(parent_workflow_id, 1), REPEAT_WORKFLOW)).await.unwrap();
+    store.record_child(&nested_id, &child(child_workflow_id(&nested_id, 0), REPEAT_WORKFLOW)).await.unwrap();
+    // Nouvelle tentative de l'activité: pas de doublon
+    store.record_child(&nested_id, &child(child_workflow_id(&nested_id, 0), REPEAT_WORKFLOW)).await.unwrap();
+
+    let parent = get_execution_by_workflow_id(&db, parent_workflow_id).await.unwrap().unwrap();
+    assert_eq!(list_child_executions(&db, parent.id).await.unwrap().len(), 2);
+
+    let uri = format!("/executions/{}/tree", execution["id"].as_str().unwrap());
+    let response = test::call_service(&app, test::TestRequest::get().uri(&uri).to_request()).await;
+    assert!(response.status().is_success());
+    let tree: Value = test::read_body_json(response).await;
+    assert_eq!(tree["workflow_id"], parent_workflow_id);
+    assert_eq!(tree["children"].as_array().unwrap().len(), 2);
+    let nested = tree["children"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|child| child["workflow_id"] == nested_id.as_str())
+        .unwrap();
+    assert_eq!(nested["status"], "RUNNING");
+    assert_eq!(nested["parent_id"], execution["id"]);
+    assert_eq!(nested["children"][0]["workflow_id"], child_workflow_id(&nested_id, 0));
+    assert_eq!(nested["children"][0]["children"], json!([]));
+
+    let uri = format!("/executions/{}/tree", Uuid::new_v4());
+    let response = test::call_service(&app, test::TestRequest::get().uri(&uri).to_request()).await;
+    assert_eq!(response.status(), 404);
+}
+
+#[tokio::test]
+async fn recording_an_already_tracked_child_links_it_to_its_parent() {
+    let db = setup_db().await;
+    let store = DbChildStore::new(db.clone());
+    let child = ChildExecution {
+        workflow_id: "wf-parent-child-0".to_string(),
+        run_id: "run-0".to_string(),
+        workflow_type: REPEAT_WORKFLOW.to_string(),
+        input: json!({}),
+    };
+
+    // Parent inconnu: l'activité échoue et sera retentée
+    assert!(store.record_child("wf-parent", &child).await.is_err());
+
+    let parent_id = insert_execution(&db, "wf-parent", None).await;
+    // Enfant déjà importé, sans lien (ex: par le réconciliateur)
+    let child_id = insert_execution(&db, &child.workflow_id, None).await;
+    store.record_child("wf-parent", &child).await.unwrap();
+
+    let recorded = get_execution_by_workflow_id(&db, &child.workflow_id).await.unwrap().unwrap();
+    assert_eq!(recorded.id, child_id);
+    assert_eq!(recorded.parent_id, Some(parent_id));
+}
@@ -0,0 +1,153 @@
+use std::{
+    net::{SocketAddr, TcpListener},
+    time::Duration,
+};
+
+use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer};
+use executor::{
+    activities::http::{is_retryable_status, send_http_request, HttpRequestError, HttpRequestInput, MAX_BODY_BYTES},
+    registry::{registry, HTTP_WORKFLOW},
+};
+use serde_json::{json, Value};
+
+// Serveur HTTP local simulant les endpoints internes appelés par les workflows
+fn start_stub_server() -> SocketAddr {
+    let server = HttpServer::new(|| {
+        App::new()
+            .route("/ok", web::get().to(|| async { HttpResponse::Ok().json(json!({ "done": true })) }))
+            .route(
+                "/echo",
+                web::post().to(|req: HttpRequest, body: web::Bytes| async move {
+                    let token = req.headers().get("x-token").and_then(|value| value.to_str().ok()).unwrap_or_default().to_string();
+                    let body: Value = serde_json::from_slice(&body).unwrap_or(Value::Null);
+                    HttpResponse::Created().json(json!({ "token": token, "body": body }))
+                }),
+            )
+            .route("/text", web::get().to(|| async { HttpResponse::Ok().body("plain text") }))
+            .route("/large", web::get().to(|| async { HttpResponse::Ok().body(vec![b'a'; MAX_BODY_BYTES + 10]) }))
+            .route("/missing", web::get().to(|| async { HttpResponse::NotFound().body("not here") }))
+            .route(
+                "/busy",
+                web::get().to(|| async { HttpResponse::ServiceUnavailable().insert_header(("Retry-After", "7")).finish() }),
+            )
+            .route(
+                "/slow",
+                web::get().to(|| async {
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    HttpResponse::Ok().finish()
+                }),
+            )
+    })
+    .workers(1)
+    .bind(("127.0.0.1", 0))
+    .expect("Failed to bind the stub server");
+    let addr = server.addrs()[0];
+    actix_web::rt::spawn(server.run());
+    addr
+}
+
+fn request(method: &str, url: String) -> HttpRequestInput {
+    HttpRequestInput { method: method.to_string(), url, headers: Default::default(), body: None, timeout_secs: None }
+}
+
+#[actix_web::test]
+async fn successful_response_is_returned_with_its_body() {
+    let addr = start_stub_server();
+    let client = reqwest::Client::new();
+
+    let response = send_http_request(&client, &request("GET", format!("http://{}/ok", addr))).await.unwrap();
+    assert_eq!(response.status, 200);
+    assert_eq!(response.body, json!({ "done": true }));
+    assert_eq!(response.headers["content-type"], "application/json");
+
+    let response = send_http_request(&client, &request("GET", format!("http://{}/text", addr))).await.unwrap();
+    assert_eq!(response.body, json!("plain text"));
+    assert!(!response.truncated);
+}
+
+#[actix_web::test]
+async fn large_bodies_are_cut_at_the_limit() {
+    let addr = start_stub_server();
+
+    let response = send_http_request(&reqwest::Client::new(), &request("GET", format!("http://{}/large", addr))).await.unwrap();
+    assert!(response.truncated);
+    assert_eq!(response.body.as_str().unwrap().len(), MAX_BODY_BYTES);
+}
+
+#[actix_web::test]
+async fn method_headers_and_json_body_are_sent() {
+    let addr = start_stub_server();
+    let mut input = request("post", format!("http://{}/echo", addr));
+    input.headers.insert("X-Token".to_string(), "secret".to_string());
+    input.body = Some(json!({ "job": 42 }));
+
+    let response = send_http_request(&reqwest::Client::new(), &input).await.unwrap();
+    assert_eq!(response.status, 201);
+    assert_eq!(response.body, json!({ "token": "secret", "body": { "job": 42 } }));
+}
+
+#[actix_web::test]
+async fn client_errors_are_not_retryable() {
+    let addr = start_stub_server();
+
+    let err = send_http_request(&reqwest::Client::new(), &request("GET", format!("http://{}/missing", addr))).await.unwrap_err();
+    assert!(!err.is_retryable());
+    assert!(matches!(&err, HttpRequestError::Status(response) if response.status == 404 && response.body == json!("not here")));
+
+    let err = send_http_request(&reqwest::Client::new(), &request("GET", "not a url".to_string())).await.unwrap_err();
+    assert!(matches!(err, HttpRequestError::InvalidRequest(_)));
+    assert!(!err.is_retryable());
+
+    // En-tête invalide: erreur de construction de la requête, pas une erreur réseau
+    let mut input = request("GET", format!("http://{}/ok", addr));
+    input.headers.insert("bad header".to_string(), "value".to_string());
+    let err = send_http_request(&reqwest::Client::new(), &input).await.unwrap_err();
+    assert!(matches!(err, HttpRequestError::InvalidRequest(_)));
+    assert!(!err.is_retryable());
+}
+
+#[actix_web::test]
+async fn server_errors_are_retried_after_the_requested_delay() {
+    let addr = start_stub_server();
+
+    let err = send_http_request(&reqwest::Client::new(), &request("GET", format!("http://{}/busy", addr))).await.unwrap_err();
+    assert!(err.is_retryable());
+    assert_eq!(err.retry_after(), Some(Duration::from_secs(7)));
+}
+
+#[actix_web::test]
+async fn transport_errors_and_timeouts_are_retryable() {
+    let addr = start_stub_server();
+    let mut slow = request("GET", format!("http://{}/slow", addr));
+    slow.timeout_secs = Some(1);
+
+    let err = send_http_request(&reqwest::Client::new(), &slow).await.unwrap_err();
+    assert!(matches!(err, HttpRequestError::Transport(_)));
+    assert!(err.is_retryable());
+
+    // Port libre: connexion refusée
+    let closed = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+    let err = send_http_request(&reqwest::Client::new(), &request("GET", format!("http://{}/ok", closed))).await.unwrap_err();
+    assert!(err.is_retryable());
+}
+
+#[test]
+fn status_classes_map_to_retry_decisions() {
+    for status in [408, 429, 500, 502, 503, 504] {
+        assert!(is_retryable_status(status), "{} should be retried", status);
+    }
+    for status in [301, 400, 401, 403, 404, 409, 422] {
+        assert!(!is_retryable_status(status), "{} should not be retried", status);
+    }
+}
+
+#[test]
+fn http_workflow_input_is_completed_with_defaults() {
+    let workflow = registry().workflow(HTTP_WORKFLOW).unwrap();
+
+    assert!(workflow.input(None).is_err());
+    let input = workflow.input(Some(json!({ "url": "http://localhost/ok" }))).unwrap();
+    assert_eq!(input["method"], "GET");
+    assert_eq!(input["headers"], json!({}));
+    assert!(workflow.input(Some(json!({ "url": "http://localhost/ok", "timeout_secs": u64::MAX }))).is_err());
+}
@@ -0,0 +1,89 @@
+use std::{sync::Arc, time::Duration};
+
+use actix_web::{test, web, App};
+use chrono::Utc;
+use executor::{
+    controller,
+    workers::start_lease_heartbeat,
+    helpers::{client::SharedClient, fake_client::FakeTemporalClient, status_tracker::{SharedStatusTracker, StatusTracker}},
+    leader::{current_leader, try_acquire_lease, LEASE_TTL, SCHEDULER_LEASE},
+};
+use migration::{Migrator, MigratorTrait};
+use sea_orm::{Database, DatabaseConnection};
+
+async fn setup_db() -> DatabaseConnection {
+    let db = Database::connect("sqlite::memory:").await.expect("Failed to open in-memory database");
+    Migrator::up(&db, None).await.expect("Failed to run migrations");
+    db
+}
+
+#[tokio::test]
+async fn only_one_instance_holds_the_lease() {
+    let db = setup_db().await;
+    let now = Utc::now();
+
+    assert!(try_acquire_lease(&db, SCHEDULER_LEASE, "a", LEASE_TTL, now).await.unwrap());
+    assert!(!try_acquire_lease(&db, SCHEDULER_LEASE, "b", LEASE_TTL, now).await.unwrap());
+
+    // Le leader renouvelle son bail avant expiration
+    let later = now + chrono::Duration::seconds(20);
+    assert!(try_acquire_lease(&db, SCHEDULER_LEASE, "a", LEASE_TTL, later).await.unwrap());
+    assert!(!try_acquire_lease(&db, SCHEDULER_LEASE, "b", LEASE_TTL, now + chrono::Duration::seconds(40)).await.unwrap());
+
+    let leader = current_leader(&db, SCHEDULER_LEASE, later).await.unwrap().unwrap();
+    assert_eq!(leader.owner, "a");
+}
+
+#[tokio::test]
+async fn heartbeat_marks_only_the_lease_holder_as_leader() {
+    let db = Arc::new(setup_db().await);
+    let first: SharedStatusTracker = Arc::new(StatusTracker::with_instance_id("a".to_string()));
+    let second: SharedStatusTracker = Arc::new(StatusTracker::with_instance_id("b".to_string()));
+
+    // Le premier renouvellement est immédiat
+    start_lease_heartbeat(db.clone(), first.clone()).await;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    start_lease_heartbeat(db.clone(), second.clone()).await;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    assert!(first.is_leader());
+    assert!(!second.is_leader());
+}
+
+#[tokio::test]
+async fn lease_fails_over_when_the_leader_stops_renewing() {
+    let db = setup_db().await;
+    let now = Utc::now();
+    let ttl = Duration::from_secs(30);
+
+    assert!(try_acquire_lease(&db, SCHEDULER_LEASE, "a", ttl, now).await.unwrap());
+
+    let expired = now + chrono::Duration::seconds(31);
+    assert!(current_leader(&db, SCHEDULER_LEASE, expired).await.unwrap().is_none());
+    assert!(try_acquire_lease(&db, SCHEDULER_LEASE, "b", ttl, expired).await.unwrap());
+    assert!(!try_acquire_lease(&db, SCHEDULER_LEASE, "a", ttl, expired).await.unwrap());
+    assert_eq!(current_leader(&db, SCHEDULER_LEASE, expired).await.unwrap().unwrap().owner, "b");
+}
+
+#[actix_web::test]
+async fn scheduler_endpoint_reports_the_current_leader() {
+    let db = setup_db().await;
+    try_acquire_lease(&db, SCHEDULER_LEASE, "replica-2", LEASE_TTL, Utc::now()).await.unwrap();
+    let tracker: SharedStatusTracker = Arc::new(StatusTracker::with_instance_id("replica-1".to_string()));
+    let client: SharedClient = Arc::new(FakeTemporalClient::new());
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .app_data(web::Data::new(client))
+            .app_data(web::Data::new(tracker))
+            .configure(controller::configure),
+    )
+    .await;
+
+    let response = test::call_service(&app, test::TestRequest::get().uri("/admin/scheduler").to_request()).await;
+    assert!(response.status().is_success());
+    let body: serde_json::Value = test::read_body_json(response).await;
+    assert_eq!(body["instance_id"], "replica-1");
+    assert_eq!(body["leader"]["owner"], "replica-2");
+    assert_eq!(body["is_leader"], false);
+}
@@ -0,0 +1,291 @@
+use std::sync::Arc;
+
+use actix_web::{test, web, App};
+use executor::{
+    controller,
+    execution_steps::StepStatus,
+    helpers::{
+        client::SharedClient,
+        fake_client::FakeTemporalClient,
+        step_store::{DbStepStore, StepStore},
+    },
+    pipeline::{step_activity_id, CompensationStatus, CompensationTrigger, PipelineInput, PipelineState},
+    registry::{registry, HTTP_REQUEST_ACTIVITY, PIPELINE_WORKFLOW},
+};
+use migration::{Migrator, MigratorTrait};
+use sea_orm::{Database, DatabaseConnection};
+use serde_json::{json, Value};
+use temporal_sdk_core_protos::temporal::api::enums::v1::WorkflowExecutionStatus;
+
+async fn setup_db() -> DatabaseConnection {
+    let db = Database::connect("sqlite::memory:").await.expect("Failed to open in-memory database");
+    Migrator::up(&db, None).await.expect("Failed to run migrations");
+    db
+}
+
+// build -> (test, lint) -> deploy
+fn diamond() -> Value {
+    json!({
+        "steps": [
+            { "id": "build", "activity": "run_command", "input": { "command": "make" } },
+            { "id": "test", "activity": "run_command", "input": { "command": "make", "args": ["test"] }, "depends_on": ["build"] },
+            { "id": "lint", "activity": "run_command", "input": { "command": "make", "args": ["lint"] }, "depends_on": ["build"] },
+            {
+                "id": "deploy",
+                "activity": "http_request",
+                "input": { "method": "POST", "url": "http://deploy.internal/releases" },
+                "depends_on": ["test", "lint"],
+                "retry": { "maximum_attempts": 10 },
+                "timeout_secs": 120
+            }
+        ]
+    })
+}
+
+#[test]
+fn pipeline_definitions_are_validated() {
+    let workflow = registry().workflow(PIPELINE_WORKFLOW).unwrap();
+    assert!(workflow.input(Some(diamond())).is_ok());
+
+    for (steps, reason) in [
+        (json!([]), "aucune étape"),
+        (json!([{ "id": "a", "activity": "run_command" }, { "id": "a", "activity": "run_command" }]), "deux fois"),
+        (json!([{ "id": "a", "activity": "send_email" }]), "activité inconnue"),
+        (json!([{ "id": "a", "activity": "record_pipeline_steps" }]), "activité inconnue"),
+        (json!([{ "id": "a", "activity": "run_command", "depends_on": ["b"] }]), "étape inconnue"),
+        (
+            json!([
+                { "id": "a", "activity": "run_command", "depends_on": ["c"] },
+                { "id": "b", "activity": "run_command", "depends_on": ["a"] },
+                { "id": "c", "activity": "run_command", "depends_on": ["b"] }
+            ]),
+            "cycle entre les étapes a, b, c",
+        ),
+    ] {
+        let err = workflow.input(Some(json!({ "steps": steps }))).unwrap_err();
+        assert!(err.contains(reason), "{} should mention {}", err, reason);
+    }
+}
+
+#[test]
+fn step_options_override_activity_defaults() {
+    let input: PipelineInput = serde_json::from_value(diamond()).unwrap();
+    let deploy = &input.steps[3];
+
+    let options = deploy.activity_options().unwrap();
+    assert_eq!(options.activity_type, HTTP_REQUEST_ACTIVITY);
+    assert_eq!(options.activity_id, Some(step_activity_id("deploy")));
+    assert_eq!(options.start_to_close_timeout, Some(std::time::Duration::from_secs(120)));
+    let retry = options.retry_policy.unwrap();
+    assert_eq!(retry.maximum_attempts, 10);
+    assert_eq!(retry.initial_interval.unwrap().seconds, 1);
+}
+
+#[test]
+fn independent_steps_become_ready_together() {
+    let input: PipelineInput = serde_json::from_value(diamond()).unwrap();
+    let mut state = PipelineState::new(&input);
+
+    assert_eq!(state.ready_steps(), vec![0]);
+    state.start(0, None);
+    assert!(state.ready_steps().is_empty());
+    state.complete(0, json!({ "exit_code": 0 }), None);
+    assert_eq!(state.ready_steps(), vec![1, 2]);
+
+    state.start(1, None);
+    state.start(2, None);
+    state.complete(1, Value::Null, None);
+    assert!(state.ready_steps().is_empty(), "deploy waits for lint");
+    state.complete(2, Value::Null, None);
+    assert_eq!(state.ready_steps(), vec![3]);
+}
+
+#[test]
+fn a_failed_step_skips_its_dependents() {
+    let input: PipelineInput = serde_json::from_value(diamond()).unwrap();
+    let mut state = PipelineState::new(&input);
+    state.start(0, None);
+    state.complete(0, Value::Null, None);
+    state.start(1, None);
+    state.start(2, None);
+
+    state.fail(1, "Activity failed: exit code 2".to_string(), None);
+    state.complete(2, Value::Null, None);
+    assert!(state.has_failed());
+    state.close_pending(StepStatus::Skipped);
+
+    assert_eq!(state.failed_step_ids(), vec!["test"]);
+    assert_eq!(state.status_of("lint"), Some(StepStatus::Completed));
+    assert_eq!(state.status_of("deploy"), Some(StepStatus::Skipped));
+}
+
+#[actix_web::test]
+async fn steps_endpoint_reads_stored_steps_then_live_query() {
+    let db = setup_db().await;
+    let fake = Arc::new(FakeTemporalClient::new());
+    let client: SharedClient = fake.clone();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.clone()))
+            .app_data(web::Data::new(client))
+            .configure(controller::configure),
+    )
+    .await;
+
+    let request = test::TestRequest::post()
+        .uri("/executions")
+        .set_json(json!({ "workflow_type": PIPELINE_WORKFLOW, "input": diamond() }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert!(response.status().is_success());
+    let execution: Value = test::read_body_json(response).await;
+    let workflow_id = execution["workflow_id"].as_str().unwrap();
+    let run_id = execution["run_id"].as_str().unwrap();
+
+    // Deux enregistrements successifs: la ligne de chaque étape est mise à jour
+    let input: PipelineInput = serde_json::from_value(diamond()).unwrap();
+    let mut state = PipelineState::new(&input);
+    let store = DbStepStore::new(db.clone());
+    store.save(workflow_id, run_id, &state.steps).await.unwrap();
+    state.start(0, None);
+    state.complete(0, json!({ "exit_code": 0 }), None);
+    store.save(workflow_id, run_id, &state.steps).await.unwrap();
+    fake.set_status(workflow_id, WorkflowExecutionStatus::Completed);
+
+    let uri = format!("/executions/{}/steps", execution["id"].as_str().unwrap());
+    let response = test::call_service(&app, test::TestRequest::get().uri(&uri).to_request()).await;
+    assert!(response.status().is_success());
+    let body: Value = test::read_body_json(response).await;
+    assert_eq!(body["source"], "database");
+    assert_eq!(body["steps"].as_array().unwrap().len(), 4);
+    assert_eq!(body["steps"][0]["status"], "COMPLETED");
+    assert_eq!(body["steps"][0]["result"]["exit_code"], 0);
+    assert_eq!(body["steps"][3]["depends_on"], json!(["test", "lint"]));
+
+    // Workflow en cours: la table reste la seule source, sans appel à Temporal
+    fake.set_status(workflow_id, WorkflowExecutionStatus::Running);
+    state.start(1, None);
+    store.save(workflow_id, run_id, &state.steps).await.unwrap();
+    let describes = fake.describe_calls();
+    let response = test::call_service(&app, test::TestRequest::get().uri(&uri).to_request()).await;
+    let body: Value = test::read_body_json(response).await;
+    assert_eq!(body["source"], "database");
+    assert_eq!(body["steps"][1]["status"], "RUNNING");
+    assert_eq!(fake.describe_calls(), describes);
+}
+
+// reserve -> charge -> ship, les deux premières étapes étant compensables
+fn order_saga() -> Value {
+    json!({
+        "steps": [
+            {
+                "id": "reserve",
+                "activity": "http_request",
+                "input": { "method": "POST", "url": "http://stock.internal/reservations" },
+                "compensate": { "activity": "http_request", "input": { "method": "DELETE", "url": "http://stock.internal/reservations/42" } }
+            },
+            {
+                "id": "charge",
+                "activity": "http_request",
+                "input": { "method": "POST", "url": "http://billing.internal/charges" },
+                "compensate": { "activity": "http_request", "input": { "method": "POST", "url": "http://billing.internal/refunds" }, "retry": { "maximum_attempts": 20 } }
+            },
+            { "id": "notify", "activity": "http_request", "input": { "url": "http://mail.internal/notify" } },
+            { "id": "ship", "activity": "run_command", "input": { "command": "ship" }, "depends_on": ["reserve", "charge", "notify"] }
+        ]
+    })
+}
+
+#[test]
+fn compensation_activities_are_validated() {
+    let workflow = registry().workflow(PIPELINE_WORKFLOW).unwrap();
+    assert!(workflow.input(Some(order_saga())).is_ok());
+
+    let invalid = json!({ "steps": [{ "id": "a", "activity": "run_command", "compensate": { "activity": "undo_everything" } }] });
+    let err = workflow.input(Some(invalid)).unwrap_err();
+    assert!(err.contains("activité de compensation inconnue"), "{}", err);
+}
+
+#[test]
+fn completed_steps_are_compensated_in_reverse_order() {
+    let input: PipelineInput = serde_json::from_value(order_saga()).unwrap();
+    let mut state = PipelineState::new(&input);
+    for index in [0, 1, 2] {
+        state.start(index, None);
+    }
+    // charge se termine avant reserve: elle sera compensée en dernier
+    state.complete(1, Value::Null, None);
+    state.complete(0, Value::Null, None);
+    state.fail(2, "Activity failed: HTTP 500".to_string(), None);
+    state.close_pending(StepStatus::Skipped);
+
+    let mut compensation = state.compensation_plan(&input, CompensationTrigger::Failure).unwrap();
+    let order: Vec<&str> = compensation.steps.iter().map(|step| step.step_id.as_str()).collect();
+    assert_eq!(order, vec!["reserve", "charge"]);
+    assert_eq!(compensation.status, CompensationStatus::Running);
+
+    let options = input.steps[1].compensate.as_ref().unwrap().activity_options("charge").unwrap();
+    assert_eq!(options.activity_id, Some("compensate:charge".to_string()));
+    assert_eq!(options.retry_policy.unwrap().maximum_attempts, 20);
+
+    compensation.start(0, None);
+    compensation.complete(0, None);
+    state.compensated(0);
+    compensation.start(1, None);
+    compensation.fail(1, "Activity failed: HTTP 409".to_string(), None);
+    compensation.finish();
+    assert_eq!(compensation.status, CompensationStatus::Failed);
+    assert_eq!(compensation.failed_step_ids(), vec!["charge"]);
+    assert_eq!(state.status_of("reserve"), Some(StepStatus::Compensated));
+    assert_eq!(state.status_of("charge"), Some(StepStatus::Completed));
+}
+
+#[test]
+fn nothing_to_compensate_without_completed_compensable_steps() {
+    let input: PipelineInput = serde_json::from_value(order_saga()).unwrap();
+    let mut state = PipelineState::new(&input);
+    state.start(2, None);
+    state.complete(2, Value::Null, None);
+
+    assert!(state.compensation_plan(&input, CompensationTrigger::Cancellation).is_none());
+}
+
+#[actix_web::test]
+async fn compensation_is_visible_on_the_execution() {
+    let db = setup_db().await;
+    let client: SharedClient = Arc::new(FakeTemporalClient::new());
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.clone()))
+            .app_data(web::Data::new(client))
+            .configure(controller::configure),
+    )
+    .await;
+
+    let request = test::TestRequest::post()
+        .uri("/executions")
+        .set_json(json!({ "workflow_type": PIPELINE_WORKFLOW, "input": order_saga() }))
+        .to_request();
+    let execution: Value = test::read_body_json(test::call_service(&app, request).await).await;
+    assert_eq!(execution["compensation"], Value::Null);
+
+    let input: PipelineInput = serde_json::from_value(order_saga()).unwrap();
+    let mut state = PipelineState::new(&input);
+    state.start(0, None);
+    state.complete(0, Value::Null, None);
+    let mut compensation = state.compensation_plan(&input, CompensationTrigger::Cancellation).unwrap();
+    compensation.start(0, None);
+    compensation.fail(0, "Activity failed: HTTP 503".to_string(), None);
+    compensation.finish();
+    DbStepStore::new(db.clone())
+        .save_compensation(execution["workflow_id"].as_str().unwrap(), &compensation)
+        .await
+        .unwrap();
+
+    let uri = format!("/executions/{}", execution["id"].as_str().unwrap());
+    let body: Value = test::read_body_json(test::call_service(&app, test::TestRequest::get().uri(&uri).to_request()).await).await;
+    assert_eq!(body["compensation"]["status"], "FAILED");
+    assert_eq!(body["compensation"]["trigger"], "cancellation");
+    assert_eq!(body["compensation"]["steps"][0]["step_id"], "reserve");
+    assert_eq!(body["compensation"]["steps"][0]["error"], "Activity failed: HTTP 503");
+}
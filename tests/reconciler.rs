@@ -0,0 +1,292 @@
+use std::{sync::Arc, time::Duration};
+
+use actix_web::{test, web, App};
+use executor::{
+    controller,
+    executions::ExecutionStatus,
+    registry::REPEAT_WORKFLOW,
+    helpers::{
+        client::{SharedClient, StartOptions},
+        fake_client::FakeTemporalClient,
+    },
+    reconciler::{latest_drift_report, reconcile_executions, reconcile_visibility, save_drift_report, ReconcileReport, PENDING_GRACE},
+    service::{create_execution, get_execution_by_id, get_execution_by_workflow_id, init_workflow, list_executions, ExecutionInput},
+};
+use migration::{Migrator, MigratorTrait};
+use sea_orm::{Database, DatabaseConnection};
+use temporal_sdk_core_protos::temporal::api::enums::v1::WorkflowExecutionStatus;
+use uuid::Uuid;
+
+async fn setup_db() -> DatabaseConnection {
+    let db = Database::connect("sqlite::memory:").await.expect("Failed to open in-memory database");
+    Migrator::up(&db, None).await.expect("Failed to run migrations");
+    db
+}
+
+async fn insert_execution(db: &DatabaseConnection, workflow_id: &str, run_id: &str, status: ExecutionStatus) -> Uuid {
+    let id = Uuid::new_v4();
+    let input = ExecutionInput {
+        id,
+        workflow_id: workflow_id.to_string(),
+        run_id: run_id.to_string(),
+        status,
+        schedule_id: None,
+        start_at: None,
+        previous_run_ids: vec![],
+        workflow_type: REPEAT_WORKFLOW.to_string(),
+        input: None,
+        parent_id: None,
+    };
+    create_execution(db, input).await.unwrap();
+    id
+}
+
+async fn insert_pending_execution(db: &DatabaseConnection, workflow_id: &str) -> Uuid {
+    insert_execution(db, workflow_id, "", ExecutionStatus::Pending).await
+}
+
+#[tokio::test]
+async fn reconciler_confirms_pending_execution_whose_workflow_started() {
+    let db = setup_db().await;
+    let client = FakeTemporalClient::new();
+    let id = insert_pending_execution(&db, "wf-orphan").await;
+    let (_, run_id) = init_workflow(&client, "wf-orphan".to_string(), StartOptions::default()).await.unwrap();
+
+    let report = reconcile_executions(&client, &db, Duration::ZERO).await.unwrap();
+
+    assert_eq!(report, ReconcileReport { confirmed: 1, restarted: 0 });
+    let execution = get_execution_by_id(&db, id).await.unwrap().unwrap();
+    assert_eq!(execution.status, ExecutionStatus::Running);
+    assert_eq!(execution.run_id, run_id);
+    assert_eq!(client.workflow_count(), 1);
+}
+
+#[tokio::test]
+async fn reconciler_starts_dangling_pending_execution() {
+    let db = setup_db().await;
+    let client = FakeTemporalClient::new();
+    let id = insert_pending_execution(&db, "wf-dangling").await;
+
+    let report = reconcile_executions(&client, &db, Duration::ZERO).await.unwrap();
+
+    assert_eq!(report, ReconcileReport { confirmed: 0, restarted: 1 });
+    let execution = get_execution_by_id(&db, id).await.unwrap().unwrap();
+    assert_eq!(execution.status, ExecutionStatus::Running);
+    assert_eq!(execution.run_id, client.workflow("wf-dangling").unwrap().run_id);
+}
+
+#[tokio::test]
+async fn reconciler_leaves_recent_and_unreachable_reservations() {
+    let db = setup_db().await;
+    let client = FakeTemporalClient::new();
+    let id = insert_pending_execution(&db, "wf-recent").await;
+
+    // Réservation récente: la requête POST /executions est peut-être encore en cours
+    let report = reconcile_executions(&client, &db, PENDING_GRACE).await.unwrap();
+    assert_eq!(report, ReconcileReport::default());
+
+    // Temporal indisponible: la réservation sera reprise au prochain passage
+    client.set_unavailable(true);
+    let report = reconcile_executions(&client, &db, Duration::ZERO).await.unwrap();
+    assert_eq!(report, ReconcileReport::default());
+
+    assert_eq!(get_execution_by_id(&db, id).await.unwrap().unwrap().status, ExecutionStatus::Pending);
+}
+
+#[tokio::test]
+async fn reconciler_does_not_restart_on_transient_describe_error() {
+    let db = setup_db().await;
+    let client = FakeTemporalClient::new();
+    let id = insert_pending_execution(&db, "wf-transient").await;
+
+    client.set_describe_unavailable(true);
+    let report = reconcile_executions(&client, &db, Duration::ZERO).await.unwrap();
+    assert_eq!(report, ReconcileReport::default());
+    assert_eq!(client.workflow_count(), 0);
+    assert_eq!(get_execution_by_id(&db, id).await.unwrap().unwrap().status, ExecutionStatus::Pending);
+
+    client.set_describe_unavailable(false);
+    let report = reconcile_executions(&client, &db, Duration::ZERO).await.unwrap();
+    assert_eq!(report, ReconcileReport { confirmed: 0, restarted: 1 });
+}
+
+#[actix_web::test]
+async fn retry_with_same_key_resumes_failed_start() {
+    let db = setup_db().await;
+    let fake = Arc::new(FakeTemporalClient::new());
+    let client: SharedClient = fake.clone();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.clone()))
+            .app_data(web::Data::new(client))
+            .configure(controller::configure),
+    )
+    .await;
+    let request = || test::TestRequest::post().uri("/executions").insert_header(("Idempotency-Key", "retry-me")).to_request();
+
+    fake.set_unavailable(true);
+    assert!(test::call_service(&app, request()).await.status().is_server_error());
+
+    fake.set_unavailable(false);
+    let response = test::call_service(&app, request()).await;
+    assert!(response.status().is_success());
+    let body: serde_json::Value = test::read_body_json(response).await;
+    assert_eq!(body["status"], "RUNNING");
+
+    let executions = list_executions(&db).await.unwrap();
+    assert_eq!(executions.len(), 1);
+    assert_eq!(fake.workflow_count(), 1);
+}
+
+#[actix_web::test]
+async fn retry_with_same_key_keeps_the_original_start_time() {
+    let db = setup_db().await;
+    let fake = Arc::new(FakeTemporalClient::new());
+    let client: SharedClient = fake.clone();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.clone()))
+            .app_data(web::Data::new(client))
+            .configure(controller::configure),
+    )
+    .await;
+    let request = |start_delay_secs: u64| {
+        test::TestRequest::post()
+            .uri("/executions")
+            .insert_header(("Idempotency-Key", "delayed"))
+            .set_json(serde_json::json!({ "start_delay_secs": start_delay_secs }))
+            .to_request()
+    };
+
+    fake.set_unavailable(true);
+    assert!(test::call_service(&app, request(3600)).await.status().is_server_error());
+    let reserved = list_executions(&db).await.unwrap().remove(0);
+
+    // Nouvel essai avec un autre délai: la réservation garde son heure de démarrage
+    fake.set_unavailable(false);
+    let response = test::call_service(&app, request(60)).await;
+    assert!(response.status().is_success());
+    let body: serde_json::Value = test::read_body_json(response).await;
+    assert_eq!(body["status"], "SCHEDULED");
+
+    let execution = get_execution_by_id(&db, reserved.id).await.unwrap().unwrap();
+    assert_eq!(execution.start_at, reserved.start_at);
+    let delay = fake.workflow(&execution.workflow_id).unwrap().options.start_delay.unwrap();
+    assert!(delay > Duration::from_secs(3500), "Started with the retry's delay: {:?}", delay);
+}
+
+#[tokio::test]
+async fn visibility_reconciliation_imports_foreign_runs_and_reports_drift() {
+    let db = setup_db().await;
+    let client = FakeTemporalClient::new();
+
+    // Démarré par bin/client.rs, inconnu de la base
+    let (_, foreign_run_id) = init_workflow(&client, "repeat-workflow-cli".to_string(), StartOptions::default()).await.unwrap();
+    client.set_foreign("repeat-workflow-cli");
+    // Démarré par le service puis supprimé de la base, avec un workflow ID généré ou fourni par le client
+    init_workflow(&client, "wf-deleted".to_string(), StartOptions::default()).await.unwrap();
+    init_workflow(&client, "order-42".to_string(), StartOptions::default()).await.unwrap();
+    // Enregistré mais disparu côté Temporal
+    let missing_id = insert_execution(&db, "wf-gone", "run-gone", ExecutionStatus::Running).await;
+    // Terminé côté Temporal, pas encore synchronisé
+    let (_, done_run_id) = init_workflow(&client, "wf-done".to_string(), StartOptions::default()).await.unwrap();
+    let done_id = insert_execution(&db, "wf-done", &done_run_id, ExecutionStatus::Running).await;
+    client.set_status("wf-done", WorkflowExecutionStatus::Completed);
+
+    let report = reconcile_visibility(&client, &db, Duration::ZERO).await.unwrap();
+
+    assert_eq!(report.visible_workflows, 4);
+    assert_eq!(report.imported, vec!["repeat-workflow-cli".to_string()]);
+    assert_eq!(report.untracked, vec!["order-42".to_string(), "wf-deleted".to_string()]);
+    assert_eq!(report.missing.len(), 1);
+    assert_eq!(report.missing[0].id, missing_id);
+    assert_eq!(report.status_mismatches.len(), 1);
+    assert_eq!(report.status_mismatches[0].id, done_id);
+    assert_eq!(report.status_mismatches[0].actual, ExecutionStatus::Completed);
+
+    let imported = get_execution_by_workflow_id(&db, "repeat-workflow-cli").await.unwrap().unwrap();
+    assert_eq!(imported.run_id, foreign_run_id);
+    assert_eq!(imported.status, ExecutionStatus::Running);
+    assert!(imported.imported);
+    assert!(!get_execution_by_id(&db, done_id).await.unwrap().unwrap().imported);
+
+    // Un second passage n'importe rien de plus
+    let report = reconcile_visibility(&client, &db, Duration::ZERO).await.unwrap();
+    assert!(report.imported.is_empty());
+    assert_eq!(report.untracked, vec!["order-42".to_string(), "wf-deleted".to_string()]);
+}
+
+#[tokio::test]
+async fn visibility_reconciliation_imports_children_under_their_parent() {
+    let db = setup_db().await;
+    let client = FakeTemporalClient::new();
+
+    let (_, parent_run_id) = init_workflow(&client, "wf-parent".to_string(), StartOptions::default()).await.unwrap();
+    let parent_id = insert_execution(&db, "wf-parent", &parent_run_id, ExecutionStatus::Running).await;
+    // Enfant dont le worker n'a pas pu enregistrer le lien
+    init_workflow(&client, "wf-parent-child-0".to_string(), StartOptions::default()).await.unwrap();
+    client.set_parent("wf-parent-child-0", "wf-parent");
+    // Enfant d'un parent inconnu de la base: reste signalé comme non suivi
+    init_workflow(&client, "wf-other-child-0".to_string(), StartOptions::default()).await.unwrap();
+    client.set_parent("wf-other-child-0", "wf-other");
+
+    let report = reconcile_visibility(&client, &db, Duration::ZERO).await.unwrap();
+
+    assert_eq!(report.imported, vec!["wf-parent-child-0".to_string()]);
+    assert_eq!(report.untracked, vec!["wf-other-child-0".to_string()]);
+    let child = get_execution_by_workflow_id(&db, "wf-parent-child-0").await.unwrap().unwrap();
+    assert_eq!(child.parent_id, Some(parent_id));
+}
+
+#[actix_web::test]
+async fn drift_endpoint_serves_latest_report() {
+    let db = setup_db().await;
+    let fake = Arc::new(FakeTemporalClient::new());
+    init_workflow(fake.as_ref(), "repeat-workflow-cli".to_string(), StartOptions::default()).await.unwrap();
+    fake.set_foreign("repeat-workflow-cli");
+    let client: SharedClient = fake.clone();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.clone()))
+            .app_data(web::Data::new(client))
+            .configure(controller::configure),
+    )
+    .await;
+
+    // Pas encore de rapport: indisponible, à réessayer après le prochain passage
+    let request = || test::TestRequest::get().uri("/admin/drift").to_request();
+    let response = test::call_service(&app, request()).await;
+    assert_eq!(response.status(), 503);
+    assert!(response.headers().get("Retry-After").is_some());
+
+    let report = reconcile_visibility(fake.as_ref(), &db, PENDING_GRACE).await.unwrap();
+    save_drift_report(&db, &report).await.unwrap();
+
+    let response = test::call_service(&app, request()).await;
+    assert!(response.status().is_success());
+    let body: serde_json::Value = test::read_body_json(response).await;
+    assert_eq!(body["imported"], serde_json::json!(["repeat-workflow-cli"]));
+}
+
+#[actix_web::test]
+async fn followers_serve_the_report_of_the_leader() {
+    let db = setup_db().await;
+    let fake = Arc::new(FakeTemporalClient::new());
+    init_workflow(fake.as_ref(), "repeat-workflow-cli".to_string(), StartOptions::default()).await.unwrap();
+    fake.set_foreign("repeat-workflow-cli");
+
+    // Le leader enregistre deux passages: seul le dernier est conservé
+    let first = reconcile_visibility(fake.as_ref(), &db, PENDING_GRACE).await.unwrap();
+    save_drift_report(&db, &first).await.unwrap();
+    let second = reconcile_visibility(fake.as_ref(), &db, PENDING_GRACE).await.unwrap();
+    save_drift_report(&db, &second).await.unwrap();
+    assert_eq!(latest_drift_report(&db).await.unwrap(), Some(second.clone()));
+
+    // Une autre instance, sans réconciliateur ni bail, partage seulement la base
+    let follower = test::init_service(App::new().app_data(web::Data::new(db.clone())).configure(controller::configure)).await;
+    let response = test::call_service(&follower, test::TestRequest::get().uri("/admin/drift").to_request()).await;
+    assert!(response.status().is_success());
+    let body: serde_json::Value = test::read_body_json(response).await;
+    assert_eq!(body["visible_workflows"], second.visible_workflows);
+    assert!(body["imported"].as_array().unwrap().is_empty());
+}
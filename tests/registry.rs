@@ -0,0 +1,111 @@
+use std::sync::Arc;
+
+use actix_web::{test, web, App};
+use executor::{
+    controller,
+    helpers::{client::SharedClient, fake_client::FakeTemporalClient},
+    registry::{registry, REPEAT_ACTIVITY, REPEAT_WORKFLOW},
+    service::list_executions,
+};
+use migration::{Migrator, MigratorTrait};
+use sea_orm::{Database, DatabaseConnection};
+use serde_json::json;
+use temporal_sdk_core_protos::coresdk::FromJsonPayloadExt;
+
+async fn setup_db() -> DatabaseConnection {
+    let db = Database::connect("sqlite::memory:").await.expect("Failed to open in-memory database");
+    Migrator::up(&db, None).await.expect("Failed to run migrations");
+    db
+}
+
+#[test]
+fn builtin_registry_declares_repeat_types() {
+    let workflow = registry().workflow(REPEAT_WORKFLOW).unwrap();
+    assert_eq!(workflow.activities, vec![REPEAT_ACTIVITY]);
+    assert_eq!(workflow.default_input, json!({ "interval_secs": 5, "total_secs": 60 }));
+    assert!(registry().activity(REPEAT_ACTIVITY).is_some());
+    assert!(registry().workflow("unknown_workflow").is_none());
+}
+
+#[test]
+fn workflow_input_is_validated_and_completed_with_defaults() {
+    let workflow = registry().workflow(REPEAT_WORKFLOW).unwrap();
+
+    assert_eq!(workflow.input(None).unwrap(), workflow.default_input);
+    assert_eq!(workflow.input(Some(json!({ "total_secs": 10 }))).unwrap(), json!({ "interval_secs": 5, "total_secs": 10 }));
+    assert!(workflow.input(Some(json!({ "total_secs": "ten" }))).is_err());
+}
+
+#[test]
+fn workflow_input_respects_schema_constraints() {
+    let workflow = registry().workflow(REPEAT_WORKFLOW).unwrap();
+
+    let err = workflow.input(Some(json!({ "interval_secs": 0 }))).unwrap_err();
+    assert!(err.contains("input.interval_secs"), "{}", err);
+    assert!(workflow.input(Some(json!({ "interval_secs": 1, "total_secs": 0 }))).is_ok());
+}
+
+#[actix_web::test]
+async fn post_execution_starts_requested_workflow_type() {
+    let db = setup_db().await;
+    let fake = Arc::new(FakeTemporalClient::new());
+    let client: SharedClient = fake.clone();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.clone()))
+            .app_data(web::Data::new(client))
+            .configure(controller::configure),
+    )
+    .await;
+
+    let request = test::TestRequest::post()
+        .uri("/executions")
+        .set_json(json!({ "workflow_type": REPEAT_WORKFLOW, "input": { "total_secs": 10 } }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert!(response.status().is_success());
+    let body: serde_json::Value = test::read_body_json(response).await;
+    assert_eq!(body["workflow_type"], REPEAT_WORKFLOW);
+    assert_eq!(body["input"], json!({ "interval_secs": 5, "total_secs": 10 }));
+
+    let workflow = fake.workflow(body["workflow_id"].as_str().unwrap()).unwrap();
+    assert_eq!(workflow.workflow_type, REPEAT_WORKFLOW);
+    let input = serde_json::Value::from_json_payload(&workflow.input[0]).unwrap();
+    assert_eq!(input["total_secs"], 10);
+}
+
+#[actix_web::test]
+async fn post_execution_rejects_unknown_type_and_invalid_input() {
+    let db = setup_db().await;
+    let client: SharedClient = Arc::new(FakeTemporalClient::new());
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.clone()))
+            .app_data(web::Data::new(client))
+            .configure(controller::configure),
+    )
+    .await;
+
+    for body in [
+        json!({ "workflow_type": "unknown_workflow" }),
+        json!({ "workflow_type": REPEAT_WORKFLOW, "input": { "interval_secs": -1 } }),
+    ] {
+        let request = test::TestRequest::post().uri("/executions").set_json(body).to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), 400);
+    }
+    assert!(list_executions(&db).await.unwrap().is_empty());
+}
+
+#[actix_web::test]
+async fn workflow_types_endpoint_lists_registry() {
+    let app = test::init_service(App::new().configure(controller::configure)).await;
+
+    let response = test::call_service(&app, test::TestRequest::get().uri("/workflow-types").to_request()).await;
+    assert!(response.status().is_success());
+    let body: serde_json::Value = test::read_body_json(response).await;
+    assert_eq!(body["workflows"][0]["name"], REPEAT_WORKFLOW);
+    assert_eq!(body["workflows"][0]["input_schema"]["type"], "object");
+    assert_eq!(body["activities"][0]["name"], REPEAT_ACTIVITY);
+    assert_eq!(body["activities"][0]["defaults"]["maximum_attempts"], 3);
+}
@@ -0,0 +1,147 @@
+use std::path::{Path, PathBuf};
+
+use executor::{
+    helpers::replay::{load_history_fixture, replay_histories},
+    registry::{registry, REPEAT_ACTIVITY},
+    versioning::{ExecutionVersion, PatchStatus},
+    workflows::{DURABLE_TIMERS_PATCH, LEGACY_REPEAT_ACTIVITY},
+};
+use temporal_sdk_core_protos::{
+    coresdk::AsJsonPayloadExt,
+    temporal::api::{common::v1::Payloads, history::v1::history_event::Attributes},
+};
+
+// Historiques exportés d'exécutions réelles: un historique fabriqué ne prouve rien sur le
+// déterminisme. repeat_workflow_completed.json a été reconstruit à la main et reste à
+// remplacer par une capture du worker d'origine (readme, Tests de rejeu). Pour (re)générer une fixture:
+//   1. temporal server start-dev, puis cargo run --bin main
+//   2. POST /executions, attendre la fin du run (GET /executions/{id})
+//   3. curl -o tests/fixtures/histories/<workflow>_<version>.json \
+//        "http://127.0.0.1:8080/executions/<id>/history?download=true"
+// Le format attendu est celui de cet export (History sérialisé par le SDK). L'historique brut,
+// pour comparaison: temporal workflow show --workflow-id <id> --run-id <run> --output json
+fn history_fixtures() -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/histories");
+    let mut fixtures: Vec<PathBuf> = std::fs::read_dir(&dir)
+        .expect("Missing tests/fixtures/histories directory")
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    fixtures.sort();
+    fixtures
+}
+
+#[tokio::test]
+async fn repeat_workflow_replays_recorded_histories() {
+    let fixtures = history_fixtures();
+    assert!(!fixtures.is_empty(), "No history fixture to replay");
+
+    let mut failures = vec![];
+    for path in fixtures {
+        let workflow_id = path.file_stem().unwrap().to_string_lossy().to_string();
+        let history = load_history_fixture(&path).expect("Failed to load history fixture");
+        if let Err(err) = replay_histories(vec![(workflow_id, history)]).await {
+            failures.push(format!("{}: {}", path.display(), err));
+        }
+    }
+
+    assert!(failures.is_empty(), "Replay failed:\n{}", failures.join("\n"));
+}
+
+// Version de chaque historique enregistré
+fn fixture_versions() -> Vec<(PathBuf, ExecutionVersion)> {
+    history_fixtures()
+        .into_iter()
+        .map(|path| {
+            let history = load_history_fixture(&path).expect("Failed to load history fixture");
+            (path, ExecutionVersion::from_history(&history))
+        })
+        .collect()
+}
+
+// Guide de versionnement (readme, section 14): tant qu'un patch est actif, un historique
+// enregistré avant lui doit être rejoué; une fois déprécié, il ne doit plus en rester.
+#[test]
+fn patches_follow_the_versioning_guide() {
+    let versions = fixture_versions();
+    let mut violations = vec![];
+
+    for workflow in registry().workflows() {
+        for patch in &workflow.patches {
+            let before: Vec<_> = versions
+                .iter()
+                .filter(|(_, version)| version.workflow_type.as_deref() == Some(workflow.name))
+                .filter(|(_, version)| !version.patches.iter().any(|id| id == patch.id))
+                .map(|(path, _)| path.display().to_string())
+                .collect();
+            match patch.status {
+                PatchStatus::Active if before.is_empty() => violations.push(format!(
+                    "{}: no history recorded before active patch {}",
+                    workflow.name, patch.id
+                )),
+                PatchStatus::Deprecated if !before.is_empty() => violations.push(format!(
+                    "{}: patch {} is deprecated but histories recorded before it remain: {}",
+                    workflow.name,
+                    patch.id,
+                    before.join(", ")
+                )),
+                _ => {}
+            }
+        }
+    }
+
+    assert!(violations.is_empty(), "Versioning guide not followed:\n{}", violations.join("\n"));
+}
+
+// Un patch encore présent dans un historique ne peut pas être retiré du registre
+#[test]
+fn recorded_histories_only_use_declared_patches() {
+    let mut violations = vec![];
+    for (path, version) in fixture_versions() {
+        let workflow = version.workflow_type.as_deref().and_then(|name| registry().workflow(name));
+        let Some(workflow) = workflow else {
+            violations.push(format!("{}: unknown workflow type {:?}", path.display(), version.workflow_type));
+            continue;
+        };
+        for id in &version.patches {
+            if !workflow.patches.iter().any(|patch| patch.id == id) {
+                violations.push(format!("{}: patch {} is not declared for {}", path.display(), id, workflow.name));
+            }
+        }
+    }
+
+    assert!(violations.is_empty(), "Undeclared patches:\n{}", violations.join("\n"));
+}
+
+// Les historiques enregistrés avant le patch des timers planifient repeat_activity avec
+// les options d'origine: la branche non patchée doit les reproduire à l'identique
+#[test]
+fn legacy_branch_schedules_the_recorded_activity_options() {
+    let options = LEGACY_REPEAT_ACTIVITY.options(REPEAT_ACTIVITY, "".as_json_payload().unwrap());
+    let secs = |duration: Option<std::time::Duration>| duration.map(|d| d.as_secs() as i64).unwrap_or(0);
+    let mut checked = 0;
+
+    for (path, version) in fixture_versions() {
+        if version.patches.iter().any(|id| id == DURABLE_TIMERS_PATCH) {
+            continue;
+        }
+        let history = load_history_fixture(&path).expect("Failed to load history fixture");
+        for event in &history.events {
+            let Some(Attributes::ActivityTaskScheduledEventAttributes(scheduled)) = &event.attributes else {
+                continue;
+            };
+            let recorded_policy = scheduled.retry_policy.clone().unwrap_or_default();
+            let policy = options.retry_policy.clone().unwrap_or_default();
+            let context = path.display();
+            assert_eq!(scheduled.activity_type.as_ref().map(|t| t.name.as_str()), Some(REPEAT_ACTIVITY), "{}", context);
+            assert_eq!(scheduled.start_to_close_timeout.as_ref().map(|d| d.seconds).unwrap_or(0), secs(options.start_to_close_timeout), "{}", context);
+            assert_eq!(scheduled.heartbeat_timeout.as_ref().map(|d| d.seconds).unwrap_or(0), secs(options.heartbeat_timeout), "{}", context);
+            assert_eq!(recorded_policy.maximum_attempts, policy.maximum_attempts, "{}", context);
+            assert_eq!(recorded_policy.initial_interval.map(|d| d.seconds), policy.initial_interval.map(|d| d.seconds), "{}", context);
+            assert_eq!(scheduled.input, Some(Payloads { payloads: vec![options.input.clone()] }), "{}", context);
+            checked += 1;
+        }
+    }
+
+    assert!(checked > 0, "No legacy history schedules repeat_activity");
+}
@@ -0,0 +1,111 @@
+use chrono::{TimeZone, Utc};
+use executor::{
+    executions::ExecutionStatus,
+    helpers::{client::StartOptions, fake_client::FakeTemporalClient},
+    schedules::{create_schedule, list_schedules, record_scheduled_runs, set_schedule_paused, trigger_schedule, ScheduleInput},
+    service::{init_workflow, list_executions, new_workflow_id},
+};
+use migration::{Migrator, MigratorTrait};
+use sea_orm::{Database, DatabaseConnection};
+
+async fn setup_db() -> DatabaseConnection {
+    let db = Database::connect("sqlite::memory:").await.expect("Failed to open in-memory database");
+    Migrator::up(&db, None).await.expect("Failed to run migrations");
+    db
+}
+
+fn interval_schedule(schedule_id: &str) -> ScheduleInput {
+    serde_json::from_value(serde_json::json!({
+        "schedule_id": schedule_id,
+        "interval_secs": 3600,
+        "overlap_policy": "buffer_one",
+        "jitter_secs": 30
+    }))
+    .unwrap()
+}
+
+#[test]
+fn schedule_requires_a_cron_or_an_interval() {
+    let input: ScheduleInput = serde_json::from_value(serde_json::json!({})).unwrap();
+    assert!(input.validate().is_err());
+    assert!(interval_schedule("hourly").validate().is_ok());
+}
+
+#[test]
+fn schedule_id_and_input_are_validated() {
+    assert!(interval_schedule("it's-daily").validate().is_err());
+    assert!(interval_schedule(&"a".repeat(1000)).validate().is_err());
+
+    let input: ScheduleInput = serde_json::from_value(serde_json::json!({
+        "interval_secs": 3600,
+        "input": { "interval_secs": 0, "total_secs": 60 }
+    }))
+    .unwrap();
+    assert!(input.validate().is_err());
+}
+
+#[tokio::test]
+async fn schedules_can_be_paused_and_listed() {
+    let client = FakeTemporalClient::new();
+    create_schedule(&client, interval_schedule("hourly")).await.unwrap();
+
+    set_schedule_paused(&client, "hourly".to_string(), true).await.unwrap();
+
+    let schedules = list_schedules(&client).await.unwrap();
+    assert_eq!(schedules.len(), 1);
+    assert_eq!(schedules[0].schedule_id, "hourly");
+    assert_eq!(schedules[0].interval_secs, vec![3600]);
+    assert!(schedules[0].paused);
+}
+
+#[tokio::test]
+async fn scheduled_runs_are_recorded_once() {
+    let db = setup_db().await;
+    let client = FakeTemporalClient::new();
+    create_schedule(&client, interval_schedule("hourly")).await.unwrap();
+    trigger_schedule(&client, "hourly".to_string()).await.unwrap();
+
+    assert_eq!(record_scheduled_runs(&client, &db, None).await.unwrap(), 1);
+    assert_eq!(record_scheduled_runs(&client, &db, None).await.unwrap(), 0);
+
+    let executions = list_executions(&db).await.unwrap();
+    assert_eq!(executions.len(), 1);
+    assert_eq!(executions[0].schedule_id.as_deref(), Some("hourly"));
+    assert_eq!(executions[0].status, ExecutionStatus::Running);
+}
+
+#[tokio::test]
+async fn every_scheduled_run_is_listed_from_visibility() {
+    let db = setup_db().await;
+    let client = FakeTemporalClient::new();
+    create_schedule(&client, interval_schedule("hourly")).await.unwrap();
+    create_schedule(&client, interval_schedule("it's-daily")).await.unwrap();
+    // Plus de runs que les actions récentes gardées par Temporal pour un schedule
+    for _ in 0..12 {
+        trigger_schedule(&client, "hourly".to_string()).await.unwrap();
+    }
+    // Démarré hors schedule: ignoré
+    init_workflow(&client, new_workflow_id(), StartOptions::default()).await.unwrap();
+
+    assert_eq!(record_scheduled_runs(&client, &db, None).await.unwrap(), 12);
+    assert!(list_executions(&db).await.unwrap().iter().all(|e| e.schedule_id.as_deref() == Some("hourly")));
+
+    let queries = client.list_queries();
+    assert!(queries.contains(&"TemporalScheduledById = 'hourly'".to_string()));
+    assert!(queries.contains(&"TemporalScheduledById = 'it\\'s-daily'".to_string()));
+}
+
+#[tokio::test]
+async fn later_passes_only_list_recent_runs() {
+    let db = setup_db().await;
+    let client = FakeTemporalClient::new();
+    create_schedule(&client, interval_schedule("hourly")).await.unwrap();
+    let since = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+
+    record_scheduled_runs(&client, &db, Some(since)).await.unwrap();
+
+    assert_eq!(
+        client.list_queries(),
+        vec!["TemporalScheduledById = 'hourly' AND StartTime >= '2025-01-01T12:00:00Z'".to_string()]
+    );
+}
@@ -0,0 +1,659 @@
+use std::{sync::Arc, time::{Duration, Instant, SystemTime}};
+
+use actix_web::{test, web, App};
+use executor::{
+    controller,
+    executions::ExecutionStatus,
+    registry::REPEAT_WORKFLOW,
+    helpers::{client::{SharedClient, StartOptions}, fake_client::FakeTemporalClient, status_tracker::{SharedStatusTracker, StatusTracker}},
+    service::{confirm_execution, create_execution, create_or_get_execution, get_execution_by_id, idempotent_workflow_id, init_workflow, list_executions, new_workflow_id, ExecutionInput, NewExecution, MAX_START_DELAY_SECS, MAX_WORKFLOW_ID_LEN},
+    workers::{update_execution_status_worker, workflow_status, UpdateExecutionStatusJob},
+};
+use migration::{Migrator, MigratorTrait};
+use prost_wkt_types::Timestamp;
+use sea_orm::{ConnectionTrait, Database, DatabaseConnection};
+use temporal_sdk_core_protos::temporal::api::enums::v1::WorkflowExecutionStatus;
+use uuid::Uuid;
+
+async fn setup_db() -> DatabaseConnection {
+    let db = Database::connect("sqlite::memory:").await.expect("Failed to open in-memory database");
+    Migrator::up(&db, None).await.expect("Failed to run migrations");
+    db
+}
+
+// Tâche de synchronisation sans délai de resynchronisation, pour enchaîner les passages
+fn sync_job() -> UpdateExecutionStatusJob {
+    UpdateExecutionStatusJob { resync_after: Duration::ZERO, ..Default::default() }
+}
+
+async fn insert_running_execution(db: &DatabaseConnection, client: &FakeTemporalClient) -> Uuid {
+    let (workflow_id, run_id) = init_workflow(client, new_workflow_id(), StartOptions::default()).await.unwrap();
+    let id = Uuid::new_v4();
+    create_execution(db, ExecutionInput { id, workflow_id, run_id, status: ExecutionStatus::Running, schedule_id: None, start_at: None, previous_run_ids: vec![], workflow_type: REPEAT_WORKFLOW.to_string(), input: None, parent_id: None })
+        .await
+        .unwrap();
+    id
+}
+
+#[tokio::test]
+async fn init_workflow_starts_repeat_workflow() {
+    let client = FakeTemporalClient::new();
+
+    let (workflow_id, run_id) = init_workflow(&client, new_workflow_id(), StartOptions::default()).await.unwrap();
+
+    let workflow = client.workflow(&workflow_id).expect("workflow should be started");
+    assert!(workflow_id.starts_with("wf-"));
+    assert_eq!(workflow.workflow_type, "repeat_workflow");
+    assert_eq!(workflow.run_id, run_id);
+}
+
+#[actix_web::test]
+async fn post_execution_records_running_execution() {
+    let db = setup_db().await;
+    let client: SharedClient = Arc::new(FakeTemporalClient::new());
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.clone()))
+            .app_data(web::Data::new(client))
+            .configure(controller::configure),
+    )
+    .await;
+
+    let response = test::call_service(&app, test::TestRequest::post().uri("/executions").to_request()).await;
+    assert!(response.status().is_success());
+    let body: serde_json::Value = test::read_body_json(response).await;
+    assert_eq!(body["status"], "RUNNING");
+
+    let executions = list_executions(&db).await.unwrap();
+    assert_eq!(executions.len(), 1);
+    assert_eq!(executions[0].workflow_id, body["workflow_id"]);
+}
+
+#[actix_web::test]
+async fn post_execution_fails_when_temporal_is_unavailable() {
+    let db = setup_db().await;
+    let fake = Arc::new(FakeTemporalClient::new());
+    fake.set_unavailable(true);
+    let client: SharedClient = fake;
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.clone()))
+            .app_data(web::Data::new(client))
+            .configure(controller::configure),
+    )
+    .await;
+
+    let response = test::call_service(&app, test::TestRequest::post().uri("/executions").to_request()).await;
+    assert!(response.status().is_server_error());
+
+    // La réservation reste PENDING pour être reprise par le réconciliateur
+    let executions = list_executions(&db).await.unwrap();
+    assert_eq!(executions.len(), 1);
+    assert_eq!(executions[0].status, ExecutionStatus::Pending);
+}
+
+#[test]
+fn workflow_status_mapping() {
+    assert_eq!(workflow_status(WorkflowExecutionStatus::Running), ExecutionStatus::Running);
+    assert_eq!(workflow_status(WorkflowExecutionStatus::Completed), ExecutionStatus::Completed);
+    assert_eq!(workflow_status(WorkflowExecutionStatus::Failed), ExecutionStatus::Failed);
+    assert_eq!(workflow_status(WorkflowExecutionStatus::Canceled), ExecutionStatus::Canceled);
+    assert_eq!(workflow_status(WorkflowExecutionStatus::Terminated), ExecutionStatus::Terminated);
+    assert_eq!(workflow_status(WorkflowExecutionStatus::TimedOut), ExecutionStatus::TimedOut);
+    assert_eq!(workflow_status(WorkflowExecutionStatus::Unspecified), ExecutionStatus::Unknown);
+}
+
+#[test]
+fn execution_status_state_machine() {
+    assert!(ExecutionStatus::Pending.can_transition_to(ExecutionStatus::Running));
+    assert!(ExecutionStatus::Scheduled.can_transition_to(ExecutionStatus::Running));
+    assert!(ExecutionStatus::Running.can_transition_to(ExecutionStatus::Completed));
+    assert!(ExecutionStatus::Running.can_transition_to(ExecutionStatus::Lost));
+    assert!(!ExecutionStatus::Running.can_transition_to(ExecutionStatus::Scheduled));
+    assert!(!ExecutionStatus::Running.can_transition_to(ExecutionStatus::Pending));
+    assert!(!ExecutionStatus::Completed.can_transition_to(ExecutionStatus::Running));
+    assert!(!ExecutionStatus::Lost.can_transition_to(ExecutionStatus::Running));
+    assert!(ExecutionStatus::Completed.is_terminal());
+    assert!(!ExecutionStatus::Unknown.is_terminal());
+    assert!(ExecutionStatus::Unknown.is_active());
+    assert!(ExecutionStatus::Unknown.can_transition_to(ExecutionStatus::Completed));
+    assert!(ExecutionStatus::Running.can_transition_to(ExecutionStatus::Unknown));
+    assert_eq!(serde_json::to_value(ExecutionStatus::TimedOut).unwrap(), "TIMED_OUT");
+    assert_eq!(ExecutionStatus::Completed.to_string(), "COMPLETED");
+}
+
+#[tokio::test]
+async fn confirm_execution_rejects_invalid_transitions() {
+    let db = setup_db().await;
+    let client = FakeTemporalClient::new();
+    let id = insert_running_execution(&db, &client).await;
+    let execution = get_execution_by_id(&db, id).await.unwrap().unwrap();
+    client.set_status(&execution.workflow_id, WorkflowExecutionStatus::Completed);
+    update_execution_status_worker(sync_job(), web::Data::new(Arc::new(db.clone())), &client, &StatusTracker::new())
+        .await
+        .unwrap();
+
+    let result = confirm_execution(&db, id, execution.run_id, ExecutionStatus::Running).await;
+    assert!(result.is_err());
+    assert_eq!(get_execution_by_id(&db, id).await.unwrap().unwrap().status, ExecutionStatus::Completed);
+}
+
+#[tokio::test]
+async fn migration_normalizes_legacy_statuses() {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    Migrator::up(&db, Some(8)).await.unwrap();
+    let client = FakeTemporalClient::new();
+    let complete = insert_running_execution(&db, &client).await;
+    let lowercase = insert_running_execution(&db, &client).await;
+    let bogus = insert_running_execution(&db, &client).await;
+    for (id, status) in [(complete, "COMPLETE"), (lowercase, " running"), (bogus, "BOGUS")] {
+        let workflow_id = get_execution_by_id(&db, id).await.unwrap().unwrap().workflow_id;
+        db.execute_unprepared(&format!("UPDATE executions SET status = '{}' WHERE workflow_id = '{}'", status, workflow_id))
+            .await
+            .unwrap();
+    }
+
+    Migrator::up(&db, None).await.unwrap();
+
+    assert_eq!(get_execution_by_id(&db, complete).await.unwrap().unwrap().status, ExecutionStatus::Completed);
+    assert_eq!(get_execution_by_id(&db, lowercase).await.unwrap().unwrap().status, ExecutionStatus::Running);
+    let bogus = get_execution_by_id(&db, bogus).await.unwrap().unwrap();
+    assert_eq!(bogus.status, ExecutionStatus::Unknown);
+    assert_eq!(bogus.status_reason.as_deref(), Some("Statut non reconnu: BOGUS"));
+}
+
+#[tokio::test]
+async fn scheduler_updates_finished_executions() {
+    let db = setup_db().await;
+    let client = FakeTemporalClient::new();
+    let id = insert_running_execution(&db, &client).await;
+    let execution = get_execution_by_id(&db, id).await.unwrap().unwrap();
+    client.set_status(&execution.workflow_id, WorkflowExecutionStatus::Completed);
+
+    update_execution_status_worker(sync_job(), web::Data::new(Arc::new(db.clone())), &client, &StatusTracker::new())
+        .await
+        .unwrap();
+
+    let execution = get_execution_by_id(&db, id).await.unwrap().unwrap();
+    assert_eq!(execution.status, ExecutionStatus::Completed);
+}
+
+#[tokio::test]
+async fn scheduler_keeps_polling_unknown_executions() {
+    let db = setup_db().await;
+    let client = FakeTemporalClient::new();
+    let id = insert_running_execution(&db, &client).await;
+    let execution = get_execution_by_id(&db, id).await.unwrap().unwrap();
+    db.execute_unprepared(&format!("UPDATE executions SET status = 'UNKNOWN' WHERE workflow_id = '{}'", execution.workflow_id))
+        .await
+        .unwrap();
+    client.set_status(&execution.workflow_id, WorkflowExecutionStatus::Completed);
+
+    update_execution_status_worker(sync_job(), web::Data::new(Arc::new(db.clone())), &client, &StatusTracker::new())
+        .await
+        .unwrap();
+
+    let execution = get_execution_by_id(&db, id).await.unwrap().unwrap();
+    assert_eq!(execution.status, ExecutionStatus::Completed);
+}
+
+#[tokio::test]
+async fn scheduler_keeps_status_when_describe_fails() {
+    let db = setup_db().await;
+    let client = FakeTemporalClient::new();
+    let id = insert_running_execution(&db, &client).await;
+    client.set_unavailable(true);
+
+    update_execution_status_worker(sync_job(), web::Data::new(Arc::new(db.clone())), &client, &StatusTracker::new())
+        .await
+        .unwrap();
+
+    let execution = get_execution_by_id(&db, id).await.unwrap().unwrap();
+    assert_eq!(execution.status, ExecutionStatus::Running);
+}
+
+#[actix_web::test]
+async fn signal_endpoint_forwards_known_signals_only() {
+    let db = setup_db().await;
+    let fake = Arc::new(FakeTemporalClient::new());
+    let id = insert_running_execution(&db, &fake).await;
+    let workflow_id = get_execution_by_id(&db, id).await.unwrap().unwrap().workflow_id;
+    let client: SharedClient = fake.clone();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.clone()))
+            .app_data(web::Data::new(client))
+            .configure(controller::configure),
+    )
+    .await;
+
+    let request = test::TestRequest::post()
+        .uri(&format!("/executions/{}/signals/extend", id))
+        .set_json(serde_json::json!({ "seconds": 30 }))
+        .to_request();
+    assert!(test::call_service(&app, request).await.status().is_success());
+
+    let request = test::TestRequest::post()
+        .uri(&format!("/executions/{}/signals/explode", id))
+        .to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), 400);
+
+    let signals = fake.workflow(&workflow_id).unwrap().signals;
+    assert_eq!(signals.len(), 1);
+    assert_eq!(signals[0].0, "extend");
+}
+
+#[actix_web::test]
+async fn repeat_signals_and_updates_are_refused_for_other_workflow_types() {
+    let db = setup_db().await;
+    let fake = Arc::new(FakeTemporalClient::new());
+    let (workflow_id, run_id) = init_workflow(fake.as_ref(), new_workflow_id(), StartOptions::default()).await.unwrap();
+    let id = Uuid::new_v4();
+    create_execution(&db, ExecutionInput { id, workflow_id: workflow_id.clone(), run_id, status: ExecutionStatus::Running, schedule_id: None, start_at: None, previous_run_ids: vec![], workflow_type: "command_workflow".to_string(), input: None, parent_id: None })
+        .await
+        .unwrap();
+    let client: SharedClient = fake.clone();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.clone()))
+            .app_data(web::Data::new(client))
+            .configure(controller::configure),
+    )
+    .await;
+
+    let request = test::TestRequest::post()
+        .uri(&format!("/executions/{}/signals/pause", id))
+        .to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), 409);
+
+    let request = test::TestRequest::post()
+        .uri(&format!("/executions/{}/updates/update_parameters", id))
+        .set_json(serde_json::json!({ "total_secs": 120 }))
+        .to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), 409);
+
+    assert!(fake.workflow(&workflow_id).unwrap().signals.is_empty());
+}
+
+#[actix_web::test]
+async fn post_execution_with_delay_is_scheduled_until_start() {
+    let db = setup_db().await;
+    let fake = Arc::new(FakeTemporalClient::new());
+    let client: SharedClient = fake.clone();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.clone()))
+            .app_data(web::Data::new(client))
+            .configure(controller::configure),
+    )
+    .await;
+
+    let request = test::TestRequest::post()
+        .uri("/executions")
+        .set_json(serde_json::json!({ "start_delay_secs": 3600 }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert!(response.status().is_success());
+    let body: serde_json::Value = test::read_body_json(response).await;
+    assert_eq!(body["status"], "SCHEDULED");
+    assert!(body["start_at"].is_string());
+
+    let workflow_id = body["workflow_id"].as_str().unwrap().to_string();
+    let workflow = fake.workflow(&workflow_id).unwrap();
+    assert_eq!(workflow.options.start_delay.map(|d| d.as_secs()), Some(3600));
+
+    // Le délai n'est pas échu: l'exécution reste planifiée
+    let data = web::Data::new(Arc::new(db.clone()));
+    update_execution_status_worker(sync_job(), data.clone(), fake.as_ref(), &StatusTracker::new()).await.unwrap();
+    let executions = list_executions(&db).await.unwrap();
+    assert_eq!(executions[0].status, ExecutionStatus::Scheduled);
+
+    // Le délai est échu: le workflow a réellement démarré
+    fake.set_execution_time(&workflow_id, Timestamp::from(SystemTime::now()));
+    update_execution_status_worker(sync_job(), data, fake.as_ref(), &StatusTracker::new()).await.unwrap();
+    let executions = list_executions(&db).await.unwrap();
+    assert_eq!(executions[0].status, ExecutionStatus::Running);
+}
+
+#[actix_web::test]
+async fn post_execution_rejects_invalid_start() {
+    let db = setup_db().await;
+    let fake = Arc::new(FakeTemporalClient::new());
+    let client: SharedClient = fake.clone();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.clone()))
+            .app_data(web::Data::new(client))
+            .configure(controller::configure),
+    )
+    .await;
+
+    let bodies = [
+        serde_json::json!({ "start_at": "2000-01-01T00:00:00Z" }),
+        serde_json::json!({ "start_at": "2999-01-01T00:00:00Z", "start_delay_secs": 10 }),
+        // Délai hors limites: refusé sans faire paniquer le handler
+        serde_json::json!({ "start_delay_secs": u64::MAX }),
+        serde_json::json!({ "start_delay_secs": MAX_START_DELAY_SECS + 1 }),
+    ];
+    for body in bodies {
+        let request = test::TestRequest::post().uri("/executions").set_json(body).to_request();
+        assert_eq!(test::call_service(&app, request).await.status(), 400);
+    }
+    assert_eq!(fake.workflow_count(), 0);
+    assert!(list_executions(&db).await.unwrap().is_empty());
+}
+
+#[test]
+fn start_delay_is_capped() {
+    let now = chrono::Utc::now();
+    let request = |secs: u64| NewExecution { start_delay_secs: Some(secs), ..Default::default() };
+
+    let planned = request(MAX_START_DELAY_SECS).planned_start(now).unwrap().unwrap();
+    assert_eq!((planned - now).num_seconds(), MAX_START_DELAY_SECS as i64);
+    assert!(request(u64::MAX).planned_start(now).is_err());
+}
+
+#[actix_web::test]
+async fn post_execution_with_idempotency_key_returns_original_execution() {
+    let db = setup_db().await;
+    let fake = Arc::new(FakeTemporalClient::new());
+    let client: SharedClient = fake.clone();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.clone()))
+            .app_data(web::Data::new(client))
+            .configure(controller::configure),
+    )
+    .await;
+
+    let request = || test::TestRequest::post().uri("/executions").insert_header(("Idempotency-Key", "order-42")).to_request();
+    let first = test::call_service(&app, request()).await;
+    assert!(first.status().is_success());
+    assert!(first.headers().get("Idempotent-Replayed").is_none());
+    let first: serde_json::Value = test::read_body_json(first).await;
+    assert_eq!(first["workflow_id"], idempotent_workflow_id("order-42"));
+
+    let second = test::call_service(&app, request()).await;
+    assert!(second.status().is_success());
+    assert_eq!(second.headers().get("Idempotent-Replayed").unwrap(), "true");
+    let second: serde_json::Value = test::read_body_json(second).await;
+    assert_eq!(first, second);
+
+    assert_eq!(fake.workflow_count(), 1);
+    assert_eq!(list_executions(&db).await.unwrap().len(), 1);
+}
+
+#[actix_web::test]
+async fn post_execution_retry_attaches_to_already_started_workflow() {
+    let db = setup_db().await;
+    let fake = Arc::new(FakeTemporalClient::new());
+    // Premier essai: le workflow a démarré mais la réponse (et l'enregistrement) ont été perdus
+    let (workflow_id, run_id) = init_workflow(fake.as_ref(), "wf-retry".to_string(), StartOptions::default())
+        .await
+        .unwrap();
+    let client: SharedClient = fake.clone();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.clone()))
+            .app_data(web::Data::new(client))
+            .configure(controller::configure),
+    )
+    .await;
+
+    let request = test::TestRequest::post()
+        .uri("/executions")
+        .set_json(serde_json::json!({ "workflow_id": workflow_id }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert!(response.status().is_success());
+    let body: serde_json::Value = test::read_body_json(response).await;
+    assert_eq!(body["run_id"], run_id);
+    assert_eq!(fake.workflow_count(), 1);
+}
+
+#[test]
+fn requested_workflow_id_is_validated() {
+    let request = |workflow_id: &str| NewExecution { workflow_id: Some(workflow_id.to_string()), ..Default::default() };
+
+    assert_eq!(request("order-42_v1.2:eu").requested_workflow_id(None), Ok(Some("order-42_v1.2:eu".to_string())));
+    for workflow_id in ["x' OR WorkflowId != '", "order 42", "commande-é", &"a".repeat(MAX_WORKFLOW_ID_LEN + 1)] {
+        assert!(request(workflow_id).requested_workflow_id(None).is_err(), "{} should be rejected", workflow_id);
+    }
+}
+
+#[tokio::test]
+async fn executions_workflow_id_is_unique() {
+    let db = setup_db().await;
+    let input = |id: Uuid| ExecutionInput { id, workflow_id: "wf-dup".to_string(), run_id: "run".to_string(), status: ExecutionStatus::Running, schedule_id: None, start_at: None, previous_run_ids: vec![], workflow_type: REPEAT_WORKFLOW.to_string(), input: None, parent_id: None };
+    let first_id = Uuid::new_v4();
+    create_execution(&db, input(first_id)).await.unwrap();
+    assert!(create_execution(&db, input(Uuid::new_v4())).await.is_err());
+
+    let (execution, created) = create_or_get_execution(&db, input(Uuid::new_v4())).await.unwrap();
+    assert!(!created);
+    assert_eq!(execution.id, first_id);
+}
+
+#[tokio::test]
+async fn scheduler_follows_continue_as_new_chain() {
+    let db = setup_db().await;
+    let client = FakeTemporalClient::new();
+    let id = insert_running_execution(&db, &client).await;
+    let execution = get_execution_by_id(&db, id).await.unwrap().unwrap();
+    let first_run_id = execution.run_id.clone();
+    let data = web::Data::new(Arc::new(db.clone()));
+
+    let second_run_id = client.continue_as_new(&execution.workflow_id).unwrap();
+    update_execution_status_worker(sync_job(), data.clone(), &client, &StatusTracker::new()).await.unwrap();
+
+    let execution = get_execution_by_id(&db, id).await.unwrap().unwrap();
+    assert_eq!(execution.status, ExecutionStatus::Running);
+    assert_eq!(execution.run_id, second_run_id);
+    assert_eq!(execution.previous_run_ids.0, vec![first_run_id.clone()]);
+
+    let third_run_id = client.continue_as_new(&execution.workflow_id).unwrap();
+    client.set_status(&execution.workflow_id, WorkflowExecutionStatus::Completed);
+    update_execution_status_worker(sync_job(), data, &client, &StatusTracker::new()).await.unwrap();
+
+    let execution = get_execution_by_id(&db, id).await.unwrap().unwrap();
+    assert_eq!(execution.status, ExecutionStatus::Completed);
+    assert_eq!(execution.run_id, third_run_id);
+    assert_eq!(execution.previous_run_ids.0, vec![first_run_id, second_run_id]);
+}
+
+#[tokio::test]
+async fn scheduler_describes_each_execution_once_while_running() {
+    let db = setup_db().await;
+    let client = FakeTemporalClient::new();
+    let running = insert_running_execution(&db, &client).await;
+    let done = insert_running_execution(&db, &client).await;
+    let done_workflow_id = get_execution_by_id(&db, done).await.unwrap().unwrap().workflow_id;
+    let data = web::Data::new(Arc::new(db.clone()));
+
+    update_execution_status_worker(sync_job(), data.clone(), &client, &StatusTracker::new()).await.unwrap();
+    assert_eq!(client.describe_calls(), 2);
+
+    // Terminées: plus interrogées
+    client.set_status(&done_workflow_id, WorkflowExecutionStatus::Completed);
+    update_execution_status_worker(sync_job(), data.clone(), &client, &StatusTracker::new()).await.unwrap();
+    assert_eq!(get_execution_by_id(&db, done).await.unwrap().unwrap().status, ExecutionStatus::Completed);
+    let calls = client.describe_calls();
+    update_execution_status_worker(sync_job(), data, &client, &StatusTracker::new()).await.unwrap();
+    assert_eq!(client.describe_calls(), calls + 1);
+    assert_eq!(get_execution_by_id(&db, running).await.unwrap().unwrap().status, ExecutionStatus::Running);
+}
+
+#[tokio::test]
+async fn scheduler_resumes_tracking_of_rows_stuck_in_continued_as_new() {
+    let db = setup_db().await;
+    let client = FakeTemporalClient::new();
+    let (workflow_id, run_id) = init_workflow(&client, new_workflow_id(), StartOptions::default()).await.unwrap();
+    let id = Uuid::new_v4();
+    let input = ExecutionInput { id, workflow_id: workflow_id.clone(), run_id, status: ExecutionStatus::ContinuedAsNew, schedule_id: None, start_at: None, previous_run_ids: vec![], workflow_type: REPEAT_WORKFLOW.to_string(), input: None, parent_id: None };
+    create_execution(&db, input).await.unwrap();
+    let latest_run_id = client.continue_as_new(&workflow_id).unwrap();
+
+    update_execution_status_worker(sync_job(), web::Data::new(Arc::new(db.clone())), &client, &StatusTracker::new())
+        .await
+        .unwrap();
+
+    let execution = get_execution_by_id(&db, id).await.unwrap().unwrap();
+    assert_eq!(execution.status, ExecutionStatus::Running);
+    assert_eq!(execution.run_id, latest_run_id);
+}
+
+#[tokio::test]
+async fn scheduler_marks_missing_workflows_as_lost() {
+    let db = setup_db().await;
+    let client = FakeTemporalClient::new();
+    let tracker = StatusTracker::new();
+    let id = insert_running_execution(&db, &client).await;
+    let execution = get_execution_by_id(&db, id).await.unwrap().unwrap();
+    client.remove_workflow(&execution.workflow_id);
+    let data = web::Data::new(Arc::new(db.clone()));
+
+    update_execution_status_worker(sync_job(), data.clone(), &client, &tracker).await.unwrap();
+
+    let execution = get_execution_by_id(&db, id).await.unwrap().unwrap();
+    assert_eq!(execution.status, ExecutionStatus::Lost);
+    assert!(execution.status_reason.unwrap().contains("introuvable"));
+
+    // Statut terminal: l'exécution n'est plus interrogée
+    update_execution_status_worker(sync_job(), data, &client, &tracker).await.unwrap();
+    let counters = tracker.snapshot();
+    assert_eq!(counters.lost, 1);
+    assert_eq!(counters.transient_errors, 0);
+}
+
+#[tokio::test]
+async fn scheduler_backs_off_rows_after_transient_errors() {
+    let db = setup_db().await;
+    let client = FakeTemporalClient::new();
+    let tracker = StatusTracker::new();
+    let id = insert_running_execution(&db, &client).await;
+    let data = web::Data::new(Arc::new(db.clone()));
+
+    client.set_unavailable(true);
+    update_execution_status_worker(sync_job(), data.clone(), &client, &tracker).await.unwrap();
+    assert_eq!(tracker.snapshot().transient_errors, 1);
+    assert_eq!(tracker.snapshot().rows_in_backoff, 1);
+
+    // Serveur revenu, mais la prochaine tentative n'est pas encore due
+    client.set_unavailable(false);
+    update_execution_status_worker(sync_job(), data, &client, &tracker).await.unwrap();
+    let counters = tracker.snapshot();
+    assert_eq!(counters.transient_errors, 1);
+    assert_eq!(counters.backed_off, 1);
+    assert_eq!(counters.updated, 0);
+    // Écartée dès la requête: aucun describe
+    assert_eq!(client.describe_calls(), 1);
+    assert_eq!(get_execution_by_id(&db, id).await.unwrap().unwrap().status, ExecutionStatus::Running);
+}
+
+#[test]
+fn status_tracker_backoff_doubles_up_to_a_cap() {
+    let tracker = StatusTracker::new();
+    let id = Uuid::new_v4();
+    let now = Instant::now();
+
+    let delays: Vec<Duration> = (0..8).map(|_| tracker.record_transient(id, now)).collect();
+    assert_eq!(delays[0], Duration::from_secs(5));
+    assert_eq!(delays[1], Duration::from_secs(10));
+    assert_eq!(delays[2], Duration::from_secs(20));
+    assert_eq!(delays[7], Duration::from_secs(300));
+
+    assert_eq!(tracker.backed_off_ids(now), vec![id]);
+    assert!(tracker.backed_off_ids(now + Duration::from_secs(301)).is_empty());
+    tracker.record_updated(id);
+    assert!(tracker.backed_off_ids(now).is_empty());
+    assert_eq!(tracker.snapshot().rows_in_backoff, 0);
+}
+
+#[actix_web::test]
+async fn scheduler_counters_endpoint() {
+    let db = setup_db().await;
+    let fake = Arc::new(FakeTemporalClient::new());
+    let tracker: SharedStatusTracker = Arc::new(StatusTracker::new());
+    tracker.record_lost(Uuid::new_v4());
+    let client: SharedClient = fake;
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .app_data(web::Data::new(client))
+            .app_data(web::Data::new(tracker))
+            .configure(controller::configure),
+    )
+    .await;
+
+    let response = test::call_service(&app, test::TestRequest::get().uri("/admin/scheduler").to_request()).await;
+    assert!(response.status().is_success());
+    let body: serde_json::Value = test::read_body_json(response).await;
+    assert_eq!(body["lost"], 1);
+    assert_eq!(body["rows_in_backoff"], 0);
+}
+
+#[tokio::test]
+async fn scheduler_syncs_many_executions_concurrently() {
+    let db = setup_db().await;
+    let client = FakeTemporalClient::new();
+    let mut ids = vec![];
+    for _ in 0..20 {
+        ids.push(insert_running_execution(&db, &client).await);
+    }
+    for id in &ids[..10] {
+        let execution = get_execution_by_id(&db, *id).await.unwrap().unwrap();
+        client.set_status(&execution.workflow_id, WorkflowExecutionStatus::Completed);
+    }
+    let tracker = StatusTracker::new();
+    let job = UpdateExecutionStatusJob { concurrency: 4, ..sync_job() };
+
+    update_execution_status_worker(job, web::Data::new(Arc::new(db.clone())), &client, &tracker).await.unwrap();
+
+    for (i, id) in ids.iter().enumerate() {
+        let execution = get_execution_by_id(&db, *id).await.unwrap().unwrap();
+        assert_eq!(execution.status, if i < 10 { ExecutionStatus::Completed } else { ExecutionStatus::Running });
+        assert!(execution.synced_at.is_some());
+    }
+    assert_eq!(tracker.snapshot().updated, 20);
+}
+
+#[tokio::test]
+async fn scheduler_skips_recently_synced_executions() {
+    let db = setup_db().await;
+    let client = FakeTemporalClient::new();
+    let tracker = StatusTracker::new();
+    let id = insert_running_execution(&db, &client).await;
+    let data = web::Data::new(Arc::new(db.clone()));
+
+    update_execution_status_worker(UpdateExecutionStatusJob::default(), data.clone(), &client, &tracker).await.unwrap();
+    let execution = get_execution_by_id(&db, id).await.unwrap().unwrap();
+    client.set_status(&execution.workflow_id, WorkflowExecutionStatus::Completed);
+
+    // Synchronisée à l'instant: ignorée jusqu'à l'échéance de resync_after
+    update_execution_status_worker(UpdateExecutionStatusJob::default(), data.clone(), &client, &tracker).await.unwrap();
+    assert_eq!(get_execution_by_id(&db, id).await.unwrap().unwrap().status, ExecutionStatus::Running);
+    assert_eq!(tracker.snapshot().updated, 1);
+
+    update_execution_status_worker(sync_job(), data, &client, &tracker).await.unwrap();
+    assert_eq!(get_execution_by_id(&db, id).await.unwrap().unwrap().status, ExecutionStatus::Completed);
+}
+
+#[tokio::test]
+async fn scheduler_skips_overlapping_passes() {
+    let db = setup_db().await;
+    let client = FakeTemporalClient::new();
+    let tracker = StatusTracker::new();
+    let id = insert_running_execution(&db, &client).await;
+    let execution = get_execution_by_id(&db, id).await.unwrap().unwrap();
+    client.set_status(&execution.workflow_id, WorkflowExecutionStatus::Completed);
+    let data = web::Data::new(Arc::new(db.clone()));
+
+    let pass = tracker.try_begin_pass().unwrap();
+    update_execution_status_worker(sync_job(), data.clone(), &client, &tracker).await.unwrap();
+    assert_eq!(get_execution_by_id(&db, id).await.unwrap().unwrap().status, ExecutionStatus::Running);
+    assert_eq!(tracker.snapshot().overlapping_passes, 1);
+
+    drop(pass);
+    update_execution_status_worker(sync_job(), data, &client, &tracker).await.unwrap();
+    assert_eq!(get_execution_by_id(&db, id).await.unwrap().unwrap().status, ExecutionStatus::Completed);
+}
@@ -0,0 +1,286 @@
+use std::{
+    collections::HashMap,
+    future,
+    net::{TcpListener, TcpStream},
+    ops::ControlFlow,
+    process::{Child, Command, Stdio},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use executor::{
+    activities::command::CommandPolicy,
+    helpers::{
+        child_store::MemoryChildStore,
+        client::{connect, StartOptions, TemporalClient},
+        clock::{FakeClock, SystemClock},
+        log_store::MemoryLogStore,
+        replay::replay_histories,
+        step_store::MemoryStepStore,
+    },
+    registry::{ActivityDeps, REPEAT_WORKFLOW},
+    versioning::PATCH_MARKER_NAME,
+    workers::build_worker,
+    workflows::{run_repeat_loop, RepeatInput, DURABLE_TIMERS_PATCH},
+};
+use temporal_client::TestService;
+use temporal_sdk_core::CoreRuntime;
+use temporal_sdk_core_api::telemetry::TelemetryOptionsBuilder;
+use temporal_sdk_core_protos::{
+    coresdk::AsJsonPayloadExt,
+    temporal::api::{
+        common::v1::{Payloads, WorkflowType},
+        enums::v1::{EventType, WorkflowExecutionStatus},
+        history::v1::{
+            history_event::Attributes, History, HistoryEvent, MarkerRecordedEventAttributes, TimerFiredEventAttributes,
+            TimerStartedEventAttributes, WorkflowExecutionCompletedEventAttributes, WorkflowExecutionStartedEventAttributes,
+            WorkflowTaskCompletedEventAttributes, WorkflowTaskScheduledEventAttributes, WorkflowTaskStartedEventAttributes,
+        },
+        testservice::v1::UnlockTimeSkippingRequest,
+    },
+};
+
+const ONE_HOUR: RepeatInput = RepeatInput {
+    interval_secs: 5,
+    total_secs: 3600,
+};
+
+#[tokio::test]
+async fn repeat_loop_runs_an_hour_on_a_fake_clock() {
+    let clock = FakeClock::new();
+    let mut ticks = 0;
+    let started = Instant::now();
+
+    let elapsed = run_repeat_loop(&clock, &ONE_HOUR, Duration::ZERO, future::pending(), |_| {
+        ticks += 1;
+        ControlFlow::Continue(())
+    })
+    .await;
+
+    assert_eq!(elapsed, Duration::from_secs(3600));
+    assert_eq!(clock.elapsed(), Duration::from_secs(3600));
+    assert_eq!(ticks, 720);
+    assert!(started.elapsed() < Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn repeat_loop_resumes_and_stops_on_break() {
+    let clock = FakeClock::new();
+
+    let elapsed = run_repeat_loop(&clock, &ONE_HOUR, Duration::from_secs(3000), future::pending(), |elapsed| {
+        if elapsed >= Duration::from_secs(3100) {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    })
+    .await;
+
+    assert_eq!(elapsed, Duration::from_secs(3100));
+    assert_eq!(clock.elapsed(), Duration::from_secs(100));
+}
+
+#[tokio::test]
+async fn repeat_loop_stops_mid_step_when_cancelled() {
+    let started = Instant::now();
+
+    // Annulation pendant la première attente d'une heure
+    let elapsed = run_repeat_loop(&SystemClock, &ONE_HOUR, Duration::ZERO, tokio::time::sleep(Duration::from_millis(50)), |_| {
+        panic!("No step should complete")
+    })
+    .await;
+
+    assert_eq!(elapsed, Duration::ZERO);
+    assert!(started.elapsed() < Duration::from_secs(1), "Cancellation waited for the end of the step");
+}
+
+fn event(event_id: i64, event_type: EventType, attributes: Attributes) -> HistoryEvent {
+    HistoryEvent {
+        event_id,
+        event_type: event_type as i32,
+        attributes: Some(attributes),
+        ..Default::default()
+    }
+}
+
+// Tâche de workflow complète (scheduled, started, completed) à partir de `event_id`
+fn workflow_task(event_id: i64) -> Vec<HistoryEvent> {
+    vec![
+        event(
+            event_id,
+            EventType::WorkflowTaskScheduled,
+            Attributes::WorkflowTaskScheduledEventAttributes(WorkflowTaskScheduledEventAttributes::default()),
+        ),
+        event(
+            event_id + 1,
+            EventType::WorkflowTaskStarted,
+            Attributes::WorkflowTaskStartedEventAttributes(WorkflowTaskStartedEventAttributes {
+                scheduled_event_id: event_id,
+                ..Default::default()
+            }),
+        ),
+        event(
+            event_id + 2,
+            EventType::WorkflowTaskCompleted,
+            Attributes::WorkflowTaskCompletedEventAttributes(WorkflowTaskCompletedEventAttributes {
+                scheduled_event_id: event_id,
+                started_event_id: event_id + 1,
+                ..Default::default()
+            }),
+        ),
+    ]
+}
+
+// Historique de repeat_workflow sur la branche des timers durables, un timer par pas
+fn durable_timers_history(input: &RepeatInput, timers: usize) -> History {
+    let mut events = vec![event(
+        1,
+        EventType::WorkflowExecutionStarted,
+        Attributes::WorkflowExecutionStartedEventAttributes(WorkflowExecutionStartedEventAttributes {
+            workflow_type: Some(WorkflowType { name: REPEAT_WORKFLOW.to_string() }),
+            input: Some(Payloads { payloads: vec![input.as_json_payload().unwrap()] }),
+            ..Default::default()
+        }),
+    )];
+    events.extend(workflow_task(2));
+    let patch = serde_json::json!({ "id": DURABLE_TIMERS_PATCH, "deprecated": false }).as_json_payload().unwrap();
+    events.push(event(
+        5,
+        EventType::MarkerRecorded,
+        Attributes::MarkerRecordedEventAttributes(MarkerRecordedEventAttributes {
+            marker_name: PATCH_MARKER_NAME.to_string(),
+            details: HashMap::from([("patch_data".to_string(), Payloads { payloads: vec![patch] })]),
+            workflow_task_completed_event_id: 4,
+            ..Default::default()
+        }),
+    ));
+
+    let mut task_completed_id = 4;
+    for timer in 1..=timers {
+        let started_id = events.len() as i64 + 1;
+        events.push(event(
+            started_id,
+            EventType::TimerStarted,
+            Attributes::TimerStartedEventAttributes(TimerStartedEventAttributes {
+                timer_id: timer.to_string(),
+                start_to_fire_timeout: Some(prost_wkt_types::Duration { seconds: input.interval_secs as i64, nanos: 0 }),
+                workflow_task_completed_event_id: task_completed_id,
+            }),
+        ));
+        events.push(event(
+            started_id + 1,
+            EventType::TimerFired,
+            Attributes::TimerFiredEventAttributes(TimerFiredEventAttributes {
+                timer_id: timer.to_string(),
+                started_event_id: started_id,
+            }),
+        ));
+        events.extend(workflow_task(started_id + 2));
+        task_completed_id = started_id + 4;
+    }
+    events.push(event(
+        task_completed_id + 1,
+        EventType::WorkflowExecutionCompleted,
+        Attributes::WorkflowExecutionCompletedEventAttributes(WorkflowExecutionCompletedEventAttributes {
+            workflow_task_completed_event_id: task_completed_id,
+            ..Default::default()
+        }),
+    ));
+    History { events }
+}
+
+// La branche des timers durables est exécutée par le worker de rejeu, sans serveur:
+// chaque pas doit correspondre à un timer de l'historique.
+#[tokio::test]
+async fn repeat_workflow_runs_one_durable_timer_per_step() {
+    let input = RepeatInput { interval_secs: 5, total_secs: 15 };
+
+    let history = durable_timers_history(&input, 3);
+    replay_histories(vec![("durable-timers".to_string(), history)])
+        .await
+        .expect("repeat_workflow should start one timer per step");
+
+    let history = durable_timers_history(&input, 4);
+    assert!(
+        replay_histories(vec![("durable-timers-extra".to_string(), history)]).await.is_err(),
+        "A fourth timer should not match the workflow code"
+    );
+}
+
+// Serveur de test Temporal avec saut de temps. Nécessite `temporal-test-server`
+// (ou TEMPORAL_TEST_SERVER):
+//     cargo test --test time_skipping -- --ignored
+#[tokio::test]
+#[ignore = "requires the Temporal time-skipping test server"]
+async fn repeat_workflow_skips_an_hour_of_timers() {
+    let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let exe = std::env::var("TEMPORAL_TEST_SERVER").unwrap_or_else(|_| "temporal-test-server".to_string());
+    let _server = KillOnDrop(
+        Command::new(exe)
+            .arg(port.to_string())
+            .stdout(Stdio::null())
+            .spawn()
+            .expect("Failed to start the Temporal test server"),
+    );
+    let deadline = Instant::now() + Duration::from_secs(30);
+    while TcpStream::connect(("127.0.0.1", port)).is_err() {
+        assert!(Instant::now() < deadline, "Test server never started");
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+
+    let client = connect(&format!("http://127.0.0.1:{}", port)).await.unwrap();
+    let runtime = CoreRuntime::new_assume_tokio(TelemetryOptionsBuilder::default().build().unwrap()).unwrap();
+    let deps = ActivityDeps {
+        clock: Arc::new(FakeClock::new()),
+        logs: Arc::new(MemoryLogStore::new()),
+        commands: Arc::new(CommandPolicy::default()),
+        http: reqwest::Client::new(),
+        steps: Arc::new(MemoryStepStore::new()),
+        children: Arc::new(MemoryChildStore::new()),
+    };
+    let mut worker = build_worker(&runtime, client.clone(), deps).unwrap();
+
+    let workflow_id = "time-skipping-repeat".to_string();
+    let run_id = client
+        .start("repeat_workflow".to_string(), workflow_id.clone(), vec![ONE_HOUR.as_json_payload().unwrap()], StartOptions::default())
+        .await
+        .unwrap();
+    client
+        .clone()
+        .unlock_time_skipping(UnlockTimeSkippingRequest::default())
+        .await
+        .unwrap();
+
+    let started = Instant::now();
+    let wait_for_completion = async {
+        loop {
+            let description = client.describe(workflow_id.clone(), Some(run_id.clone())).await.unwrap();
+            let status = description
+                .workflow_execution_info
+                .map(|info| info.status())
+                .unwrap_or_default();
+            if status != WorkflowExecutionStatus::Running {
+                return status;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    };
+
+    let status = tokio::select! {
+        status = wait_for_completion => status,
+        result = worker.run() => panic!("Worker stopped early: {:?}", result),
+    };
+
+    assert_eq!(status, WorkflowExecutionStatus::Completed);
+    assert!(started.elapsed() < Duration::from_secs(10), "Timers were not skipped");
+}
+
+// Tuer le serveur de test à la fin du test
+struct KillOnDrop(Child);
+
+impl Drop for KillOnDrop {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
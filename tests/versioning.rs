@@ -0,0 +1,177 @@
+use std::{collections::HashMap, sync::Arc};
+
+use actix_web::{test, web, App};
+use executor::{
+    controller,
+    executions::ExecutionStatus,
+    helpers::{
+        client::{SharedClient, StartOptions},
+        fake_client::FakeTemporalClient,
+        status_tracker::StatusTracker,
+    },
+    registry::{registry, REPEAT_WORKFLOW},
+    service::{create_execution, get_execution_by_id, init_workflow, new_workflow_id, ExecutionInput},
+    versioning::{ExecutionVersion, PatchStatus, PATCH_MARKER_NAME},
+    workers::{update_execution_status_worker, UpdateExecutionStatusJob},
+    workflows::DURABLE_TIMERS_PATCH,
+};
+use migration::{Migrator, MigratorTrait};
+use sea_orm::{Database, DatabaseConnection};
+use serde_json::{json, Value};
+use temporal_sdk_core_protos::{
+    coresdk::AsJsonPayloadExt,
+    temporal::api::{
+        common::v1::{Payloads, WorkerVersionStamp, WorkflowType},
+        history::v1::{
+            history_event::Attributes, History, HistoryEvent, MarkerRecordedEventAttributes,
+            WorkflowExecutionStartedEventAttributes, WorkflowTaskCompletedEventAttributes,
+        },
+    },
+};
+use uuid::Uuid;
+
+async fn setup_db() -> DatabaseConnection {
+    let db = Database::connect("sqlite::memory:").await.expect("Failed to open in-memory database");
+    Migrator::up(&db, None).await.expect("Failed to run migrations");
+    db
+}
+
+async fn insert_running_execution(db: &DatabaseConnection, client: &FakeTemporalClient) -> Uuid {
+    let (workflow_id, run_id) = init_workflow(client, new_workflow_id(), StartOptions::default()).await.unwrap();
+    let id = Uuid::new_v4();
+    create_execution(db, ExecutionInput { id, workflow_id, run_id, status: ExecutionStatus::Running, schedule_id: None, start_at: None, previous_run_ids: vec![], workflow_type: REPEAT_WORKFLOW.to_string(), input: None, parent_id: None })
+        .await
+        .unwrap();
+    id
+}
+
+fn event(event_id: i64, attributes: Attributes) -> HistoryEvent {
+    HistoryEvent {
+        event_id,
+        attributes: Some(attributes),
+        ..Default::default()
+    }
+}
+
+fn task_completed(event_id: i64, build_id: &str) -> HistoryEvent {
+    event(
+        event_id,
+        Attributes::WorkflowTaskCompletedEventAttributes(WorkflowTaskCompletedEventAttributes {
+            worker_version: Some(WorkerVersionStamp {
+                build_id: build_id.to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }),
+    )
+}
+
+fn patch_marker(event_id: i64, patch_id: &str) -> HistoryEvent {
+    let data = json!({ "id": patch_id, "deprecated": false }).as_json_payload().unwrap();
+    event(
+        event_id,
+        Attributes::MarkerRecordedEventAttributes(MarkerRecordedEventAttributes {
+            marker_name: PATCH_MARKER_NAME.to_string(),
+            details: HashMap::from([("patch_data".to_string(), Payloads { payloads: vec![data] })]),
+            ..Default::default()
+        }),
+    )
+}
+
+// repeat_workflow démarré sur executor@0.1.0, poursuivi sur executor@0.2.0
+fn patched_history() -> History {
+    History {
+        events: vec![
+            event(
+                1,
+                Attributes::WorkflowExecutionStartedEventAttributes(WorkflowExecutionStartedEventAttributes {
+                    workflow_type: Some(WorkflowType { name: REPEAT_WORKFLOW.to_string() }),
+                    ..Default::default()
+                }),
+            ),
+            task_completed(4, "executor@0.1.0"),
+            patch_marker(5, DURABLE_TIMERS_PATCH),
+            task_completed(9, "executor@0.1.0"),
+            task_completed(13, "executor@0.2.0"),
+        ],
+    }
+}
+
+#[test]
+fn version_is_read_from_history() {
+    let version = ExecutionVersion::from_history(&patched_history());
+
+    assert_eq!(version.workflow_type.as_deref(), Some(REPEAT_WORKFLOW));
+    assert_eq!(version.patches, vec![DURABLE_TIMERS_PATCH.to_string()]);
+    assert_eq!(version.build_ids, vec!["executor@0.1.0".to_string(), "executor@0.2.0".to_string()]);
+    assert_eq!(version.build_id(), Some("executor@0.2.0"));
+    assert_eq!(ExecutionVersion::from_history(&History::default()), ExecutionVersion::default());
+}
+
+#[test]
+fn registry_declares_unique_patches() {
+    for workflow in registry().workflows() {
+        for (position, patch) in workflow.patches.iter().enumerate() {
+            assert!(
+                !workflow.patches[..position].iter().any(|other| other.id == patch.id),
+                "{} declares patch {} twice",
+                workflow.name,
+                patch.id
+            );
+        }
+    }
+    let repeat = registry().workflow(REPEAT_WORKFLOW).unwrap();
+    assert_eq!(repeat.patches[0].id, DURABLE_TIMERS_PATCH);
+    assert_eq!(repeat.patches[0].status, PatchStatus::Active);
+}
+
+#[tokio::test]
+async fn scheduler_records_worker_build_id() {
+    let db = setup_db().await;
+    let client = FakeTemporalClient::new();
+    let id = insert_running_execution(&db, &client).await;
+    let execution = get_execution_by_id(&db, id).await.unwrap().unwrap();
+    let job = UpdateExecutionStatusJob { resync_after: std::time::Duration::ZERO, ..Default::default() };
+
+    // Aucune tâche traitée: pas de build ID
+    update_execution_status_worker(job.clone(), web::Data::new(Arc::new(db.clone())), &client, &StatusTracker::new())
+        .await
+        .unwrap();
+    assert_eq!(get_execution_by_id(&db, id).await.unwrap().unwrap().build_id, None);
+
+    client.set_build_id(&execution.workflow_id, "executor@0.2.0");
+    update_execution_status_worker(job, web::Data::new(Arc::new(db.clone())), &client, &StatusTracker::new())
+        .await
+        .unwrap();
+    assert_eq!(get_execution_by_id(&db, id).await.unwrap().unwrap().build_id.as_deref(), Some("executor@0.2.0"));
+}
+
+#[actix_web::test]
+async fn version_endpoint_reads_current_run_history() {
+    let db = setup_db().await;
+    let fake = Arc::new(FakeTemporalClient::new());
+    let id = insert_running_execution(&db, &fake).await;
+    let execution = get_execution_by_id(&db, id).await.unwrap().unwrap();
+    fake.set_history(&execution.workflow_id, patched_history());
+
+    let client: SharedClient = fake.clone();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.clone()))
+            .app_data(web::Data::new(client))
+            .configure(controller::configure),
+    )
+    .await;
+
+    let uri = format!("/executions/{}/version", id);
+    let response = test::call_service(&app, test::TestRequest::get().uri(&uri).to_request()).await;
+    assert!(response.status().is_success());
+    let body: Value = test::read_body_json(response).await;
+    assert_eq!(body["workflow_type"], REPEAT_WORKFLOW);
+    assert_eq!(body["patches"], json!([DURABLE_TIMERS_PATCH]));
+    assert_eq!(body["build_ids"], json!(["executor@0.1.0", "executor@0.2.0"]));
+
+    let uri = format!("/executions/{}/version", Uuid::new_v4());
+    let response = test::call_service(&app, test::TestRequest::get().uri(&uri).to_request()).await;
+    assert_eq!(response.status(), 404);
+}
@@ -0,0 +1,147 @@
+use prost_wkt_types::{Duration, Timestamp};
+use executor::{
+    helpers::{client::StartOptions, fake_client::FakeTemporalClient, workflow_state::repeat_state_from_history},
+    service::{get_execution_state, init_workflow, new_workflow_id, StateSource},
+    workflows::{ExtendSignal, ParametersUpdate, RepeatInput, RepeatPhase, RepeatState},
+};
+use temporal_sdk_core_protos::{
+    coresdk::AsJsonPayloadExt,
+    temporal::api::{
+        common::v1::Payloads,
+        history::v1::{
+            history_event::Attributes, History, HistoryEvent, TimerCanceledEventAttributes, TimerFiredEventAttributes,
+            TimerStartedEventAttributes, WorkflowExecutionSignaledEventAttributes, WorkflowExecutionStartedEventAttributes,
+        },
+    },
+};
+
+fn event(event_id: i64, seconds: i64, attributes: Attributes) -> HistoryEvent {
+    HistoryEvent {
+        event_id,
+        event_time: Some(Timestamp { seconds, nanos: 0 }),
+        attributes: Some(attributes),
+        ..Default::default()
+    }
+}
+
+fn timer_started(event_id: i64, seconds: i64, timer_id: &str) -> HistoryEvent {
+    event(
+        event_id,
+        seconds,
+        Attributes::TimerStartedEventAttributes(TimerStartedEventAttributes {
+            timer_id: timer_id.to_string(),
+            start_to_fire_timeout: Some(Duration { seconds: 5, nanos: 0 }),
+            ..Default::default()
+        }),
+    )
+}
+
+fn signaled(event_id: i64, seconds: i64, signal_name: &str, input: Option<Payloads>) -> HistoryEvent {
+    event(
+        event_id,
+        seconds,
+        Attributes::WorkflowExecutionSignaledEventAttributes(WorkflowExecutionSignaledEventAttributes {
+            signal_name: signal_name.to_string(),
+            input,
+            ..Default::default()
+        }),
+    )
+}
+
+#[test]
+fn state_is_rebuilt_from_timers_and_signals() {
+    let input = RepeatInput { interval_secs: 5, total_secs: 60 };
+    let extend = ExtendSignal { seconds: 30 };
+    let history = History {
+        events: vec![
+            event(
+                1,
+                0,
+                Attributes::WorkflowExecutionStartedEventAttributes(WorkflowExecutionStartedEventAttributes {
+                    input: Some(Payloads { payloads: vec![input.as_json_payload().unwrap()] }),
+                    ..Default::default()
+                }),
+            ),
+            timer_started(5, 0, "1"),
+            event(6, 5, Attributes::TimerFiredEventAttributes(TimerFiredEventAttributes { timer_id: "1".to_string(), started_event_id: 5 })),
+            timer_started(10, 5, "2"),
+            signaled(11, 7, "extend", Some(Payloads { payloads: vec![extend.as_json_payload().unwrap()] })),
+            signaled(15, 8, "pause", None),
+            event(
+                18,
+                8,
+                Attributes::TimerCanceledEventAttributes(TimerCanceledEventAttributes {
+                    timer_id: "2".to_string(),
+                    started_event_id: 10,
+                    ..Default::default()
+                }),
+            ),
+        ],
+    };
+
+    let state = repeat_state_from_history(&history);
+
+    assert_eq!(state.phase, RepeatPhase::Paused);
+    assert_eq!(state.parameters, input);
+    assert_eq!(state.total_secs, 90);
+    assert_eq!(state.elapsed_secs, 8);
+    assert_eq!(state.signals_received, vec!["extend", "pause"]);
+}
+
+#[test]
+fn huge_extend_signals_saturate_the_total_duration() {
+    let extend = ExtendSignal { seconds: u64::MAX };
+    let payloads = || Some(Payloads { payloads: vec![extend.as_json_payload().unwrap()] });
+    let history = History {
+        events: vec![
+            event(
+                1,
+                0,
+                Attributes::WorkflowExecutionStartedEventAttributes(WorkflowExecutionStartedEventAttributes {
+                    input: Some(Payloads { payloads: vec![RepeatInput::default().as_json_payload().unwrap()] }),
+                    ..Default::default()
+                }),
+            ),
+            signaled(5, 1, "extend", payloads()),
+            signaled(6, 2, "extend", payloads()),
+        ],
+    };
+
+    let state = repeat_state_from_history(&history);
+    assert_eq!(state.total_secs, u64::MAX);
+
+    let mut state = RepeatState::new(RepeatInput::default());
+    state.extend(std::time::Duration::from_secs(u64::MAX));
+    state.extend(std::time::Duration::from_secs(u64::MAX));
+    assert_eq!(state.total_secs, u64::MAX);
+    assert!(!state.is_done());
+}
+
+#[tokio::test]
+async fn state_is_rebuilt_from_history() {
+    let client = FakeTemporalClient::new();
+    let (workflow_id, run_id) = init_workflow(&client, new_workflow_id(), StartOptions::default()).await.unwrap();
+
+    let state = get_execution_state(&client, workflow_id, run_id).await.unwrap();
+
+    assert_eq!(state.source, StateSource::History);
+    assert_eq!(state.state.phase, RepeatPhase::Running);
+}
+
+#[test]
+fn parameters_update_cannot_shrink_below_elapsed_time() {
+    let mut state = RepeatState::new(RepeatInput { interval_secs: 5, total_secs: 60 });
+    state.advance(std::time::Duration::from_secs(30));
+
+    let shrink = ParametersUpdate { total_secs: Some(20), ..Default::default() };
+    assert!(state.validate_update(&shrink).is_err());
+    let zero_interval = ParametersUpdate { interval_secs: Some(0), ..Default::default() };
+    assert!(state.validate_update(&zero_interval).is_err());
+
+    let update = ParametersUpdate { interval_secs: Some(10), total_secs: Some(40) };
+    assert!(state.validate_update(&update).is_ok());
+    state.apply_update(&update);
+    assert_eq!(state.interval_secs, 10);
+    assert_eq!(state.total_secs, 40);
+    assert_eq!(state.progress, 75.0);
+}